@@ -0,0 +1,107 @@
+use bevy::{prelude::*, utils::Instant, window::PrimaryWindow};
+use bevy_egui::{async_tessellation::EguiAsyncTessellation, prelude::*};
+
+const ROWS: usize = 120;
+const COLUMNS: usize = 16;
+
+/// Main thread time spent in [`EguiSet::ProcessOutput`], averaged over the last frames.
+#[derive(Resource, Default)]
+struct ProcessOutputTime {
+    started: Option<Instant>,
+    average_ms: f64,
+}
+
+/// This example shows a heavy table, and compares the main thread time spent ending the pass
+/// and tessellating it with and without [`EguiAsyncTessellation`] (toggled with the checkbox).
+fn main() {
+    App::new()
+        .add_plugins(DefaultPlugins)
+        .add_plugins(EguiPlugin::default())
+        .init_resource::<ProcessOutputTime>()
+        .add_systems(
+            PostUpdate,
+            (
+                start_timer_system.before(EguiSet::ProcessOutput),
+                stop_timer_system.after(EguiSet::ProcessOutput),
+            ),
+        )
+        .add_systems(Update, table_system)
+        .run();
+}
+
+fn start_timer_system(mut process_output_time: ResMut<ProcessOutputTime>) {
+    process_output_time.started = Some(Instant::now());
+}
+
+fn stop_timer_system(mut process_output_time: ResMut<ProcessOutputTime>) {
+    let Some(started) = process_output_time.started.take() else {
+        return;
+    };
+    let elapsed_ms = started.elapsed().as_secs_f64() * 1000.0;
+    process_output_time.average_ms = process_output_time.average_ms * 0.95 + elapsed_ms * 0.05;
+}
+
+fn table_system(
+    mut commands: Commands,
+    mut contexts: EguiContexts,
+    process_output_time: Res<ProcessOutputTime>,
+    primary_window: Query<(Entity, Has<EguiAsyncTessellation>), With<PrimaryWindow>>,
+    time: Res<Time>,
+) {
+    let Ok((window, async_tessellation)) = primary_window.get_single() else {
+        return;
+    };
+    let seconds = time.elapsed_seconds();
+    egui::CentralPanel::default().show(contexts.ctx_mut(), |ui| {
+        let mut enabled = async_tessellation;
+        ui.horizontal(|ui| {
+            ui.checkbox(&mut enabled, "Tessellate on the AsyncComputeTaskPool");
+            ui.label(format!(
+                "ProcessOutput: {:.2} ms",
+                process_output_time.average_ms
+            ));
+        });
+        if enabled != async_tessellation {
+            if enabled {
+                commands
+                    .entity(window)
+                    .insert(EguiAsyncTessellation::default());
+            } else {
+                commands.entity(window).remove::<EguiAsyncTessellation>();
+            }
+        }
+        ui.separator();
+
+        egui::ScrollArea::both().show(ui, |ui| {
+            egui::Grid::new("table").striped(true).show(ui, |ui| {
+                for row in 0..ROWS {
+                    for column in 0..COLUMNS {
+                        let value = ((row * COLUMNS + column) as f32 + seconds).sin() * 1000.0;
+                        let color = if value < 0.0 {
+                            egui::Color32::LIGHT_RED
+                        } else {
+                            egui::Color32::LIGHT_GREEN
+                        };
+                        let (rect, _) =
+                            ui.allocate_exact_size(egui::vec2(64.0, 16.0), egui::Sense::hover());
+                        let painter = ui.painter();
+                        painter.rect_stroke(rect, 2.0, (1.0, color));
+                        painter.circle_filled(
+                            rect.left_center() + egui::vec2(8.0, 0.0),
+                            4.0,
+                            color,
+                        );
+                        painter.text(
+                            rect.right_center(),
+                            egui::Align2::RIGHT_CENTER,
+                            format!("{value:.1}"),
+                            egui::FontId::monospace(10.0),
+                            ui.visuals().text_color(),
+                        );
+                    }
+                    ui.end_row();
+                }
+            });
+        });
+    });
+}
@@ -0,0 +1,113 @@
+use bevy::{prelude::*, render::camera::RenderTarget};
+use bevy_egui::{pane::EguiPaneViewport, prelude::*};
+
+/// This example shows two cameras in resizable Egui panes. Images the cameras render to get resized
+/// together with the panes, and hovering a pane casts a ray from its camera.
+fn main() {
+    App::new()
+        .add_plugins(DefaultPlugins)
+        .add_plugins(EguiPlugin::default())
+        .add_systems(Startup, setup_system)
+        .add_systems(Update, ui_system)
+        .run();
+}
+
+#[derive(Component)]
+struct SidePane;
+
+#[derive(Component)]
+struct CentralPane;
+
+fn setup_system(
+    mut commands: Commands,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut materials: ResMut<Assets<StandardMaterial>>,
+    mut images: ResMut<Assets<Image>>,
+    mut contexts: EguiContexts,
+) {
+    commands.spawn(PbrBundle {
+        mesh: meshes.add(Cuboid::new(2.0, 2.0, 2.0)),
+        material: materials.add(Color::rgb(0.8, 0.7, 0.6)),
+        ..default()
+    });
+    commands.spawn(PbrBundle {
+        mesh: meshes.add(Plane3d::default().mesh().size(10.0, 10.0)),
+        material: materials.add(Color::rgb(0.3, 0.5, 0.3)),
+        transform: Transform::from_xyz(0.0, -1.0, 0.0),
+        ..default()
+    });
+    commands.spawn(PointLightBundle {
+        point_light: PointLight {
+            shadows_enabled: true,
+            ..default()
+        },
+        transform: Transform::from_xyz(4.0, 8.0, 4.0),
+        ..default()
+    });
+
+    // The window itself only shows the UI.
+    commands.spawn(Camera2dBundle::default());
+
+    let mut spawn_pane = |transform: Transform, order: isize| {
+        let handle = images.add(EguiPaneViewport::new_image(UVec2::new(512, 512)));
+        let texture_id = contexts.add_image(handle.clone_weak());
+        let camera = commands
+            .spawn(Camera3dBundle {
+                camera: Camera {
+                    order,
+                    target: RenderTarget::Image(handle.clone()),
+                    ..default()
+                },
+                transform,
+                ..default()
+            })
+            .id();
+        EguiPaneViewport::new(camera, texture_id, handle)
+    };
+    let side_pane = spawn_pane(
+        Transform::from_xyz(0.0, 10.0, 0.01).looking_at(Vec3::ZERO, Vec3::Y),
+        -2,
+    );
+    let central_pane = spawn_pane(
+        Transform::from_xyz(-4.0, 3.0, 6.0).looking_at(Vec3::ZERO, Vec3::Y),
+        -1,
+    );
+    commands.spawn((side_pane, SidePane));
+    commands.spawn((central_pane, CentralPane));
+}
+
+fn ui_system(
+    mut contexts: EguiContexts,
+    mut side_pane: Query<&mut EguiPaneViewport, (With<SidePane>, Without<CentralPane>)>,
+    mut central_pane: Query<&mut EguiPaneViewport, With<CentralPane>>,
+    cameras: Query<(&Camera, &GlobalTransform)>,
+) {
+    let mut side_pane = side_pane.single_mut();
+    let mut central_pane = central_pane.single_mut();
+    let ctx = contexts.ctx_mut();
+
+    egui::TopBottomPanel::bottom("status_bar").show(ctx, |ui| {
+        let hovered_ray = [&*side_pane, &*central_pane].into_iter().find_map(|pane| {
+            let (camera, camera_transform) = cameras.get(pane.camera).ok()?;
+            pane.pointer_ray(camera, camera_transform)
+        });
+        match hovered_ray {
+            Some(ray) => ui.label(format!(
+                "Pointer ray: origin {:.2}, direction {:.2}",
+                ray.origin, *ray.direction
+            )),
+            None => ui.label("Hover a pane to cast a ray"),
+        };
+    });
+    egui::SidePanel::left("side_pane")
+        .resizable(true)
+        .default_width(300.0)
+        .show(ctx, |ui| {
+            ui.heading("Top view");
+            side_pane.show(ui);
+        });
+    egui::CentralPanel::default().show(ctx, |ui| {
+        ui.heading("Perspective view");
+        central_pane.show(ui);
+    });
+}
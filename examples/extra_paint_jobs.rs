@@ -0,0 +1,58 @@
+use bevy::{prelude::*, window::PrimaryWindow};
+use bevy_egui::{prelude::*, EguiExtraPaintJobs};
+
+/// This example paints a mesh built outside of Egui (a sine wave) on top of the Egui shapes.
+fn main() {
+    App::new()
+        .add_plugins(DefaultPlugins)
+        .add_plugins(EguiPlugin::default())
+        .add_systems(Startup, setup_system)
+        .add_systems(Update, (ui_example_system, wave_system))
+        .run();
+}
+
+fn setup_system(mut commands: Commands, windows: Query<Entity, With<PrimaryWindow>>) {
+    commands
+        .entity(windows.single())
+        .insert(EguiExtraPaintJobs::default());
+}
+
+fn ui_example_system(mut contexts: EguiContexts) {
+    egui::Window::new("Hello").show(contexts.ctx_mut(), |ui| {
+        ui.label("The wave is painted above this window");
+    });
+}
+
+fn wave_system(
+    time: Res<Time>,
+    mut contexts: EguiContexts,
+    mut extra_paint_jobs: Query<&mut EguiExtraPaintJobs, With<PrimaryWindow>>,
+) {
+    let Ok(mut extra_paint_jobs) = extra_paint_jobs.get_single_mut() else {
+        return;
+    };
+    let screen_rect = contexts.ctx_mut().screen_rect();
+    let clip_rect = screen_rect.shrink(20.0);
+
+    // A strip of quads, each one a vertical bar up to the wave.
+    let mut mesh = egui::epaint::Mesh::default();
+    let bars = 100;
+    let bar_width = clip_rect.width() / bars as f32;
+    for i in 0..bars {
+        let x = clip_rect.left() + i as f32 * bar_width;
+        let phase = i as f32 * 0.1 + time.elapsed_seconds() * 2.0;
+        let height = (phase.sin() * 0.5 + 0.5) * clip_rect.height() * 0.5;
+        let rect = egui::Rect::from_min_max(
+            egui::pos2(x, clip_rect.bottom() - height),
+            egui::pos2(x + bar_width * 0.8, clip_rect.bottom()),
+        );
+        mesh.add_colored_rect(
+            rect,
+            egui::Color32::from_rgba_unmultiplied(80, 160, 255, 160),
+        );
+    }
+    extra_paint_jobs.primitives.push(egui::ClippedPrimitive {
+        clip_rect,
+        primitive: egui::epaint::Primitive::Mesh(mesh),
+    });
+}
@@ -0,0 +1,66 @@
+use bevy::{
+    diagnostic::{FrameTimeDiagnosticsPlugin, LogDiagnosticsPlugin},
+    prelude::*,
+    render::camera::RenderTarget,
+    window::{PresentMode, WindowRef, WindowResolution},
+};
+use bevy_egui::prelude::*;
+
+const EXTRA_WINDOWS: usize = 3;
+const LABELS_PER_WINDOW: usize = 3000;
+
+/// This example renders a lot of widgets in several windows and logs frame times.
+///
+/// Egui contexts of different windows are tessellated in parallel, so adding windows with heavy
+/// UIs increases frame times less than running them one after another would.
+fn main() {
+    App::new()
+        .add_plugins(DefaultPlugins.set(WindowPlugin {
+            primary_window: Some(Window {
+                present_mode: PresentMode::AutoNoVsync,
+                ..default()
+            }),
+            ..default()
+        }))
+        .add_plugins((FrameTimeDiagnosticsPlugin, LogDiagnosticsPlugin::default()))
+        .add_plugins(EguiPlugin::default())
+        .add_systems(Startup, create_windows_system)
+        .add_systems(Update, ui_system)
+        .run();
+}
+
+fn create_windows_system(mut commands: Commands) {
+    commands.spawn(Camera2dBundle::default());
+    for i in 0..EXTRA_WINDOWS {
+        let window = commands
+            .spawn(Window {
+                title: format!("Window {}", i + 1),
+                resolution: WindowResolution::new(640.0, 480.0),
+                present_mode: PresentMode::AutoNoVsync,
+                ..default()
+            })
+            .id();
+        commands.spawn(Camera2dBundle {
+            camera: Camera {
+                target: RenderTarget::Window(WindowRef::Entity(window)),
+                ..default()
+            },
+            ..default()
+        });
+    }
+}
+
+fn ui_system(mut contexts: Query<&mut EguiContext, With<Window>>, time: Res<Time>) {
+    for mut ctx in contexts.iter_mut() {
+        egui::CentralPanel::default().show(ctx.get_mut(), |ui| {
+            // Not using `ScrollArea::show_rows` on purpose, so that all the labels get tessellated.
+            egui::ScrollArea::vertical().show(ui, |ui| {
+                ui.horizontal_wrapped(|ui| {
+                    for i in 0..LABELS_PER_WINDOW {
+                        ui.label(format!("{i}: {:.2}", time.elapsed_seconds() + i as f32));
+                    }
+                });
+            });
+        });
+    }
+}
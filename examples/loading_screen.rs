@@ -0,0 +1,75 @@
+use bevy::{prelude::*, window::PrimaryWindow};
+use bevy_egui::{prelude::*, EguiPrewarm};
+
+#[derive(States, Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+enum GameState {
+    #[default]
+    Loading,
+    Playing,
+}
+
+#[derive(Resource)]
+struct LoadingTimer(Timer);
+
+#[derive(Resource, Default)]
+struct Paused(bool);
+
+/// This example rasterizes the glyphs of the pause menu while a (fake) loading screen is shown,
+/// so that opening the menu for the first time (with Escape) doesn't hitch.
+fn main() {
+    App::new()
+        .add_plugins(DefaultPlugins)
+        .add_plugins(EguiPlugin::default())
+        .init_state::<GameState>()
+        .init_resource::<Paused>()
+        .insert_resource(LoadingTimer(Timer::from_seconds(2.0, TimerMode::Once)))
+        .add_systems(OnEnter(GameState::Loading), prewarm_system)
+        .add_systems(Update, loading_system.run_if(in_state(GameState::Loading)))
+        .add_systems(
+            Update,
+            pause_menu_system.run_if(in_state(GameState::Playing)),
+        )
+        .run();
+}
+
+fn prewarm_system(mut commands: Commands, primary_window: Query<Entity, With<PrimaryWindow>>) {
+    let Ok(window) = primary_window.get_single() else {
+        return;
+    };
+    // The texts share the glyphs of the pause menu, at the sizes it uses.
+    commands.entity(window).insert(EguiPrewarm {
+        texts: vec![EguiPrewarm::ASCII.to_owned()],
+        font_ids: vec![egui::FontId::proportional(32.0)],
+    });
+}
+
+fn loading_system(
+    time: Res<Time>,
+    mut timer: ResMut<LoadingTimer>,
+    mut next_state: ResMut<NextState<GameState>>,
+) {
+    if timer.0.tick(time.delta()).finished() {
+        next_state.set(GameState::Playing);
+    }
+}
+
+fn pause_menu_system(
+    mut contexts: EguiContexts,
+    keyboard_input: Res<ButtonInput<KeyCode>>,
+    mut paused: ResMut<Paused>,
+) {
+    if keyboard_input.just_pressed(KeyCode::Escape) {
+        paused.0 = !paused.0;
+    }
+    if !paused.0 {
+        return;
+    }
+    egui::CentralPanel::default().show(contexts.ctx_mut(), |ui| {
+        ui.vertical_centered(|ui| {
+            ui.label(egui::RichText::new("Paused").size(32.0));
+            ui.label("Press Escape to resume");
+            ui.add_space(16.0);
+            ui.label("Master volume, music volume, sound effects, field of view, sensitivity");
+        });
+    });
+}
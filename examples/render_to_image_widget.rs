@@ -8,12 +8,12 @@ use bevy::{
         view::RenderLayers,
     },
 };
-use bevy_egui::{egui::Widget, EguiContexts, EguiPlugin, EguiUserTextures};
+use bevy_egui::{egui::Widget, prelude::*};
 
 fn main() {
     App::new()
         .add_plugins(DefaultPlugins)
-        .add_plugins(EguiPlugin)
+        .add_plugins(EguiPlugin::default())
         .add_systems(Startup, setup)
         .add_systems(Update, rotator_system)
         .add_systems(Update, render_to_image_example_system)
@@ -0,0 +1,84 @@
+use bevy::{prelude::*, window::PrimaryWindow};
+use bevy_egui::{prelude::*, safe_area::EguiSafeArea};
+
+/// This example simulates a phone notch: press 'R' to "rotate" the device, which moves the notch
+/// from the top edge to the left one. Panels are laid out within the safe area.
+fn main() {
+    App::new()
+        .add_plugins(DefaultPlugins)
+        .add_plugins(EguiPlugin::default())
+        .add_systems(Update, rotate_system)
+        .add_systems(Update, ui_example_system)
+        .run();
+}
+
+const NOTCH_SIZE: f32 = 40.0;
+const HOME_INDICATOR_SIZE: f32 = 20.0;
+
+fn rotate_system(
+    keyboard_input: Res<ButtonInput<KeyCode>>,
+    mut landscape: Local<bool>,
+    mut safe_areas: Query<&mut EguiSafeArea, With<PrimaryWindow>>,
+) {
+    if keyboard_input.just_pressed(KeyCode::KeyR) {
+        *landscape = !*landscape;
+    }
+    let Ok(mut safe_area) = safe_areas.get_single_mut() else {
+        return;
+    };
+    let new_safe_area = if *landscape {
+        EguiSafeArea {
+            left: NOTCH_SIZE,
+            bottom: HOME_INDICATOR_SIZE,
+            ..default()
+        }
+    } else {
+        EguiSafeArea {
+            top: NOTCH_SIZE,
+            bottom: HOME_INDICATOR_SIZE,
+            ..default()
+        }
+    };
+    safe_area.set_if_neq(new_safe_area);
+}
+
+fn ui_example_system(
+    mut contexts: EguiContexts,
+    safe_areas: Query<&EguiSafeArea, With<PrimaryWindow>>,
+) {
+    let ctx = contexts.ctx_mut();
+
+    // Paint the simulated notch and home indicator behind the UI.
+    if let Ok(safe_area) = safe_areas.get_single() {
+        let screen_rect = ctx.screen_rect();
+        let window_rect = egui::Rect::from_min_max(
+            egui::pos2(0.0, 0.0),
+            screen_rect.max + egui::vec2(safe_area.right, safe_area.bottom),
+        );
+        let painter = ctx.layer_painter(egui::LayerId::background());
+        let notch_color = egui::Color32::from_rgb(120, 30, 30);
+        if safe_area.top > 0.0 {
+            painter.rect_filled(window_rect.with_max_y(safe_area.top), 0.0, notch_color);
+        }
+        if safe_area.left > 0.0 {
+            painter.rect_filled(window_rect.with_max_x(safe_area.left), 0.0, notch_color);
+        }
+        if safe_area.bottom > 0.0 {
+            painter.rect_filled(
+                window_rect.with_min_y(window_rect.max.y - safe_area.bottom),
+                0.0,
+                egui::Color32::from_rgb(30, 30, 120),
+            );
+        }
+    }
+
+    egui::TopBottomPanel::top("top_panel").show(ctx, |ui| {
+        ui.label("This panel stays below the notch.");
+    });
+    egui::SidePanel::left("side_panel").show(ctx, |ui| {
+        ui.label("Press 'R' to rotate.");
+    });
+    egui::CentralPanel::default().show(ctx, |ui| {
+        ui.label(format!("Screen rect: {:?}", ui.ctx().screen_rect()));
+    });
+}
@@ -0,0 +1,43 @@
+use bevy::prelude::*;
+use bevy_egui::{
+    prelude::*,
+    toast::{EguiToastPlugin, EguiToasts, Toast, ToastLevel},
+};
+
+/// This example queues toast notifications from a window's buttons and from a keyboard shortcut.
+fn main() {
+    App::new()
+        .add_plugins(DefaultPlugins)
+        .add_plugins(EguiPlugin::default())
+        .add_plugins(EguiToastPlugin)
+        .add_systems(Update, (save_shortcut_system, ui_example_system))
+        .run();
+}
+
+fn save_shortcut_system(keyboard_input: Res<ButtonInput<KeyCode>>, mut toasts: ResMut<EguiToasts>) {
+    if keyboard_input.just_pressed(KeyCode::KeyS) {
+        toasts.push(Toast {
+            level: ToastLevel::Success,
+            ..Toast::new("Saved!")
+        });
+    }
+}
+
+fn ui_example_system(mut contexts: EguiContexts, mut toasts: ResMut<EguiToasts>) {
+    egui::Window::new("Toasts").show(contexts.ctx_mut(), |ui| {
+        ui.label("Press 'S' to save");
+        if ui.button("Info").clicked() {
+            toasts.push(Toast::new("Something happened"));
+        }
+        if ui.button("Error").clicked() {
+            toasts.push(Toast {
+                level: ToastLevel::Error,
+                duration: 5.0,
+                ..Toast::new("Something went wrong")
+            });
+        }
+        if ui.button("Dismiss all").clicked() {
+            toasts.clear();
+        }
+    });
+}
@@ -0,0 +1,42 @@
+use bevy::{app::AppExit, prelude::*, window::CompositeAlphaMode};
+use bevy_egui::prelude::*;
+
+/// This example shows an Egui window floating over the desktop, in a transparent and undecorated
+/// Bevy window.
+///
+/// Windows without a camera are cleared with opaque black, so a camera clears the window with
+/// a transparent color. On macOS, the antialiased edges of the Egui window come out slightly
+/// darker (see `EguiWindowAlphaMode`).
+fn main() {
+    App::new()
+        .insert_resource(ClearColor(Color::NONE))
+        .add_plugins(DefaultPlugins.set(WindowPlugin {
+            primary_window: Some(Window {
+                transparent: true,
+                decorations: false,
+                #[cfg(target_os = "macos")]
+                composite_alpha_mode: CompositeAlphaMode::PostMultiplied,
+                #[cfg(not(target_os = "macos"))]
+                composite_alpha_mode: CompositeAlphaMode::PreMultiplied,
+                ..default()
+            }),
+            ..default()
+        }))
+        .add_plugins(EguiPlugin::default())
+        .add_systems(Startup, setup_camera_system)
+        .add_systems(Update, ui_example_system)
+        .run();
+}
+
+fn setup_camera_system(mut commands: Commands) {
+    commands.spawn(Camera2dBundle::default());
+}
+
+fn ui_example_system(mut contexts: EguiContexts, mut exit: EventWriter<AppExit>) {
+    egui::Window::new("Desktop widget").show(contexts.ctx_mut(), |ui| {
+        ui.label("Drag me around, the rest of the window is see-through.");
+        if ui.button("Quit").clicked() {
+            exit.send(AppExit);
+        }
+    });
+}
@@ -1,5 +1,8 @@
-use bevy::{prelude::*, window::PrimaryWindow};
-use bevy_egui::{EguiContexts, EguiPlugin, EguiSettings};
+use bevy::{prelude::*, render::texture::ImageSamplerDescriptor, window::PrimaryWindow};
+use bevy_egui::{
+    prelude::*,
+    theme::{EguiTheme, EguiThemeHandle},
+};
 
 struct Images {
     bevy_icon: Handle<Image>,
@@ -16,15 +19,34 @@ impl FromWorld for Images {
     }
 }
 
+#[derive(Resource)]
+struct Themes {
+    default: Handle<EguiTheme>,
+    light: Handle<EguiTheme>,
+}
+
+impl FromWorld for Themes {
+    fn from_world(world: &mut World) -> Self {
+        let asset_server = world.get_resource_mut::<AssetServer>().unwrap();
+        Self {
+            default: asset_server.load("themes/default.egui-theme.ron"),
+            light: asset_server.load("themes/light.egui-theme.ron"),
+        }
+    }
+}
+
 /// This example demonstrates the following functionality and use-cases of bevy_egui:
 /// - rendering loaded assets;
 /// - toggling hidpi scaling (by pressing '/' button);
-/// - configuring egui contexts during the startup.
+/// - configuring egui contexts during the startup;
+/// - switching between themes loaded from `*.egui-theme.ron` assets (edit them while the example
+///   is running with the `bevy/file_watcher` feature enabled to see them hot-reloaded).
 fn main() {
     App::new()
         .insert_resource(ClearColor(Color::rgb(0.0, 0.0, 0.0)))
         .insert_resource(Msaa::Sample4)
         .init_resource::<UiState>()
+        .init_resource::<Themes>()
         .add_plugins(DefaultPlugins.set(WindowPlugin {
             primary_window: Some(Window {
                 prevent_default_event_handling: false,
@@ -32,11 +54,12 @@ fn main() {
             }),
             ..default()
         }))
-        .add_plugins(EguiPlugin)
-        .add_systems(Startup, configure_visuals_system)
+        .add_plugins(EguiPlugin::default())
+        .add_systems(Startup, configure_theme_system)
         .add_systems(Startup, configure_ui_state_system)
         .add_systems(Update, update_ui_scale_factor_system)
         .add_systems(Update, ui_example_system)
+        .add_systems(Update, update_theme_system)
         .run();
 }
 #[derive(Default, Resource)]
@@ -47,13 +70,17 @@ struct UiState {
     inverted: bool,
     egui_texture_handle: Option<egui::TextureHandle>,
     is_window_open: bool,
+    light_theme: bool,
 }
 
-fn configure_visuals_system(mut contexts: EguiContexts) {
-    contexts.ctx_mut().set_visuals(egui::Visuals {
-        window_rounding: 0.0.into(),
-        ..Default::default()
-    });
+fn configure_theme_system(
+    mut commands: Commands,
+    themes: Res<Themes>,
+    windows: Query<Entity, With<PrimaryWindow>>,
+) {
+    commands
+        .entity(windows.single())
+        .insert(EguiThemeHandle(themes.default.clone()));
 }
 
 fn configure_ui_state_system(mut ui_state: ResMut<UiState>) {
@@ -80,12 +107,30 @@ fn update_ui_scale_factor_system(
     }
 }
 
+fn update_theme_system(
+    ui_state: Res<UiState>,
+    themes: Res<Themes>,
+    mut theme_handles: Query<&mut EguiThemeHandle, With<PrimaryWindow>>,
+) {
+    if let Ok(mut theme_handle) = theme_handles.get_single_mut() {
+        let theme = if ui_state.light_theme {
+            &themes.light
+        } else {
+            &themes.default
+        };
+        if theme_handle.0 != *theme {
+            theme_handle.0 = theme.clone();
+        }
+    }
+}
+
 fn ui_example_system(
     mut ui_state: ResMut<UiState>,
     // You are not required to store Egui texture ids in systems. We store this one here just to
     // demonstrate that rendering by using a texture id of a removed image is handled without
     // making bevy_egui panic.
     mut rendered_texture_id: Local<egui::TextureId>,
+    mut nearest_texture_id: Local<egui::TextureId>,
     mut is_initialized: Local<bool>,
     // If you need to access the ids from multiple systems, you can also initialize the `Images`
     // resource while building the app and use `Res<Images>` instead.
@@ -110,6 +155,11 @@ fn ui_example_system(
     if !*is_initialized {
         *is_initialized = true;
         *rendered_texture_id = contexts.add_image(images.bevy_icon.clone_weak());
+        // The same image can be also rendered with a different sampler.
+        *nearest_texture_id = contexts.add_image_with_sampler(
+            images.bevy_icon.clone_weak(),
+            ImageSamplerDescriptor::nearest(),
+        );
     }
 
     let ctx = contexts.ctx_mut();
@@ -145,6 +195,11 @@ fn ui_example_system(
                 *rendered_texture_id,
                 [256.0, 256.0],
             )));
+            ui.label("Nearest filtering:");
+            ui.add(egui::widgets::Image::new(egui::load::SizedTexture::new(
+                *nearest_texture_id,
+                [256.0, 256.0],
+            )));
 
             ui.allocate_space(egui::Vec2::new(1.0, 10.0));
             ui.checkbox(&mut ui_state.is_window_open, "Window Is Open");
@@ -165,6 +220,10 @@ fn ui_example_system(
                     std::process::exit(0);
                 }
             });
+            egui::menu::menu_button(ui, "Theme", |ui| {
+                ui.radio_value(&mut ui_state.light_theme, false, "Default");
+                ui.radio_value(&mut ui_state.light_theme, true, "Light");
+            });
         });
     });
 
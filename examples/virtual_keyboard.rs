@@ -0,0 +1,97 @@
+use bevy::{
+    ecs::event::Events,
+    input::{
+        mouse::MouseButtonInput,
+        touch::{TouchInput, TouchPhase},
+        ButtonState, InputSystem,
+    },
+    prelude::*,
+    window::PrimaryWindow,
+};
+use bevy_egui::{
+    prelude::*,
+    virtual_keyboard::{
+        EguiVirtualKeyboardPlugin, EguiVirtualKeyboardSettings, VirtualKeyboardLayout,
+    },
+};
+
+/// This example simulates a touch screen without a system keyboard: mouse clicks are turned into
+/// touches. Tap a text field to show the virtual keyboard, press 'L' to switch its layout.
+fn main() {
+    App::new()
+        .add_plugins(DefaultPlugins)
+        .add_plugins(EguiPlugin::default())
+        .add_plugins(EguiVirtualKeyboardPlugin)
+        .add_systems(
+            PreUpdate,
+            simulate_touch_system
+                .after(InputSystem)
+                .before(EguiSet::ProcessInput),
+        )
+        .add_systems(Update, (switch_layout_system, ui_example_system))
+        .run();
+}
+
+/// Replaces presses of the left mouse button with a touch that follows the cursor.
+fn simulate_touch_system(
+    mut mouse_button_events: ResMut<Events<MouseButtonInput>>,
+    mut touch_events: EventWriter<TouchInput>,
+    windows: Query<(Entity, &Window), With<PrimaryWindow>>,
+    mut touching: Local<bool>,
+) {
+    let Ok((window, window_component)) = windows.get_single() else {
+        return;
+    };
+    let Some(position) = window_component.cursor_position() else {
+        return;
+    };
+    let was_touching = *touching;
+    for event in mouse_button_events.drain() {
+        if event.button == MouseButton::Left {
+            *touching = event.state == ButtonState::Pressed;
+        }
+    }
+    let phase = match (was_touching, *touching) {
+        (false, true) => TouchPhase::Started,
+        (true, true) => TouchPhase::Moved,
+        (true, false) => TouchPhase::Ended,
+        (false, false) => return,
+    };
+    touch_events.send(TouchInput {
+        phase,
+        position,
+        window,
+        force: None,
+        id: 0,
+    });
+}
+
+fn switch_layout_system(
+    keyboard_input: Res<ButtonInput<KeyCode>>,
+    mut settings: ResMut<EguiVirtualKeyboardSettings>,
+    mut azerty: Local<bool>,
+) {
+    if keyboard_input.just_pressed(KeyCode::KeyL) {
+        *azerty = !*azerty;
+        settings.layout = if *azerty {
+            VirtualKeyboardLayout::azerty()
+        } else {
+            VirtualKeyboardLayout::qwerty()
+        };
+    }
+}
+
+fn ui_example_system(
+    mut contexts: EguiContexts,
+    mut name: Local<String>,
+    mut notes: Local<String>,
+) {
+    egui::CentralPanel::default().show(contexts.ctx_mut(), |ui| {
+        ui.horizontal(|ui| {
+            ui.label("Name:");
+            ui.text_edit_singleline(&mut *name);
+        });
+        ui.label("Notes:");
+        ui.text_edit_multiline(&mut *notes);
+    });
+}
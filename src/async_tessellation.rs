@@ -0,0 +1,248 @@
+//! Tessellating the shapes of contexts off the main thread.
+
+use bevy::{ecs::component::Component, log, tasks::AsyncComputeTaskPool};
+
+/// Tessellates the shapes of a context on the [`AsyncComputeTaskPool`] instead of the main
+/// thread, insert it on the window entity.
+///
+/// **The context is displayed with one frame of latency**: the shapes of a pass are tessellated
+/// while the next frame runs, and painted once the next pass ends (the first frame after inserting
+/// the component paints nothing). Every pass gets painted, in order. Texture deltas are still
+/// applied during the pass that produced them, and textures freed by a pass are only freed with
+/// the next one, once the primitives that could reference them are replaced. [`EguiExtraPaintJobs`](crate::EguiExtraPaintJobs)
+/// aren't delayed. When the font atlas is rebuilt (the scale factor changed, or the atlas got
+/// full), the primitives in flight reference glyphs that no longer exist: they're discarded, and
+/// the pass is tessellated on the main thread instead.
+///
+/// Useful for contexts with heavy UIs (dense tables, plots), whose tessellation takes
+/// milliseconds. Removing the component or despawning the entity drops the pass in flight (its
+/// tessellation is skipped if it hasn't started yet). After removing the component, the textures
+/// freed by the last pass are freed with the next one.
+#[derive(Component, Default)]
+pub struct EguiAsyncTessellation {
+    in_flight: Option<InFlightTessellation>,
+}
+
+/// The result of a task tessellating the shapes of a pass. The task is detached, as the type of
+/// its handle depends on whether Bevy runs multithreaded, it's cancelled when dropped if it
+/// hasn't started yet. The receiver is behind a mutex, as components need to be `Sync`.
+struct InFlightTessellation {
+    receiver: std::sync::Mutex<std::sync::mpsc::Receiver<Vec<egui::ClippedPrimitive>>>,
+    cancelled: std::sync::Arc<std::sync::atomic::AtomicBool>,
+}
+
+impl Drop for InFlightTessellation {
+    fn drop(&mut self) {
+        self.cancelled
+            .store(true, std::sync::atomic::Ordering::Relaxed);
+    }
+}
+
+impl InFlightTessellation {
+    fn wait(self) -> Vec<egui::ClippedPrimitive> {
+        let receiver = self
+            .receiver
+            .lock()
+            .unwrap_or_else(std::sync::PoisonError::into_inner);
+        // Tasks spawned on the web run on the main thread between frames, waiting would block
+        // forever.
+        #[cfg(target_arch = "wasm32")]
+        let primitives = receiver.try_recv().map_err(|_| {
+            log::error!("The tessellation of the previous pass didn't finish, skipping it");
+        });
+        #[cfg(not(target_arch = "wasm32"))]
+        let primitives = receiver.recv().map_err(|_| {
+            log::error!("The tessellation of the previous pass panicked, skipping it");
+        });
+        primitives.unwrap_or_default()
+    }
+}
+
+impl EguiAsyncTessellation {
+    /// Returns `true` while the shapes of a pass are being tessellated.
+    pub fn in_flight(&self) -> bool {
+        self.in_flight.is_some()
+    }
+
+    /// Starts tessellating the shapes of a pass, and returns the primitives of the previous one
+    /// (waiting for them if they aren't ready yet). Swaps the frees of the textures delta with the
+    /// delayed ones of the previous pass.
+    pub(crate) fn tessellate(
+        &mut self,
+        ctx: &egui::Context,
+        shapes: Vec<egui::epaint::ClippedShape>,
+        pixels_per_point: f32,
+        textures_delta: &mut egui::TexturesDelta,
+        delayed_frees: &mut Vec<egui::TextureId>,
+    ) -> Vec<egui::ClippedPrimitive> {
+        std::mem::swap(&mut textures_delta.free, delayed_frees);
+
+        let previous = self.in_flight.take().map(InFlightTessellation::wait);
+        let font_atlas_rebuilt = textures_delta.set.iter().any(|(texture_id, delta)| {
+            *texture_id == egui::TextureId::default() && delta.is_whole()
+        });
+        if font_atlas_rebuilt {
+            return ctx.tessellate(shapes, pixels_per_point);
+        }
+
+        let options = ctx.tessellation_options(|options| *options);
+        let (font_tex_size, prepared_discs) = ctx.fonts(|fonts| {
+            let atlas = fonts.texture_atlas();
+            let atlas = atlas.lock();
+            (atlas.size(), atlas.prepared_discs())
+        });
+        let (sender, receiver) = std::sync::mpsc::sync_channel(1);
+        let cancelled = std::sync::Arc::<std::sync::atomic::AtomicBool>::default();
+        let task_cancelled = cancelled.clone();
+        AsyncComputeTaskPool::get()
+            .spawn(async move {
+                if task_cancelled.load(std::sync::atomic::Ordering::Relaxed) {
+                    return;
+                }
+                let primitives = egui::epaint::Tessellator::new(
+                    pixels_per_point,
+                    options,
+                    font_tex_size,
+                    prepared_discs,
+                )
+                .tessellate_shapes(shapes);
+                // The receiver is gone if the context was despawned meanwhile.
+                let _ = sender.send(primitives);
+            })
+            .detach();
+        self.in_flight = Some(InFlightTessellation {
+            receiver: std::sync::Mutex::new(receiver),
+            cancelled,
+        });
+        previous.unwrap_or_default()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{test_utils::input_test_app, EguiContexts, EguiRenderOutput};
+    use bevy::{
+        app::{App, Update},
+        ecs::system::Local,
+    };
+
+    #[test]
+    fn test_async_tessellation() {
+        let (mut app, window) = input_test_app();
+        // Every pass paints a rect whose red channel is the number of the pass.
+        app.add_systems(Update, |mut contexts: EguiContexts, mut pass: Local<u8>| {
+            let Some(ctx) = contexts.try_ctx_mut() else {
+                return;
+            };
+            *pass += 1;
+            ctx.layer_painter(egui::LayerId::background()).rect_filled(
+                egui::Rect::from_min_size(egui::pos2(10.0, 10.0), egui::vec2(10.0, 10.0)),
+                0.0,
+                egui::Color32::from_rgb(*pass, 0, 0),
+            );
+        });
+        let painted_passes = |app: &App| {
+            let mut passes: Vec<u8> = app
+                .world
+                .get::<EguiRenderOutput>(window)
+                .unwrap()
+                .paint_jobs
+                .iter()
+                .filter_map(|primitive| match &primitive.primitive {
+                    egui::epaint::Primitive::Mesh(mesh) => Some(&mesh.vertices),
+                    egui::epaint::Primitive::Callback(_) => None,
+                })
+                .flatten()
+                .filter(|vertex| vertex.color.a() == 255)
+                .map(|vertex| vertex.color.r())
+                .collect();
+            passes.dedup();
+            passes
+        };
+
+        app.update();
+        assert_eq!(painted_passes(&app), [1]);
+
+        // The shapes of a pass are painted once the next pass ends.
+        app.world
+            .entity_mut(window)
+            .insert(EguiAsyncTessellation::default());
+        app.update();
+        assert!(painted_passes(&app).is_empty());
+        for pass in 2..10 {
+            app.update();
+            assert_eq!(painted_passes(&app), [pass]);
+        }
+        assert!(app
+            .world
+            .get::<EguiAsyncTessellation>(window)
+            .unwrap()
+            .in_flight());
+
+        // Back to the synchronous tessellation.
+        app.world
+            .entity_mut(window)
+            .remove::<EguiAsyncTessellation>();
+        app.update();
+        assert_eq!(painted_passes(&app), [11]);
+
+        // Despawning a context with a pass in flight.
+        app.world
+            .entity_mut(window)
+            .insert(EguiAsyncTessellation::default());
+        app.update();
+        app.world.despawn(window);
+        app.update();
+        app.update();
+    }
+
+    #[cfg(feature = "render")]
+    #[test]
+    fn test_async_tessellation_texture_frees() {
+        use crate::{EguiContext, EguiManagedTextures};
+        use bevy::{asset::Assets, render::texture::Image};
+
+        let (mut app, window) = input_test_app();
+        app.world
+            .entity_mut(window)
+            .insert(EguiAsyncTessellation::default());
+        let texture = app
+            .world
+            .get_mut::<EguiContext>(window)
+            .unwrap()
+            .get_mut()
+            .load_texture(
+                "texture",
+                egui::ColorImage::new([4, 4], egui::Color32::RED),
+                Default::default(),
+            );
+        let egui::TextureId::Managed(texture_id) = texture.id() else {
+            unreachable!();
+        };
+        app.update();
+        let handle = app.world.resource::<EguiManagedTextures>()[&(window, texture_id)]
+            .handle
+            .clone_weak();
+        assert!(app.world.resource::<Assets<Image>>().contains(&handle));
+
+        // The free is delayed until the next pass.
+        drop(texture);
+        app.update();
+        assert!(app
+            .world
+            .resource::<EguiManagedTextures>()
+            .contains_key(&(window, texture_id)));
+
+        // Removing the component doesn't lose it.
+        app.world
+            .entity_mut(window)
+            .remove::<EguiAsyncTessellation>();
+        app.update();
+        assert!(!app
+            .world
+            .resource::<EguiManagedTextures>()
+            .contains_key(&(window, texture_id)));
+        assert!(!app.world.resource::<Assets<Image>>().contains(&handle));
+    }
+}
@@ -0,0 +1,431 @@
+//! Egui contexts owned by other plugins (node editors, inspectors) and painted into the contexts
+//! of `bevy_egui`.
+
+use crate::{EguiAnyOutputChanged, EguiContext, EguiInput, EguiOutputChanged, EguiRenderOutput};
+use bevy::{
+    ecs::{
+        entity::Entity,
+        system::{Query, ResMut, Resource},
+    },
+    log,
+    utils::HashSet,
+};
+
+/// Number of bits of the managed texture ids of a child context, the bits above identify the
+/// child in the managed textures of its parent.
+const CHILD_TEXTURE_ID_BITS: u32 = 48;
+
+/// Egui contexts created by other plugins, run and painted as a part of the contexts of
+/// `bevy_egui` (their parents).
+///
+/// A child gets the input of its parent: the pointer while it's above the rect of the child
+/// (presses keep being forwarded until they're released), and the keyboard while
+/// [`EguiChildContext::keyboard_focus`] is enabled. Its passes begin and end along with the
+/// passes of the parent, during [`EguiSet::BeginFrame`](crate::EguiSet::BeginFrame) and
+/// [`EguiSet::ProcessOutput`](crate::EguiSet::ProcessOutput), so it's drawn into during
+/// [`Update`](bevy::app::Update) like any other context. Its primitives are painted over the ones
+/// of the parent, clipped to the rect of the child, and its managed textures are managed along
+/// with the ones of the parent. The platform output of children (cursor icons, copied text) is
+/// ignored.
+///
+/// ```rust
+/// use bevy::{prelude::*, window::PrimaryWindow};
+/// use bevy_egui::child_context::EguiChildContexts;
+///
+/// fn setup_system(
+///     mut child_contexts: ResMut<EguiChildContexts>,
+///     primary_window: Query<Entity, With<PrimaryWindow>>,
+/// ) {
+///     let ctx = egui::Context::default();
+///     let child = child_contexts.register(primary_window.single(), ctx, "node_editor");
+///     child.rect = egui::Rect::from_min_size(egui::pos2(100.0, 100.0), egui::vec2(400.0, 300.0));
+/// }
+///
+/// fn node_editor_system(child_contexts: Res<EguiChildContexts>) {
+///     let Some(child) = child_contexts.get("node_editor") else {
+///         return;
+///     };
+///     egui::CentralPanel::default().show(child.ctx(), |ui| {
+///         ui.label("Nodes");
+///     });
+/// }
+/// ```
+#[derive(Resource, Default)]
+pub struct EguiChildContexts {
+    children: Vec<EguiChildContext>,
+    next_namespace: u64,
+    /// Managed textures (already namespaced) of the removed children, to be freed.
+    pending_frees: Vec<(Entity, u64)>,
+}
+
+/// A child context, see [`EguiChildContexts`].
+pub struct EguiChildContext {
+    /// The context entity (a window) the child belongs to.
+    pub parent: Entity,
+    /// The rect of the child in the Egui points of the parent, it's the screen rect of the child.
+    pub rect: egui::Rect,
+    /// Forwards the keyboard input of the parent to the child (`false` by default).
+    pub keyboard_focus: bool,
+    id: String,
+    ctx: egui::Context,
+    namespace: u64,
+    /// Managed textures allocated by the child, without the namespace.
+    textures: HashSet<u64>,
+    pass_open: bool,
+    pointer_inside: bool,
+    pressed_buttons: Vec<egui::PointerButton>,
+}
+
+impl EguiChildContext {
+    /// The identifier the child was registered with.
+    pub fn id(&self) -> &str {
+        &self.id
+    }
+
+    /// The context of the child.
+    pub fn ctx(&self) -> &egui::Context {
+        &self.ctx
+    }
+
+    /// Returns the id of a managed texture of the child in the managed textures of the parent
+    /// (see [`EguiManagedTextures`](crate::EguiManagedTextures)).
+    pub fn parent_texture_id(&self, texture_id: u64) -> u64 {
+        self.namespace | texture_id
+    }
+
+    fn namespaced(&self, texture_id: egui::TextureId) -> egui::TextureId {
+        match texture_id {
+            egui::TextureId::Managed(id) => egui::TextureId::Managed(self.parent_texture_id(id)),
+            egui::TextureId::User(_) => texture_id,
+        }
+    }
+
+    /// Returns the events of the parent the child gets.
+    fn forwarded_events(&mut self, events: &[egui::Event]) -> Vec<egui::Event> {
+        use egui::Event;
+
+        let mut forwarded = Vec::new();
+        for event in events {
+            match event {
+                Event::PointerMoved(pos) => {
+                    let inside = self.rect.contains(*pos);
+                    if inside || !self.pressed_buttons.is_empty() {
+                        forwarded.push(event.clone());
+                    } else if self.pointer_inside {
+                        forwarded.push(Event::PointerGone);
+                    }
+                    self.pointer_inside = inside;
+                }
+                Event::PointerButton {
+                    button, pressed, ..
+                } => {
+                    if *pressed && self.pointer_inside {
+                        self.pressed_buttons.push(*button);
+                        forwarded.push(event.clone());
+                    } else if !*pressed && self.pressed_buttons.contains(button) {
+                        self.pressed_buttons.retain(|pressed| pressed != button);
+                        forwarded.push(event.clone());
+                    }
+                }
+                Event::PointerGone => {
+                    if self.pointer_inside {
+                        forwarded.push(event.clone());
+                    }
+                    self.pointer_inside = false;
+                }
+                Event::MouseMoved(_)
+                | Event::Scroll(_)
+                | Event::Zoom(_)
+                | Event::MouseWheel { .. }
+                | Event::Touch { .. } => {
+                    if self.pointer_inside {
+                        forwarded.push(event.clone());
+                    }
+                }
+                Event::Copy
+                | Event::Cut
+                | Event::Paste(_)
+                | Event::Text(_)
+                | Event::Key { .. }
+                | Event::CompositionStart
+                | Event::CompositionUpdate(_)
+                | Event::CompositionEnd(_) => {
+                    if self.keyboard_focus {
+                        forwarded.push(event.clone());
+                    }
+                }
+                _ => forwarded.push(event.clone()),
+            }
+        }
+        forwarded
+    }
+}
+
+impl EguiChildContexts {
+    /// Registers a context as a child of a context entity (a window), replacing the child with
+    /// the same id. The rect of the child is empty until it's set.
+    pub fn register(
+        &mut self,
+        parent: Entity,
+        ctx: egui::Context,
+        id: &str,
+    ) -> &mut EguiChildContext {
+        self.remove(id);
+        self.next_namespace += 1;
+        self.children.push(EguiChildContext {
+            parent,
+            rect: egui::Rect::NOTHING,
+            keyboard_focus: false,
+            id: id.to_owned(),
+            ctx,
+            namespace: self.next_namespace << CHILD_TEXTURE_ID_BITS,
+            textures: HashSet::default(),
+            pass_open: false,
+            pointer_inside: false,
+            pressed_buttons: Vec::new(),
+        });
+        self.children.last_mut().unwrap()
+    }
+
+    /// Unregisters a child, its managed textures are freed during the next
+    /// [`EguiSet::ProcessOutput`](crate::EguiSet::ProcessOutput). Returns `false` if no child has
+    /// the id.
+    pub fn remove(&mut self, id: &str) -> bool {
+        let Some(index) = self.children.iter().position(|child| child.id == id) else {
+            return false;
+        };
+        let child = self.children.remove(index);
+        self.pending_frees.extend(
+            child
+                .textures
+                .iter()
+                .map(|texture_id| (child.parent, child.parent_texture_id(*texture_id))),
+        );
+        true
+    }
+
+    /// Returns a child.
+    pub fn get(&self, id: &str) -> Option<&EguiChildContext> {
+        self.children.iter().find(|child| child.id == id)
+    }
+
+    /// Returns a child.
+    pub fn get_mut(&mut self, id: &str) -> Option<&mut EguiChildContext> {
+        self.children.iter_mut().find(|child| child.id == id)
+    }
+
+    /// Iterates over the children.
+    pub fn iter(&self) -> impl Iterator<Item = &EguiChildContext> {
+        self.children.iter()
+    }
+
+    /// Number of children.
+    pub fn len(&self) -> usize {
+        self.children.len()
+    }
+
+    /// Returns `true` if there are no children.
+    pub fn is_empty(&self) -> bool {
+        self.children.is_empty()
+    }
+}
+
+/// Begins the passes of the children whose parents run a pass, with the input of the parents.
+pub fn begin_child_frames_system(
+    mut child_contexts: ResMut<EguiChildContexts>,
+    parents: Query<(&EguiContext, &EguiInput)>,
+) {
+    for child in &mut child_contexts.children {
+        let Ok((parent_ctx, parent_input)) = parents.get(child.parent) else {
+            continue;
+        };
+        if !parent_ctx.pass_due {
+            continue;
+        }
+        let events = child.forwarded_events(&parent_input.events);
+        let raw_input = egui::RawInput {
+            screen_rect: Some(child.rect),
+            events,
+            ..parent_input.0.clone()
+        };
+        let zoom_factor = parent_ctx.ctx.zoom_factor();
+        if child.ctx.zoom_factor() != zoom_factor {
+            child.ctx.set_zoom_factor(zoom_factor);
+        }
+        if child.pass_open {
+            log::error!(
+                "The previous pass of the child context {:?} didn't end, discarding it",
+                child.id
+            );
+            let _ = child.ctx.end_frame();
+        }
+        child.ctx.begin_frame(raw_input);
+        child.pass_open = true;
+    }
+}
+
+/// Ends the passes of the children, and appends their primitives and textures delta to the
+/// render output of their parents. Frees the managed textures of the removed children.
+pub fn end_child_frames_system(
+    mut child_contexts: ResMut<EguiChildContexts>,
+    mut parents: Query<(&mut EguiRenderOutput, Option<&mut EguiOutputChanged>)>,
+    mut any_output_changed: ResMut<EguiAnyOutputChanged>,
+) {
+    let child_contexts = &mut *child_contexts;
+    for (parent, texture_id) in child_contexts.pending_frees.drain(..) {
+        if let Ok((mut render_output, _)) = parents.get_mut(parent) {
+            render_output
+                .textures_delta
+                .free
+                .push(egui::TextureId::Managed(texture_id));
+        }
+    }
+
+    for child in &mut child_contexts.children {
+        if !child.pass_open {
+            continue;
+        }
+        child.pass_open = false;
+        let full_output = child.ctx.end_frame();
+        let Ok((mut render_output, output_changed)) = parents.get_mut(child.parent) else {
+            continue;
+        };
+
+        let mut textures_delta = full_output.textures_delta;
+        for (texture_id, image_delta) in &mut textures_delta.set {
+            if let egui::TextureId::Managed(id) = *texture_id {
+                if image_delta.pos.is_none() {
+                    child.textures.insert(id);
+                }
+            }
+            *texture_id = child.namespaced(*texture_id);
+        }
+        for texture_id in &mut textures_delta.free {
+            if let egui::TextureId::Managed(id) = *texture_id {
+                child.textures.remove(&id);
+            }
+            *texture_id = child.namespaced(*texture_id);
+        }
+
+        let primitives: Vec<_> = child
+            .ctx
+            .tessellate(full_output.shapes, full_output.pixels_per_point)
+            .into_iter()
+            .filter_map(|mut primitive| {
+                primitive.clip_rect = primitive.clip_rect.intersect(child.rect);
+                if !primitive.clip_rect.is_positive() {
+                    return None;
+                }
+                match &mut primitive.primitive {
+                    egui::epaint::Primitive::Mesh(mesh) => {
+                        mesh.texture_id = child.namespaced(mesh.texture_id);
+                    }
+                    egui::epaint::Primitive::Callback(_) => {
+                        log::warn!("Paint callbacks aren't supported, skipping a primitive of the child context {:?}", child.id);
+                        return None;
+                    }
+                }
+                Some(primitive)
+            })
+            .collect();
+
+        if let Some(mut output_changed) = output_changed {
+            if !primitives.is_empty() || !textures_delta.is_empty() {
+                output_changed.changed = true;
+                any_output_changed.0 = true;
+            }
+        }
+        render_output.paint_jobs.extend(primitives);
+        render_output.textures_delta.append(textures_delta);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{test_utils::input_test_app, EguiManagedTextures};
+    use bevy::{
+        app::Update,
+        ecs::system::Res,
+        input::{
+            mouse::{MouseButton, MouseButtonInput},
+            ButtonState,
+        },
+        math::Vec2,
+        window::CursorMoved,
+    };
+
+    #[test]
+    fn test_child_context() {
+        let (mut app, window) = input_test_app();
+        let child_rect =
+            egui::Rect::from_min_size(egui::pos2(100.0, 100.0), egui::vec2(200.0, 100.0));
+        let mut child_contexts = app.world.resource_mut::<EguiChildContexts>();
+        let child = child_contexts.register(window, egui::Context::default(), "child");
+        child.rect = child_rect;
+        let font_texture_id = child.parent_texture_id(0);
+
+        #[derive(Resource, Default)]
+        struct Clicked(bool);
+        app.init_resource::<Clicked>().add_systems(
+            Update,
+            |child_contexts: Res<EguiChildContexts>, mut clicked: ResMut<Clicked>| {
+                let Some(child) = child_contexts.get("child") else {
+                    return;
+                };
+                egui::CentralPanel::default().show(child.ctx(), |ui| {
+                    if ui.button("Click me").clicked() {
+                        clicked.0 = true;
+                    }
+                });
+            },
+        );
+        app.update();
+
+        let render_output = app.world.get::<EguiRenderOutput>(window).unwrap();
+        let child_primitives: Vec<_> = render_output
+            .paint_jobs
+            .iter()
+            .filter(|primitive| match &primitive.primitive {
+                egui::epaint::Primitive::Mesh(mesh) => {
+                    mesh.texture_id == egui::TextureId::Managed(font_texture_id)
+                }
+                egui::epaint::Primitive::Callback(_) => false,
+            })
+            .collect();
+        assert!(!child_primitives.is_empty());
+        assert!(child_primitives
+            .iter()
+            .all(|primitive| child_rect.contains_rect(primitive.clip_rect)));
+        assert!(app
+            .world
+            .resource::<EguiManagedTextures>()
+            .contains_key(&(window, font_texture_id)));
+
+        // The button is at the top left corner of the child.
+        for state in [ButtonState::Pressed, ButtonState::Released] {
+            app.world.send_event(CursorMoved {
+                window,
+                position: Vec2::new(115.0, 112.0),
+                delta: None,
+            });
+            app.world.send_event(MouseButtonInput {
+                button: MouseButton::Left,
+                state,
+                window,
+            });
+            app.update();
+        }
+        assert!(app.world.resource::<Clicked>().0);
+
+        assert!(app
+            .world
+            .resource_mut::<EguiChildContexts>()
+            .remove("child"));
+        app.update();
+        assert!(!app
+            .world
+            .resource::<EguiManagedTextures>()
+            .contains_key(&(window, font_texture_id)));
+    }
+}
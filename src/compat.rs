@@ -0,0 +1,77 @@
+//! Items for crates that support several versions of `bevy_egui`.
+//!
+//! Renamed items keep a deprecated alias in this module (also re-exported from the crate root
+//! where they used to live) until the version named in the deprecation note, so that a crate
+//! built on top of `bevy_egui` can move to the new names without dropping the previous version
+//! right away. Signatures that change get an adapter with the previous signature rather than a
+//! bare alias.
+//!
+//! The constants describe what the version in use provides: [`COMPAT_VERSION`] is bumped
+//! whenever an alias or an adapter is added to or removed from this module, and the `HAS_*`
+//! constants tell which optional features are compiled in.
+//!
+//! ```rust
+//! use bevy_egui::compat;
+//!
+//! if compat::HAS_RENDER && compat::COMPAT_VERSION >= 1 {
+//!     // Register systems that need `EguiUserTextures`...
+//! }
+//! ```
+
+/// The revision of the items of this module, bumped whenever one is added or removed.
+pub const COMPAT_VERSION: u32 = 1;
+
+/// The version of `bevy_egui`, as in its manifest.
+pub const BEVY_EGUI_VERSION: &str = env!("CARGO_PKG_VERSION");
+
+/// Whether the `render` feature is enabled (Egui is rendered by `bevy_egui`).
+pub const HAS_RENDER: bool = cfg!(feature = "render");
+
+/// Whether the `manage_clipboard` feature is enabled.
+pub const HAS_MANAGE_CLIPBOARD: bool = cfg!(feature = "manage_clipboard");
+
+/// Whether the `open_url` feature is enabled.
+pub const HAS_OPEN_URL: bool = cfg!(feature = "open_url");
+
+/// Whether the `immutable_ctx` feature is enabled (contexts can be borrowed immutably).
+pub const HAS_IMMUTABLE_CTX: bool = cfg!(feature = "immutable_ctx");
+
+/// Whether the `accesskit` feature is enabled.
+pub const HAS_ACCESSKIT: bool = cfg!(feature = "accesskit");
+
+/// Whether the `theme` feature is enabled.
+pub const HAS_THEME: bool = cfg!(feature = "theme");
+
+/// Whether the `interaction_metrics` feature is enabled.
+pub const HAS_INTERACTION_METRICS: bool = cfg!(feature = "interaction_metrics");
+
+/// Whether the `validate_user_textures` feature is enabled.
+pub const HAS_VALIDATE_USER_TEXTURES: bool = cfg!(feature = "validate_user_textures");
+
+/// The previous name of [`EguiPlatformOutput`](crate::EguiPlatformOutput).
+#[deprecated(note = "renamed to `EguiPlatformOutput`, the alias will be removed in 0.29")]
+pub type EguiOutput = crate::EguiPlatformOutput;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{test_utils::input_test_app, EguiPlatformOutput};
+
+    #[test]
+    #[allow(deprecated)]
+    fn test_compat() {
+        assert_eq!(BEVY_EGUI_VERSION, env!("CARGO_PKG_VERSION"));
+        assert_eq!(HAS_RENDER, cfg!(feature = "render"));
+
+        // The deprecated aliases still name the current items.
+        let (mut app, window) = input_test_app();
+        app.world
+            .get_mut::<EguiOutput>(window)
+            .unwrap()
+            .platform_output
+            .copied_text = "copied".to_owned();
+        let platform_output = app.world.get::<EguiPlatformOutput>(window).unwrap();
+        assert_eq!(platform_output.platform_output.copied_text, "copied");
+        let _: Option<&EguiOutput> = app.world.get::<EguiOutput>(window);
+    }
+}
@@ -26,18 +26,41 @@ fn linear_from_srgb(srgb: vec3<f32>) -> vec3<f32> {
     return select(higher, lower, cutoff);
 }
 
+fn srgb_from_linear(linear: vec3<f32>) -> vec3<f32> {
+    let cutoff = linear < vec3<f32>(0.0031308);
+    let lower = linear * 12.92;
+    let higher = 1.055 * pow(linear, vec3<f32>(1.0 / 2.4)) - 0.055;
+    return select(higher, lower, cutoff);
+}
+
 @vertex
 fn vs_main(in: VertexInput) -> VertexOutput {
     let position = in.position * transform.scale + transform.translation;
+#ifdef ACCURATE_COLORS
+    // Colors are multiplied in gamma space, like Egui does.
+    let color = in.color;
+#else
     let color = vec4<f32>(linear_from_srgb(in.color.rgb), in.color.a);
+#endif
     return VertexOutput(vec4<f32>(position, 0.0, 1.0), color, in.uv);
 }
 
 @fragment
 fn fs_main(in: VertexOutput) -> @location(0) vec4<f32> {
     let texture_color = textureSample(image_texture, image_sampler, in.uv);
+#ifdef ACCURATE_COLORS
+#ifdef GAMMA_PREMULTIPLIED_TEXTURES
+    let texture_gamma = texture_color;
+#else
+    // Textures allocated via Bevy are sRGB textures, and not premultiplied.
+    let texture_gamma = vec4<f32>(srgb_from_linear(texture_color.rgb) * texture_color.a, texture_color.a);
+#endif
+    let color_gamma = in.color * texture_gamma;
+    let color = vec4<f32>(linear_from_srgb(color_gamma.rgb), color_gamma.a);
+#else
     // This assumes that texture images are not premultiplied.
     let color = in.color * vec4<f32>(texture_color.rgb * texture_color.a, texture_color.a);
+#endif
 
     return color;
 }
@@ -1,12 +1,14 @@
 use crate::{
     render_systems::{
         EguiPipelines, EguiTextureBindGroups, EguiTextureId, EguiTransform, EguiTransforms,
+        ExtractedEguiRenderTargets,
     },
-    EguiRenderOutput, EguiSettings, WindowSize,
+    EguiColorMode, EguiRenderOutput, EguiSettings, WindowSize,
 };
 use bevy::{
     core::cast_slice,
     ecs::world::{FromWorld, World},
+    log,
     prelude::{Entity, Handle, Resource},
     render::{
         render_asset::RenderAssetUsages,
@@ -27,6 +29,7 @@ use bevy::{
     },
 };
 use egui::{TextureFilter, TextureOptions};
+use std::ops::Range;
 
 /// Egui shader.
 pub const EGUI_SHADER_HANDLE: Handle<Shader> = Handle::weak_from_u128(9898276442290979394);
@@ -92,12 +95,26 @@ impl FromWorld for EguiPipeline {
 pub struct EguiPipelineKey {
     /// Texture format of a window's swap chain to render to.
     pub texture_format: TextureFormat,
+    /// How colors are computed, see [`EguiColorMode`].
+    pub color_mode: EguiColorMode,
+    /// Whether the textures contain premultiplied colors in gamma space (managed textures in
+    /// [`EguiColorMode::Accurate`]) instead of unmultiplied colors in an sRGB texture.
+    pub gamma_premultiplied_textures: bool,
+    /// Whether the alpha channel is written, see [`EguiWindowAlphaMode`](crate::EguiWindowAlphaMode).
+    pub write_alpha: bool,
 }
 
 impl SpecializedRenderPipeline for EguiPipeline {
     type Key = EguiPipelineKey;
 
     fn specialize(&self, key: Self::Key) -> RenderPipelineDescriptor {
+        let mut shader_defs = Vec::new();
+        if key.color_mode == EguiColorMode::Accurate {
+            shader_defs.push("ACCURATE_COLORS".into());
+        }
+        if key.gamma_premultiplied_textures {
+            shader_defs.push("GAMMA_PREMULTIPLIED_TEXTURES".into());
+        }
         RenderPipelineDescriptor {
             label: Some("egui render pipeline".into()),
             layout: vec![
@@ -106,7 +123,7 @@ impl SpecializedRenderPipeline for EguiPipeline {
             ],
             vertex: VertexState {
                 shader: EGUI_SHADER_HANDLE,
-                shader_defs: Vec::new(),
+                shader_defs: shader_defs.clone(),
                 entry_point: "vs_main".into(),
                 buffers: vec![VertexBufferLayout::from_vertex_formats(
                     VertexStepMode::Vertex,
@@ -119,7 +136,7 @@ impl SpecializedRenderPipeline for EguiPipeline {
             },
             fragment: Some(FragmentState {
                 shader: EGUI_SHADER_HANDLE,
-                shader_defs: Vec::new(),
+                shader_defs,
                 entry_point: "fs_main".into(),
                 targets: vec![Some(ColorTargetState {
                     format: key.texture_format,
@@ -135,7 +152,11 @@ impl SpecializedRenderPipeline for EguiPipeline {
                             operation: BlendOperation::Add,
                         },
                     }),
-                    write_mask: ColorWrites::ALL,
+                    write_mask: if key.write_alpha {
+                        ColorWrites::ALL
+                    } else {
+                        ColorWrites::COLOR
+                    },
                 })],
             }),
             primitive: PrimitiveState {
@@ -151,12 +172,32 @@ impl SpecializedRenderPipeline for EguiPipeline {
 }
 
 #[derive(Debug)]
-struct DrawCommand {
-    vertices_count: usize,
-    egui_texture: EguiTextureId,
-    clipping_zone: (u32, u32, u32, u32), // x, y, w, h
+pub(crate) struct DrawCommand {
+    pub(crate) indices: Range<u32>,
+    /// Byte offset of the segment of the vertex buffer that the indices point into.
+    pub(crate) vertex_offset: u64,
+    pub(crate) egui_texture: EguiTextureId,
+    pub(crate) clipping_zone: (u32, u32, u32, u32), // x, y, w, h
 }
 
+/// How many bytes of vertex or index data [`EguiNode`] keeps allocated between frames
+/// after the data shrinks.
+pub const MAX_RETAINED_DATA_CAPACITY: usize = 4 * 1024 * 1024;
+
+/// How many indices [`EguiNode`] draws with a single draw call at most, unless
+/// [`EguiSettings::max_indices_per_draw`] is set.
+///
+/// Some mobile GPUs render garbage when a single draw call has millions of indices, so larger
+/// meshes are split into several draw calls. The render device doesn't report how many indices
+/// it can draw at once (wgpu has no such limit), so the default is derived from the largest buffer
+/// it supports ([`max_buffer_size`](bevy::render::settings::WgpuLimits::max_buffer_size)), capped
+/// at this value.
+pub const DEFAULT_MAX_INDICES_PER_DRAW: u32 = 1 << 20;
+
+/// How many vertices a segment of the vertex buffer holds at most, so that `u32` indices can
+/// point at all of them.
+const MAX_VERTICES_PER_SEGMENT: u64 = 1 << 32;
+
 /// Egui render node.
 pub struct EguiNode {
     window_entity: Entity,
@@ -183,29 +224,48 @@ impl EguiNode {
             index_buffer: None,
         }
     }
-}
-
-impl Node for EguiNode {
-    fn update(&mut self, world: &mut World) {
-        let mut window_sizes = world.query::<(&WindowSize, &mut EguiRenderOutput)>();
-
-        let Ok((window_size, mut render_output)) = window_sizes.get_mut(world, self.window_entity)
-        else {
-            return;
-        };
-        let window_size = *window_size;
-        let paint_jobs = std::mem::take(&mut render_output.paint_jobs);
-
-        let egui_settings = &world.get_resource::<EguiSettings>().unwrap();
 
-        let render_device = world.get_resource::<RenderDevice>().unwrap();
-
-        let scale_factor = window_size.scale_factor * egui_settings.scale_factor;
-        if window_size.physical_width == 0.0 || window_size.physical_height == 0.0 {
-            return;
-        }
+    /// Packs vertices and indices of the paint jobs, and records the draw commands.
+    ///
+    /// The buffers are reused between frames, but capacity above
+    /// [`MAX_RETAINED_DATA_CAPACITY`] is released once the data shrinks below it.
+    /// Meshes with more than `max_indices_per_draw` indices are drawn with several draw calls.
+    ///
+    /// This is called by [`Node::update`]. It isn't a part of the public API, it's only exposed to
+    /// the allocation counting test, which needs a test binary of its own.
+    #[doc(hidden)]
+    pub fn pack_paint_jobs(
+        &mut self,
+        paint_jobs: &[egui::ClippedPrimitive],
+        window_size: WindowSize,
+        scale_factor: f32,
+        max_indices_per_draw: u32,
+    ) {
+        self.pack_paint_jobs_in_segments(
+            paint_jobs,
+            window_size,
+            scale_factor,
+            max_indices_per_draw,
+            MAX_VERTICES_PER_SEGMENT,
+        );
+    }
 
-        let mut index_offset = 0;
+    /// Same as [`Self::pack_paint_jobs`]. Once the vertices of the frame don't fit in a segment
+    /// of the vertex buffer anymore, the next meshes start a new one, their indices are rebased on
+    /// it.
+    fn pack_paint_jobs_in_segments(
+        &mut self,
+        paint_jobs: &[egui::ClippedPrimitive],
+        window_size: WindowSize,
+        scale_factor: f32,
+        max_indices_per_draw: u32,
+        max_vertices_per_segment: u64,
+    ) {
+        // The number of vertices in the current segment, which starts at `vertex_offset` bytes.
+        let mut index_offset: u64 = 0;
+        let mut vertex_offset: u64 = 0;
+        // Draw calls must not split triangles.
+        let max_indices_per_draw = (max_indices_per_draw / 3 * 3).max(3) as usize;
 
         self.draw_commands.clear();
         self.vertex_data.clear();
@@ -214,7 +274,7 @@ impl Node for EguiNode {
         for egui::epaint::ClippedPrimitive {
             clip_rect,
             primitive,
-        } in &paint_jobs
+        } in paint_jobs
         {
             let mesh = match primitive {
                 egui::epaint::Primitive::Mesh(mesh) => mesh,
@@ -238,16 +298,30 @@ impl Node for EguiNode {
                 continue;
             }
 
-            self.vertex_data
-                .extend_from_slice(cast_slice::<_, u8>(mesh.vertices.as_slice()));
-            let indices_with_offset = mesh
+            if mesh
                 .indices
                 .iter()
-                .map(|i| i + index_offset)
-                .collect::<Vec<_>>();
-            self.index_data
-                .extend_from_slice(cast_slice(indices_with_offset.as_slice()));
-            index_offset += mesh.vertices.len() as u32;
+                .any(|&index| index as usize >= mesh.vertices.len())
+            {
+                log::warn_once!("Skipping an Egui mesh with indices past its vertices");
+                continue;
+            }
+            let vertices_count = mesh.vertices.len() as u64;
+            if index_offset > 0 && index_offset + vertices_count > max_vertices_per_segment {
+                vertex_offset = self.vertex_data.len() as u64;
+                index_offset = 0;
+            }
+
+            let first_index = (self.index_data.len() / std::mem::size_of::<u32>()) as u32;
+            self.vertex_data
+                .extend_from_slice(cast_slice::<_, u8>(mesh.vertices.as_slice()));
+            self.index_data.extend(
+                mesh.indices
+                    .iter()
+                    // Fits, as indices point at the vertices of their meshes.
+                    .flat_map(|i| (i + index_offset as u32).to_ne_bytes()),
+            );
+            index_offset += vertices_count;
 
             let texture_handle = match mesh.texture_id {
                 egui::TextureId::Managed(id) => EguiTextureId::Managed(self.window_entity, id),
@@ -256,18 +330,72 @@ impl Node for EguiNode {
 
             let x_viewport_clamp = (x + w).saturating_sub(window_size.physical_width as u32);
             let y_viewport_clamp = (y + h).saturating_sub(window_size.physical_height as u32);
-            self.draw_commands.push(DrawCommand {
-                vertices_count: mesh.indices.len(),
-                egui_texture: texture_handle,
-                clipping_zone: (
-                    x,
-                    y,
-                    w.saturating_sub(x_viewport_clamp).max(1),
-                    h.saturating_sub(y_viewport_clamp).max(1),
-                ),
-            });
+            let clipping_zone = (
+                x,
+                y,
+                w.saturating_sub(x_viewport_clamp).max(1),
+                h.saturating_sub(y_viewport_clamp).max(1),
+            );
+            let mut start = 0;
+            while start < mesh.indices.len() {
+                let end = (start + max_indices_per_draw).min(mesh.indices.len());
+                self.draw_commands.push(DrawCommand {
+                    indices: first_index + start as u32..first_index + end as u32,
+                    vertex_offset,
+                    egui_texture: texture_handle,
+                    clipping_zone,
+                });
+                start = end;
+            }
+        }
+
+        for data in [&mut self.vertex_data, &mut self.index_data] {
+            if data.capacity() > MAX_RETAINED_DATA_CAPACITY
+                && data.len() <= MAX_RETAINED_DATA_CAPACITY
+            {
+                data.shrink_to(MAX_RETAINED_DATA_CAPACITY);
+            }
+        }
+    }
+}
+
+impl Node for EguiNode {
+    fn update(&mut self, world: &mut World) {
+        // Empty outputs aren't extracted, so nothing from the previous frames must be drawn.
+        self.draw_commands.clear();
+
+        let Some(&(window_size, computed_scale)) = world
+            .resource::<ExtractedEguiRenderTargets>()
+            .get(&self.window_entity)
+        else {
+            return;
+        };
+        let mut render_outputs = world.query::<&mut EguiRenderOutput>();
+        let Ok(mut render_output) = render_outputs.get_mut(world, self.window_entity) else {
+            return;
+        };
+        let paint_jobs = std::mem::take(&mut render_output.paint_jobs);
+
+        if window_size.physical_width == 0.0 || window_size.physical_height == 0.0 {
+            return;
         }
 
+        let render_device = world.get_resource::<RenderDevice>().unwrap();
+        let max_indices_per_draw = world
+            .get_resource::<EguiSettings>()
+            .and_then(|settings| settings.max_indices_per_draw)
+            .unwrap_or_else(|| {
+                let max_buffer_size = render_device.limits().max_buffer_size;
+                (max_buffer_size / std::mem::size_of::<u32>() as u64)
+                    .min(DEFAULT_MAX_INDICES_PER_DRAW as u64) as u32
+            });
+        self.pack_paint_jobs(
+            &paint_jobs,
+            window_size,
+            computed_scale.pixels_per_point,
+            max_indices_per_draw,
+        );
+
         if self.vertex_data.len() > self.vertex_buffer_capacity {
             self.vertex_buffer_capacity = if self.vertex_data.len().is_power_of_two() {
                 self.vertex_data.len()
@@ -302,6 +430,11 @@ impl Node for EguiNode {
         render_context: &mut RenderContext,
         world: &World,
     ) -> Result<(), NodeRunError> {
+        // The pass loads the window contents instead of clearing them, so it can be skipped.
+        if self.draw_commands.is_empty() {
+            return Ok(());
+        }
+
         let egui_pipelines = &world.get_resource::<EguiPipelines>().unwrap().0;
         let pipeline_cache = world.get_resource::<PipelineCache>().unwrap();
 
@@ -353,14 +486,16 @@ impl Node for EguiNode {
                     occlusion_query_set: None,
                 });
 
-        let Some(pipeline_id) = egui_pipelines.get(&extracted_window.entity) else {
+        let Some(window_pipelines) = egui_pipelines.get(&extracted_window.entity) else {
             return Ok(());
         };
-        let Some(pipeline) = pipeline_cache.get_render_pipeline(*pipeline_id) else {
+        let (Some(user_textures_pipeline), Some(managed_textures_pipeline)) = (
+            pipeline_cache.get_render_pipeline(window_pipelines.user_textures),
+            pipeline_cache.get_render_pipeline(window_pipelines.managed_textures),
+        ) else {
             return Ok(());
         };
 
-        render_pass.set_pipeline(pipeline);
         render_pass.set_vertex_buffer(0, *self.vertex_buffer.as_ref().unwrap().slice(..));
         render_pass.set_index_buffer(
             *self.index_buffer.as_ref().unwrap().slice(..),
@@ -371,20 +506,30 @@ impl Node for EguiNode {
         let transform_buffer_bind_group = &egui_transforms.bind_group.as_ref().unwrap().1;
         render_pass.set_bind_group(0, transform_buffer_bind_group, &[transform_buffer_offset]);
 
-        let mut vertex_offset: u32 = 0;
+        let mut current_pipeline = None;
+        let mut current_vertex_offset = 0;
         for draw_command in &self.draw_commands {
             if draw_command.clipping_zone.0 < extracted_window.physical_width
                 && draw_command.clipping_zone.1 < extracted_window.physical_height
             {
-                let texture_bind_group = match bind_groups.get(&draw_command.egui_texture) {
-                    Some(texture_resource) => texture_resource,
-                    None => {
-                        vertex_offset += draw_command.vertices_count as u32;
-                        continue;
-                    }
+                let Some(texture_bind_group) = bind_groups.get(&draw_command.egui_texture) else {
+                    continue;
                 };
 
+                let pipeline = match draw_command.egui_texture {
+                    EguiTextureId::Managed(..) => managed_textures_pipeline,
+                    EguiTextureId::User(_) => user_textures_pipeline,
+                };
+                if current_pipeline != Some(pipeline.id()) {
+                    render_pass.set_pipeline(pipeline);
+                    current_pipeline = Some(pipeline.id());
+                }
                 render_pass.set_bind_group(1, texture_bind_group, &[]);
+                if current_vertex_offset != draw_command.vertex_offset {
+                    render_pass
+                        .set_vertex_buffer(0, *vertex_buffer.slice(draw_command.vertex_offset..));
+                    current_vertex_offset = draw_command.vertex_offset;
+                }
 
                 render_pass.set_scissor_rect(
                     draw_command.clipping_zone.0,
@@ -401,12 +546,7 @@ impl Node for EguiNode {
                     ),
                 );
 
-                render_pass.draw_indexed(
-                    vertex_offset..(vertex_offset + draw_command.vertices_count as u32),
-                    0,
-                    0..1,
-                );
-                vertex_offset += draw_command.vertices_count as u32;
+                render_pass.draw_indexed(draw_command.indices.clone(), 0, 0..1);
             }
         }
 
@@ -431,15 +571,30 @@ fn alpha_image_as_color_image(image: &egui::FontImage) -> egui::ColorImage {
 pub(crate) fn color_image_as_bevy_image(
     egui_image: &egui::ColorImage,
     sampler_descriptor: ImageSampler,
+    color_mode: EguiColorMode,
 ) -> Image {
-    let pixels = egui_image
-        .pixels
-        .iter()
-        // We unmultiply Egui textures to premultiply them later in the fragment shader.
-        // As user textures loaded as Bevy assets are not premultiplied (and there seems to be no
-        // convenient way to convert them to premultiplied ones), we do the this with Egui ones.
-        .flat_map(|color| color.to_srgba_unmultiplied())
-        .collect();
+    let (pixels, format) = match color_mode {
+        EguiColorMode::Standard => (
+            egui_image
+                .pixels
+                .iter()
+                // We unmultiply Egui textures to premultiply them later in the fragment shader.
+                // As user textures loaded as Bevy assets are not premultiplied (and there seems to be no
+                // convenient way to convert them to premultiplied ones), we do the this with Egui ones.
+                .flat_map(|color| color.to_srgba_unmultiplied())
+                .collect(),
+            TextureFormat::Rgba8UnormSrgb,
+        ),
+        // The shader multiplies the colors in gamma space, as they are.
+        EguiColorMode::Accurate => (
+            egui_image
+                .pixels
+                .iter()
+                .flat_map(|color| color.to_array())
+                .collect(),
+            TextureFormat::Rgba8Unorm,
+        ),
+    };
 
     Image {
         sampler: sampler_descriptor,
@@ -451,7 +606,7 @@ pub(crate) fn color_image_as_bevy_image(
             },
             TextureDimension::D2,
             pixels,
-            TextureFormat::Rgba8UnormSrgb,
+            format,
             RenderAssetUsages::MAIN_WORLD | RenderAssetUsages::RENDER_WORLD,
         )
     }
@@ -479,3 +634,88 @@ pub(crate) fn texture_options_as_sampler_descriptor(
         ..Default::default()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::render_systems::EguiTextureId;
+
+    fn mesh(vertices: usize, indices: impl IntoIterator<Item = u32>) -> egui::ClippedPrimitive {
+        egui::ClippedPrimitive {
+            clip_rect: egui::Rect::from_min_size(egui::pos2(10.0, 10.0), egui::vec2(50.0, 50.0)),
+            primitive: egui::epaint::Primitive::Mesh(egui::Mesh {
+                indices: indices.into_iter().collect(),
+                vertices: vec![egui::epaint::Vertex::default(); vertices],
+                texture_id: egui::TextureId::User(7),
+            }),
+        }
+    }
+
+    #[test]
+    fn test_paint_jobs_packing_splits_large_meshes() {
+        let window = Entity::from_raw(0);
+        let window_size = WindowSize::new(100.0, 100.0, 1.0);
+        let paint_jobs = [
+            mesh(3, 0..3),
+            mesh(3000, 0..3000),
+            // Corrupt meshes are skipped.
+            mesh(3, [0, 1, 3]),
+            mesh(3, 0..3),
+        ];
+        let mut node = EguiNode::new(window);
+        // Rounded down to whole triangles.
+        node.pack_paint_jobs(&paint_jobs, window_size, 1.0, 1001);
+
+        let draw_commands = &node.draw_commands;
+        assert_eq!(
+            draw_commands
+                .iter()
+                .map(|draw_command| draw_command.indices.clone())
+                .collect::<Vec<_>>(),
+            [
+                0..3,
+                3..1002,
+                1002..2001,
+                2001..3000,
+                3000..3003,
+                3003..3006
+            ]
+        );
+        for draw_command in draw_commands {
+            assert_eq!(draw_command.egui_texture, EguiTextureId::User(7));
+            assert_eq!(draw_command.clipping_zone, (10, 10, 50, 50));
+        }
+    }
+
+    #[test]
+    fn test_paint_jobs_packing_starts_new_vertex_segments() {
+        let window_size = WindowSize::new(100.0, 100.0, 1.0);
+        let paint_jobs = [mesh(3, 0..3), mesh(3000, 0..3000), mesh(3, [2, 1, 0])];
+        let mut node = EguiNode::new(Entity::from_raw(0));
+        // The indices of the second mesh would go past the segment, and then the third one's.
+        node.pack_paint_jobs_in_segments(&paint_jobs, window_size, 1.0, 3000, 3001);
+
+        let vertex_size = std::mem::size_of::<egui::epaint::Vertex>() as u64;
+        assert_eq!(
+            node.draw_commands
+                .iter()
+                .map(|draw_command| (draw_command.indices.clone(), draw_command.vertex_offset))
+                .collect::<Vec<_>>(),
+            [
+                (0..3, 0),
+                (3..3003, 3 * vertex_size),
+                (3003..3006, 3003 * vertex_size)
+            ]
+        );
+        let indices: Vec<u32> = node
+            .index_data
+            .chunks_exact(4)
+            .map(|bytes| u32::from_ne_bytes(bytes.try_into().unwrap()))
+            .collect();
+        assert_eq!(indices[..3], [0, 1, 2]);
+        assert_eq!(indices[3..6], [0, 1, 2]);
+        assert_eq!(indices[3002], 2999);
+        assert_eq!(indices[3003..], [2, 1, 0]);
+        assert_eq!(node.vertex_data.len() as u64, 3006 * vertex_size);
+    }
+}
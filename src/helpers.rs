@@ -0,0 +1,55 @@
+use crate::EguiComputedScale;
+use bevy::math::{Rect, Vec2};
+#[cfg(feature = "render")]
+use bevy::{
+    math::Vec3,
+    prelude::{Camera, GlobalTransform},
+};
+
+/// Converts a position in physical window pixels into Egui points.
+pub fn physical_to_egui_pos(
+    physical_position: Vec2,
+    computed_scale: &EguiComputedScale,
+) -> egui::Pos2 {
+    let position = physical_position / computed_scale.pixels_per_point;
+    egui::pos2(position.x, position.y)
+}
+
+/// Converts a position in Egui points into physical window pixels.
+pub fn egui_to_physical_pos(egui_position: egui::Pos2, computed_scale: &EguiComputedScale) -> Vec2 {
+    Vec2::new(egui_position.x, egui_position.y) * computed_scale.pixels_per_point
+}
+
+/// Converts a rect in Egui points (for example, [`crate::EguiTextCursorInfo::caret_rect`])
+/// into physical window pixels.
+pub fn egui_to_physical_rect(egui_rect: egui::Rect, computed_scale: &EguiComputedScale) -> Rect {
+    Rect::from_corners(
+        egui_to_physical_pos(egui_rect.min, computed_scale),
+        egui_to_physical_pos(egui_rect.max, computed_scale),
+    )
+}
+
+/// Projects a world position with a camera that renders to a window, and returns the position
+/// in the Egui points of that window's context.
+///
+/// Returns [`None`] if the position is outside the camera's view frustum or the camera
+/// viewport isn't computed yet.
+#[cfg(feature = "render")]
+pub fn world_to_egui_pos(
+    camera: &Camera,
+    camera_transform: &GlobalTransform,
+    world_position: Vec3,
+    computed_scale: &EguiComputedScale,
+) -> Option<egui::Pos2> {
+    let ndc = camera.world_to_ndc(camera_transform, world_position)?;
+    if !(0.0..=1.0).contains(&ndc.z) {
+        return None;
+    }
+    let viewport = camera.physical_viewport_rect()?;
+    let viewport_position = (ndc.truncate() + Vec2::ONE) / 2.0 * viewport.size().as_vec2();
+    let physical_position = Vec2::new(
+        viewport.min.x as f32 + viewport_position.x,
+        viewport.max.y as f32 - viewport_position.y,
+    );
+    Some(physical_to_egui_pos(physical_position, computed_scale))
+}
@@ -0,0 +1,287 @@
+//! Capturing the next pressed key or button, for rebinding game actions.
+
+use bevy::{
+    ecs::system::{ResMut, Resource},
+    input::{gamepad::GamepadButton, keyboard::KeyCode, mouse::MouseButton, ButtonInput},
+};
+
+/// A key or button recorded by [`EguiInputCapture`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum CapturedButton {
+    /// A physical key.
+    Key(KeyCode),
+    /// A mouse button.
+    Mouse(MouseButton),
+    /// A gamepad button.
+    Gamepad(GamepadButton),
+}
+
+/// A press recorded by [`EguiInputCapture`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct CapturedInput {
+    /// The pressed key or button.
+    pub button: CapturedButton,
+    /// The modifier keys held during the press.
+    pub modifiers: egui::Modifiers,
+}
+
+/// Records the next key, mouse button or gamepad button press, to let the player pick a binding
+/// in an Egui settings screen.
+///
+/// While capturing is [`active`](EguiInputCapture::active), the next press (modifier keys
+/// excepted, they are recorded along with the press) is stored in
+/// [`captured`](EguiInputCapture::captured) and capturing stops. The press and its release never
+/// reach Egui, and are also removed from [`ButtonInput`] if
+/// [`withhold_from_game`](EguiInputCapture::withhold_from_game) is enabled. Text typed while
+/// capturing is dropped. Escape cancels capturing without recording anything.
+///
+/// ```rust
+/// use bevy::prelude::*;
+/// use bevy_egui::{input::{CapturedButton, EguiInputCapture}, prelude::*};
+///
+/// #[derive(Resource)]
+/// struct JumpBinding(CapturedButton);
+///
+/// fn rebinding_row_system(
+///     mut contexts: EguiContexts,
+///     mut input_capture: ResMut<EguiInputCapture>,
+///     mut jump_binding: ResMut<JumpBinding>,
+/// ) {
+///     if let Some(captured) = input_capture.take() {
+///         jump_binding.0 = captured.button;
+///     }
+///     egui::Window::new("Controls").show(contexts.ctx_mut(), |ui| {
+///         ui.horizontal(|ui| {
+///             ui.label("Jump");
+///             let text = if input_capture.active {
+///                 "Press a key...".to_owned()
+///             } else {
+///                 format!("{:?}", jump_binding.0)
+///             };
+///             if ui.button(text).clicked() {
+///                 input_capture.start();
+///             }
+///         });
+///     });
+/// }
+/// ```
+#[derive(Resource, Clone, Debug)]
+pub struct EguiInputCapture {
+    /// Whether the next press gets recorded.
+    pub active: bool,
+    /// The last recorded press.
+    pub captured: Option<CapturedInput>,
+    /// Removes the recorded press (and Escape when it cancels capturing) from [`ButtonInput`],
+    /// so that game systems don't react to it (`true` by default).
+    pub withhold_from_game: bool,
+    /// Buttons whose presses and releases are withheld from Egui until they're released.
+    withheld: Vec<CapturedButton>,
+    /// Buttons to remove from [`ButtonInput`].
+    pending_resets: Vec<CapturedButton>,
+}
+
+impl Default for EguiInputCapture {
+    fn default() -> Self {
+        Self {
+            active: false,
+            captured: None,
+            withhold_from_game: true,
+            withheld: Vec::new(),
+            pending_resets: Vec::new(),
+        }
+    }
+}
+
+impl EguiInputCapture {
+    /// Starts capturing, forgetting the last recorded press.
+    pub fn start(&mut self) {
+        self.active = true;
+        self.captured = None;
+    }
+
+    /// Stops capturing without recording anything.
+    pub fn cancel(&mut self) {
+        self.active = false;
+    }
+
+    /// Takes the recorded press.
+    pub fn take(&mut self) -> Option<CapturedInput> {
+        self.captured.take()
+    }
+
+    /// Records the press if capturing is active. Returns `true` if the press or release is
+    /// withheld from Egui.
+    pub fn withholds(
+        &mut self,
+        button: CapturedButton,
+        pressed: bool,
+        modifiers: egui::Modifiers,
+    ) -> bool {
+        if let Some(index) = self
+            .withheld
+            .iter()
+            .position(|withheld| *withheld == button)
+        {
+            // Key repeats are withheld as well.
+            if !pressed {
+                self.withheld.swap_remove(index);
+            }
+            return true;
+        }
+        if !pressed || !self.active || is_modifier(button) {
+            return false;
+        }
+
+        self.active = false;
+        if button != CapturedButton::Key(KeyCode::Escape) {
+            self.captured = Some(CapturedInput { button, modifiers });
+        }
+        self.withheld.push(button);
+        if self.withhold_from_game {
+            self.pending_resets.push(button);
+        }
+        true
+    }
+}
+
+fn is_modifier(button: CapturedButton) -> bool {
+    matches!(
+        button,
+        CapturedButton::Key(
+            KeyCode::ShiftLeft
+                | KeyCode::ShiftRight
+                | KeyCode::ControlLeft
+                | KeyCode::ControlRight
+                | KeyCode::AltLeft
+                | KeyCode::AltRight
+                | KeyCode::SuperLeft
+                | KeyCode::SuperRight
+                | KeyCode::Meta
+        )
+    )
+}
+
+/// Removes the presses recorded by [`EguiInputCapture`] from [`ButtonInput`], see
+/// [`EguiInputCapture::withhold_from_game`].
+pub fn withhold_captured_input_system(
+    mut input_capture: ResMut<EguiInputCapture>,
+    mut keys: Option<ResMut<ButtonInput<KeyCode>>>,
+    mut mouse_buttons: Option<ResMut<ButtonInput<MouseButton>>>,
+    mut gamepad_buttons: Option<ResMut<ButtonInput<GamepadButton>>>,
+) {
+    if input_capture.pending_resets.is_empty() {
+        return;
+    }
+    for button in input_capture.pending_resets.drain(..) {
+        match button {
+            CapturedButton::Key(key) => {
+                if let Some(keys) = &mut keys {
+                    keys.reset(key);
+                }
+            }
+            CapturedButton::Mouse(button) => {
+                if let Some(mouse_buttons) = &mut mouse_buttons {
+                    mouse_buttons.reset(button);
+                }
+            }
+            CapturedButton::Gamepad(button) => {
+                if let Some(gamepad_buttons) = &mut gamepad_buttons {
+                    gamepad_buttons.reset(button);
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils::{input_test_app, CapturedEvents};
+    use bevy::{
+        app::App,
+        input::{mouse::MouseButtonInput, ButtonState},
+    };
+
+    #[test]
+    fn test_input_capture() {
+        use bevy::input::{
+            keyboard::{Key, KeyCode, KeyboardInput},
+            ButtonInput,
+        };
+
+        let (mut app, window) = input_test_app();
+        let send_key = |app: &mut App, key_code, logical_key, state| {
+            app.world.send_event(KeyboardInput {
+                key_code,
+                logical_key,
+                state,
+                window,
+            });
+        };
+        app.world.resource_mut::<EguiInputCapture>().start();
+        send_key(
+            &mut app,
+            KeyCode::ControlLeft,
+            Key::Control,
+            ButtonState::Pressed,
+        );
+        send_key(
+            &mut app,
+            KeyCode::KeyS,
+            Key::Character("s".into()),
+            ButtonState::Pressed,
+        );
+        app.update();
+
+        // The modifier is recorded along with the press, and the press reaches neither Egui nor
+        // the game.
+        let captured = app.world.resource_mut::<EguiInputCapture>().take().unwrap();
+        assert_eq!(captured.button, CapturedButton::Key(KeyCode::KeyS));
+        assert!(captured.modifiers.ctrl);
+        assert!(!app.world.resource::<EguiInputCapture>().active);
+        assert!(app.world.resource::<CapturedEvents>().0.is_empty());
+        let keys = app.world.resource::<ButtonInput<KeyCode>>();
+        assert!(!keys.pressed(KeyCode::KeyS) && !keys.just_pressed(KeyCode::KeyS));
+        assert!(keys.pressed(KeyCode::ControlLeft));
+
+        // Neither does the release.
+        send_key(
+            &mut app,
+            KeyCode::KeyS,
+            Key::Character("s".into()),
+            ButtonState::Released,
+        );
+        app.update();
+        assert!(app.world.resource::<CapturedEvents>().0.is_empty());
+
+        // Escape cancels capturing.
+        app.world.resource_mut::<EguiInputCapture>().start();
+        send_key(&mut app, KeyCode::Escape, Key::Escape, ButtonState::Pressed);
+        app.update();
+        let input_capture = app.world.resource::<EguiInputCapture>();
+        assert!(!input_capture.active && input_capture.captured.is_none());
+        assert!(app.world.resource::<CapturedEvents>().0.is_empty());
+
+        // Mouse buttons are captured as well.
+        app.world.resource_mut::<EguiInputCapture>().start();
+        app.world.send_event(MouseButtonInput {
+            button: MouseButton::Right,
+            state: ButtonState::Pressed,
+            window,
+        });
+        app.update();
+        assert_eq!(
+            app.world
+                .resource::<EguiInputCapture>()
+                .captured
+                .unwrap()
+                .button,
+            CapturedButton::Mouse(MouseButton::Right)
+        );
+        assert!(app.world.resource::<CapturedEvents>().0.is_empty());
+        assert!(!app
+            .world
+            .resource::<ButtonInput<MouseButton>>()
+            .pressed(MouseButton::Right));
+    }
+}
@@ -0,0 +1,213 @@
+//! Tracking which context has keyboard focus.
+
+use super::InputResources;
+use crate::{EguiContext, EguiPrimaryContext};
+use bevy::{
+    ecs::{change_detection::DetectChangesMut, event::EventReader},
+    prelude::{Entity, Has, Query, Resource, With},
+    window::{PrimaryWindow, WindowFocused},
+};
+
+/// The context of the window that has focus, [`None`] if no window is focused or focus events
+/// haven't been received yet.
+///
+/// Events of [`EguiInputSource`](super::EguiInputSource)s are routed to this context, or to the
+/// primary one if it's [`None`].
+#[derive(Resource, Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct EguiFocusedContext(pub Option<Entity>);
+
+/// Owner of the text input focus, see [`TextInputFocus`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum TextInputOwner {
+    /// An Egui context (a window) that wants keyboard input.
+    Egui(Entity),
+    /// A text input of another plugin, identified by a name.
+    Other(&'static str),
+}
+
+/// Which text input owns the keyboard, shared by the plugins that consume text.
+///
+/// Plugins that read [`KeyboardInput`](bevy::input::keyboard::KeyboardInput) or
+/// [`ReceivedCharacter`](bevy::window::ReceivedCharacter) events independently would otherwise all
+/// type into their focused text boxes at once. The protocol is:
+///
+/// - An owner sets the focus to itself when its text input gets focused (clicked, for example),
+///   and resets it to [`None`] when the text input loses focus, but only if it still owns it.
+/// - A plugin doesn't consume text and keys while another owner holds the focus.
+/// - Nobody clobbers the focus of another owner, except when the user explicitly focuses
+///   a text input of their own.
+///
+/// `bevy_egui` claims the focus (as [`TextInputOwner::Egui`]) during [`EguiSet::BeginFrame`] when
+/// a context wants keyboard input, and releases it once none does. While [`TextInputOwner::Other`]
+/// holds the focus, Egui widgets lose their focus, and text and key events aren't fed to Egui
+/// (see [`EguiSettings::respect_external_text_focus`]).
+///
+/// [`EguiSet::BeginFrame`]: crate::EguiSet::BeginFrame
+/// [`EguiSettings::respect_external_text_focus`]: crate::EguiSettings::respect_external_text_focus
+#[derive(Resource, Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct TextInputFocus(pub Option<TextInputOwner>);
+
+impl TextInputFocus {
+    /// Returns `true` if a text input that isn't an Egui one owns the focus.
+    #[must_use]
+    pub fn is_external(&self) -> bool {
+        matches!(self.0, Some(TextInputOwner::Other(_)))
+    }
+}
+
+/// Contexts that can be primary, see [`primary_context`].
+pub type PrimaryContextQuery<'w, 's> =
+    Query<'w, 's, (Entity, Has<PrimaryWindow>, Has<EguiPrimaryContext>), With<EguiContext>>;
+
+/// Returns the context marked with [`EguiPrimaryContext`] if there's any, or the one of the
+/// [`PrimaryWindow`] otherwise.
+pub fn primary_context(contexts: &PrimaryContextQuery) -> Option<Entity> {
+    contexts
+        .iter()
+        .filter(|(_, _, is_primary_context)| *is_primary_context)
+        .map(|(entity, ..)| entity)
+        .min_by_key(|entity| entity.index())
+        .or_else(|| {
+            contexts
+                .iter()
+                .find_map(|(entity, is_primary_window, _)| is_primary_window.then_some(entity))
+        })
+}
+
+/// Updates the [`EguiFocusedContext`], and syncs the modifier keys state when the focus changes.
+///
+/// Returns the windows that lost focus.
+pub(super) fn process_window_focused(
+    ev_window_focused: &mut EventReader<WindowFocused>,
+    input_resources: &mut InputResources,
+) -> Vec<Entity> {
+    let mut focused_context = input_resources.focused_context.0;
+    let mut focus_changed = false;
+    let mut unfocused_windows = Vec::new();
+    for event in ev_window_focused.read() {
+        focus_changed = true;
+        if event.focused {
+            focused_context = Some(event.window);
+        } else {
+            unfocused_windows.push(event.window);
+            if focused_context == Some(event.window) {
+                focused_context = None;
+            }
+        }
+    }
+    input_resources
+        .focused_context
+        .set_if_neq(EguiFocusedContext(focused_context));
+
+    // Keys pressed or released while the window was unfocused don't produce keyboard events,
+    // `ButtonInput` is the best guess for the state of modifiers when the focus changes.
+    if focus_changed {
+        let keys = &*input_resources.keys;
+        input_resources
+            .modifier_keys_state
+            .set_from_button_input(keys);
+    }
+    unfocused_windows
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        test_utils::{input_test_app, CapturedEvents},
+        EguiContexts, EguiWantsInput,
+    };
+    use bevy::{
+        app::{App, Update},
+        ecs::system::ResMut,
+        input::{
+            mouse::{MouseButton, MouseButtonInput},
+            ButtonState,
+        },
+        math::Vec2,
+        window::{CursorMoved, ReceivedCharacter},
+    };
+
+    #[test]
+    fn test_text_input_focus_handoff() {
+        #[derive(Resource, Default)]
+        struct Text(String);
+
+        fn ui_system(mut text: ResMut<Text>, mut contexts: EguiContexts) {
+            egui::CentralPanel::default().show(contexts.ctx_mut(), |ui| {
+                ui.text_edit_singleline(&mut text.0);
+            });
+        }
+
+        fn focus_text_edit(app: &mut App, window: Entity) {
+            app.world.send_event(CursorMoved {
+                window,
+                position: Vec2::new(20.0, 15.0),
+                delta: None,
+            });
+            for state in [ButtonState::Pressed, ButtonState::Released] {
+                app.world.send_event(MouseButtonInput {
+                    button: MouseButton::Left,
+                    state,
+                    window,
+                });
+                app.update();
+            }
+        }
+
+        fn type_char(app: &mut App, window: Entity, char: &str) {
+            app.world.send_event(ReceivedCharacter {
+                window,
+                char: char.into(),
+            });
+            app.update();
+        }
+
+        let (mut app, window) = input_test_app();
+        app.init_resource::<Text>().add_systems(Update, ui_system);
+        app.update();
+        assert_eq!(
+            *app.world.resource::<TextInputFocus>(),
+            TextInputFocus(None)
+        );
+
+        // Egui claims the focus once the text field is focused.
+        focus_text_edit(&mut app, window);
+        type_char(&mut app, window, "a");
+        assert_eq!(
+            *app.world.resource::<TextInputFocus>(),
+            TextInputFocus(Some(TextInputOwner::Egui(window)))
+        );
+        assert_eq!(app.world.resource::<Text>().0, "a");
+
+        // Another text input takes the focus, the text typed during the same frame isn't fed to Egui,
+        // and the text field loses focus.
+        app.world
+            .insert_resource(TextInputFocus(Some(TextInputOwner::Other("chat"))));
+        type_char(&mut app, window, "b");
+        assert!(app.world.resource::<CapturedEvents>().0.is_empty());
+        assert_eq!(app.world.resource::<Text>().0, "a");
+        assert!(!app.world.resource::<EguiWantsInput>().wants_keyboard_input);
+        assert_eq!(
+            *app.world.resource::<TextInputFocus>(),
+            TextInputFocus(Some(TextInputOwner::Other("chat")))
+        );
+
+        // Focusing the text field doesn't clobber the external focus.
+        focus_text_edit(&mut app, window);
+        assert_eq!(
+            *app.world.resource::<TextInputFocus>(),
+            TextInputFocus(Some(TextInputOwner::Other("chat")))
+        );
+
+        // Once released, Egui claims the focus, as the text field was focused by the click.
+        app.world.insert_resource(TextInputFocus(None));
+        type_char(&mut app, window, "c");
+        assert_eq!(
+            *app.world.resource::<TextInputFocus>(),
+            TextInputFocus(Some(TextInputOwner::Egui(window)))
+        );
+        // Egui selects the existing text when the field regains focus.
+        assert_eq!(app.world.resource::<Text>().0, "c");
+    }
+}
@@ -0,0 +1,372 @@
+//! Keyboard input: keys, modifiers, text and clipboard shortcuts.
+
+use super::{ContextSystemParams, InputResources};
+use bevy::{
+    ecs::event::EventReader,
+    input::{
+        keyboard::{Key, KeyCode, KeyboardInput},
+        ButtonInput,
+    },
+    prelude::Resource,
+    window::ReceivedCharacter,
+};
+
+/// Stores "pressed" state of modifier keys.
+/// Will be removed if Bevy adds support for `ButtonInput<Key>` (logical keys).
+///
+/// The state is tracked from logical keys of [`KeyboardInput`] events, so that modifiers remapped
+/// by keyboard layouts work. As the events of keys pressed or released while a window is unfocused
+/// are missed, the state is synced with the [`ButtonInput<KeyCode>`] resource every time a window
+/// gains or loses focus.
+#[derive(Resource, Default, Clone, Copy, Debug)]
+pub struct ModifierKeysState {
+    shift: bool,
+    ctrl: bool,
+    alt: bool,
+    win: bool,
+}
+
+impl ModifierKeysState {
+    /// Sets the state from pressed physical keys.
+    ///
+    /// Can be used when injecting synthetic input, as the state isn't synced with [`ButtonInput`]
+    /// every frame.
+    pub fn set_from_button_input(&mut self, keys: &ButtonInput<KeyCode>) {
+        self.shift = keys.any_pressed([KeyCode::ShiftLeft, KeyCode::ShiftRight]);
+        self.ctrl = keys.any_pressed([KeyCode::ControlLeft, KeyCode::ControlRight]);
+        self.alt = keys.any_pressed([KeyCode::AltLeft, KeyCode::AltRight]);
+        self.win = keys.any_pressed([KeyCode::SuperLeft, KeyCode::SuperRight, KeyCode::Meta]);
+    }
+
+    /// Converts the state into Egui modifiers. On macOS, the Command key is reported as the
+    /// "Super" one by Bevy, and Egui treats it as [`egui::Modifiers::command`] instead of Control.
+    pub(super) fn to_egui_modifiers(self, is_macos: bool) -> egui::Modifiers {
+        egui::Modifiers {
+            alt: self.alt,
+            ctrl: self.ctrl,
+            shift: self.shift,
+            mac_cmd: is_macos && self.win,
+            command: if is_macos { self.win } else { self.ctrl },
+        }
+    }
+}
+
+/// Updates the modifier keys state, returns the read events to be fed to Egui contexts later.
+pub(super) fn update_modifier_keys(
+    ev_keyboard_input: &mut EventReader<KeyboardInput>,
+    modifier_keys_state: &mut ModifierKeysState,
+) -> Vec<KeyboardInput> {
+    let mut keyboard_input_events = Vec::new();
+    for event in ev_keyboard_input.read() {
+        // Copy the events as we might want to pass them to an Egui context later.
+        keyboard_input_events.push(event.clone());
+
+        let KeyboardInput {
+            logical_key, state, ..
+        } = event;
+        match logical_key {
+            Key::Shift => {
+                modifier_keys_state.shift = state.is_pressed();
+            }
+            Key::Control => {
+                modifier_keys_state.ctrl = state.is_pressed();
+            }
+            Key::Alt => {
+                modifier_keys_state.alt = state.is_pressed();
+            }
+            Key::Super | Key::Meta => {
+                modifier_keys_state.win = state.is_pressed();
+            }
+            _ => {}
+        };
+    }
+    keyboard_input_events
+}
+
+/// Feeds the typed text, unless it's a shortcut.
+pub(super) fn process_received_characters(
+    ev_received_character: &mut EventReader<ReceivedCharacter>,
+    input_resources: &mut InputResources,
+    context_params: &mut ContextSystemParams,
+    modifiers: egui::Modifiers,
+) {
+    let egui::Modifiers {
+        alt, ctrl, command, ..
+    } = modifiers;
+    let win = input_resources.modifier_keys_state.win;
+    let pending_events = &mut input_resources.pending_events.received_character;
+
+    let received_characters: Vec<_> = pending_events
+        .take_with(ev_received_character.read())
+        .collect();
+    if !command && !win || !*context_params.is_macos && ctrl && alt {
+        for (event, retries) in received_characters {
+            let Some(mut window_context) = context_params.window_context_or_defer(
+                event.window,
+                &event,
+                retries,
+                pending_events,
+            ) else {
+                continue;
+            };
+
+            if event.char.matches(char::is_control).count() == 0 {
+                window_context
+                    .egui_input
+                    .events
+                    .push(egui::Event::Text(event.char.to_string()));
+            }
+        }
+    }
+}
+
+/// Feeds key presses and releases, along with the copy, cut and paste shortcuts.
+pub(super) fn process_keyboard_input(
+    keyboard_input_events: &[KeyboardInput],
+    input_resources: &mut InputResources,
+    context_params: &mut ContextSystemParams,
+    modifiers: egui::Modifiers,
+) {
+    #[cfg(all(
+        feature = "manage_clipboard",
+        not(target_os = "android"),
+        not(target_arch = "wasm32")
+    ))]
+    let command = modifiers.command;
+    let pending_events = &mut input_resources.pending_events.keyboard_input;
+
+    // Modifier keys of the pending events have already been applied during the previous frames.
+    for (event, retries) in pending_events.take_with(keyboard_input_events.iter()) {
+        let Some(mut window_context) =
+            context_params.window_context_or_defer(event.window, &event, retries, pending_events)
+        else {
+            continue;
+        };
+
+        let (Some(key), physical_key) = (
+            bevy_to_egui_key(&event.logical_key),
+            bevy_to_egui_physical_key(&event.key_code),
+        ) else {
+            continue;
+        };
+
+        let egui_event = egui::Event::Key {
+            key,
+            pressed: event.state.is_pressed(),
+            repeat: false,
+            modifiers,
+            physical_key,
+        };
+        window_context.egui_input.events.push(egui_event);
+
+        // We also check that it's an `ButtonState::Pressed` event, as we don't want to
+        // copy, cut or paste on the key release.
+        #[cfg(all(
+            feature = "manage_clipboard",
+            not(target_os = "android"),
+            not(target_arch = "wasm32")
+        ))]
+        if command && event.state.is_pressed() {
+            match key {
+                egui::Key::C => {
+                    window_context.egui_input.events.push(egui::Event::Copy);
+                }
+                egui::Key::X => {
+                    window_context.egui_input.events.push(egui::Event::Cut);
+                }
+                egui::Key::V => {
+                    if let Some(contents) = input_resources.egui_clipboard.get_contents() {
+                        window_context
+                            .egui_input
+                            .events
+                            .push(egui::Event::Text(contents))
+                    }
+                }
+                _ => {}
+            }
+        }
+    }
+}
+
+/// Feeds the copy, cut and paste events of the browser.
+#[cfg(all(
+    feature = "manage_clipboard",
+    target_arch = "wasm32",
+    web_sys_unstable_apis
+))]
+pub(super) fn process_web_clipboard_events(
+    input_resources: &mut InputResources,
+    context_params: &mut ContextSystemParams,
+) {
+    while let Some(event) = input_resources.egui_clipboard.try_receive_clipboard_event() {
+        // In web, we assume that we have only 1 window per app.
+        let mut window_context = context_params.contexts.single_mut();
+
+        match event {
+            crate::web_clipboard::WebClipboardEvent::Copy => {
+                window_context.egui_input.events.push(egui::Event::Copy);
+            }
+            crate::web_clipboard::WebClipboardEvent::Cut => {
+                window_context.egui_input.events.push(egui::Event::Cut);
+            }
+            crate::web_clipboard::WebClipboardEvent::Paste(contents) => {
+                input_resources
+                    .egui_clipboard
+                    .set_contents_internal(&contents);
+                window_context
+                    .egui_input
+                    .events
+                    .push(egui::Event::Text(contents))
+            }
+        }
+    }
+}
+
+/// Matches the implementation of <https://github.com/emilk/egui/blob/68b3ef7f6badfe893d3bbb1f791b481069d807d9/crates/egui-winit/src/lib.rs#L1005>.
+pub fn bevy_to_egui_key(key: &Key) -> Option<egui::Key> {
+    let key = match key {
+        Key::Character(str) => return egui::Key::from_name(str.as_str()),
+        Key::Unidentified(_) | Key::Dead(_) => return None,
+
+        Key::Enter => egui::Key::Enter,
+        Key::Tab => egui::Key::Tab,
+        Key::Space => egui::Key::Space,
+        Key::ArrowDown => egui::Key::ArrowDown,
+        Key::ArrowLeft => egui::Key::ArrowLeft,
+        Key::ArrowRight => egui::Key::ArrowRight,
+        Key::ArrowUp => egui::Key::ArrowUp,
+        Key::End => egui::Key::End,
+        Key::Home => egui::Key::Home,
+        Key::PageDown => egui::Key::PageDown,
+        Key::PageUp => egui::Key::PageUp,
+        Key::Backspace => egui::Key::Backspace,
+        Key::Delete => egui::Key::Delete,
+        Key::Insert => egui::Key::Insert,
+        Key::Escape => egui::Key::Escape,
+        Key::F1 => egui::Key::F1,
+        Key::F2 => egui::Key::F2,
+        Key::F3 => egui::Key::F3,
+        Key::F4 => egui::Key::F4,
+        Key::F5 => egui::Key::F5,
+        Key::F6 => egui::Key::F6,
+        Key::F7 => egui::Key::F7,
+        Key::F8 => egui::Key::F8,
+        Key::F9 => egui::Key::F9,
+        Key::F10 => egui::Key::F10,
+        Key::F11 => egui::Key::F11,
+        Key::F12 => egui::Key::F12,
+        Key::F13 => egui::Key::F13,
+        Key::F14 => egui::Key::F14,
+        Key::F15 => egui::Key::F15,
+        Key::F16 => egui::Key::F16,
+        Key::F17 => egui::Key::F17,
+        Key::F18 => egui::Key::F18,
+        Key::F19 => egui::Key::F19,
+        Key::F20 => egui::Key::F20,
+
+        _ => return None,
+    };
+    Some(key)
+}
+
+/// Matches the implementation of <https://github.com/emilk/egui/blob/68b3ef7f6badfe893d3bbb1f791b481069d807d9/crates/egui-winit/src/lib.rs#L1080>.
+pub fn bevy_to_egui_physical_key(key: &KeyCode) -> Option<egui::Key> {
+    let key = match key {
+        KeyCode::ArrowDown => egui::Key::ArrowDown,
+        KeyCode::ArrowLeft => egui::Key::ArrowLeft,
+        KeyCode::ArrowRight => egui::Key::ArrowRight,
+        KeyCode::ArrowUp => egui::Key::ArrowUp,
+
+        KeyCode::Escape => egui::Key::Escape,
+        KeyCode::Tab => egui::Key::Tab,
+        KeyCode::Backspace => egui::Key::Backspace,
+        KeyCode::Enter | KeyCode::NumpadEnter => egui::Key::Enter,
+
+        KeyCode::Insert => egui::Key::Insert,
+        KeyCode::Delete => egui::Key::Delete,
+        KeyCode::Home => egui::Key::Home,
+        KeyCode::End => egui::Key::End,
+        KeyCode::PageUp => egui::Key::PageUp,
+        KeyCode::PageDown => egui::Key::PageDown,
+
+        // Punctuation
+        KeyCode::Space => egui::Key::Space,
+        KeyCode::Comma => egui::Key::Comma,
+        KeyCode::Period => egui::Key::Period,
+        // KeyCode::Colon => egui::Key::Colon, // NOTE: there is no physical colon key on an american keyboard
+        KeyCode::Semicolon => egui::Key::Semicolon,
+        KeyCode::Backslash => egui::Key::Backslash,
+        KeyCode::Slash | KeyCode::NumpadDivide => egui::Key::Slash,
+        KeyCode::BracketLeft => egui::Key::OpenBracket,
+        KeyCode::BracketRight => egui::Key::CloseBracket,
+        KeyCode::Backquote => egui::Key::Backtick,
+
+        KeyCode::Cut => egui::Key::Cut,
+        KeyCode::Copy => egui::Key::Copy,
+        KeyCode::Paste => egui::Key::Paste,
+        KeyCode::Minus | KeyCode::NumpadSubtract => egui::Key::Minus,
+        KeyCode::NumpadAdd => egui::Key::Plus,
+        KeyCode::Equal => egui::Key::Equals,
+
+        KeyCode::Digit0 | KeyCode::Numpad0 => egui::Key::Num0,
+        KeyCode::Digit1 | KeyCode::Numpad1 => egui::Key::Num1,
+        KeyCode::Digit2 | KeyCode::Numpad2 => egui::Key::Num2,
+        KeyCode::Digit3 | KeyCode::Numpad3 => egui::Key::Num3,
+        KeyCode::Digit4 | KeyCode::Numpad4 => egui::Key::Num4,
+        KeyCode::Digit5 | KeyCode::Numpad5 => egui::Key::Num5,
+        KeyCode::Digit6 | KeyCode::Numpad6 => egui::Key::Num6,
+        KeyCode::Digit7 | KeyCode::Numpad7 => egui::Key::Num7,
+        KeyCode::Digit8 | KeyCode::Numpad8 => egui::Key::Num8,
+        KeyCode::Digit9 | KeyCode::Numpad9 => egui::Key::Num9,
+
+        KeyCode::KeyA => egui::Key::A,
+        KeyCode::KeyB => egui::Key::B,
+        KeyCode::KeyC => egui::Key::C,
+        KeyCode::KeyD => egui::Key::D,
+        KeyCode::KeyE => egui::Key::E,
+        KeyCode::KeyF => egui::Key::F,
+        KeyCode::KeyG => egui::Key::G,
+        KeyCode::KeyH => egui::Key::H,
+        KeyCode::KeyI => egui::Key::I,
+        KeyCode::KeyJ => egui::Key::J,
+        KeyCode::KeyK => egui::Key::K,
+        KeyCode::KeyL => egui::Key::L,
+        KeyCode::KeyM => egui::Key::M,
+        KeyCode::KeyN => egui::Key::N,
+        KeyCode::KeyO => egui::Key::O,
+        KeyCode::KeyP => egui::Key::P,
+        KeyCode::KeyQ => egui::Key::Q,
+        KeyCode::KeyR => egui::Key::R,
+        KeyCode::KeyS => egui::Key::S,
+        KeyCode::KeyT => egui::Key::T,
+        KeyCode::KeyU => egui::Key::U,
+        KeyCode::KeyV => egui::Key::V,
+        KeyCode::KeyW => egui::Key::W,
+        KeyCode::KeyX => egui::Key::X,
+        KeyCode::KeyY => egui::Key::Y,
+        KeyCode::KeyZ => egui::Key::Z,
+
+        KeyCode::F1 => egui::Key::F1,
+        KeyCode::F2 => egui::Key::F2,
+        KeyCode::F3 => egui::Key::F3,
+        KeyCode::F4 => egui::Key::F4,
+        KeyCode::F5 => egui::Key::F5,
+        KeyCode::F6 => egui::Key::F6,
+        KeyCode::F7 => egui::Key::F7,
+        KeyCode::F8 => egui::Key::F8,
+        KeyCode::F9 => egui::Key::F9,
+        KeyCode::F10 => egui::Key::F10,
+        KeyCode::F11 => egui::Key::F11,
+        KeyCode::F12 => egui::Key::F12,
+        KeyCode::F13 => egui::Key::F13,
+        KeyCode::F14 => egui::Key::F14,
+        KeyCode::F15 => egui::Key::F15,
+        KeyCode::F16 => egui::Key::F16,
+        KeyCode::F17 => egui::Key::F17,
+        KeyCode::F18 => egui::Key::F18,
+        KeyCode::F19 => egui::Key::F19,
+        KeyCode::F20 => egui::Key::F20,
+        _ => return None,
+    };
+    Some(key)
+}
@@ -0,0 +1,1287 @@
+//! The built-in input is read by [`process_input_system`], which delegates to the submodules
+//! handling the individual devices. Other input devices can be plugged in by implementing
+//! [`EguiInputSource`] and registering it with [`EguiInputSourceAppExt::add_egui_input_source`].
+//! Events can be remapped or dropped before they reach Egui with [`EguiInputFilters`], or
+//! suppressed for a context with [`EguiInputSuppressed`].
+
+pub mod capture;
+pub mod focus;
+pub mod keyboard;
+pub mod pointer;
+pub mod touch;
+
+pub use capture::{CapturedButton, CapturedInput, EguiInputCapture};
+pub use focus::{EguiFocusedContext, TextInputFocus, TextInputOwner};
+pub use keyboard::{bevy_to_egui_key, bevy_to_egui_physical_key, ModifierKeysState};
+pub use pointer::{EguiPointerEvent, EguiPointerOverride};
+
+use crate::{
+    EguiContext, EguiContextQuery, EguiContextQueryItem, EguiInput, EguiSet, EguiSettings,
+    EguiTimeSource,
+};
+use bevy::{
+    app::App,
+    ecs::{
+        change_detection::DetectChangesMut,
+        component::Component,
+        event::{Event, EventReader, EventWriter},
+        query::QueryEntityError,
+        schedule::{InternedScheduleLabel, IntoSystemConfigs, IntoSystemSetConfigs, SystemSet},
+        system::{Local, Res, ResMut, Resource, StaticSystemParam, SystemParam, SystemParamItem},
+    },
+    input::{
+        gamepad::GamepadButtonInput,
+        keyboard::{KeyCode, KeyboardInput},
+        mouse::{MouseButtonInput, MouseMotion, MouseWheel},
+        touch::TouchInput,
+        ButtonInput,
+    },
+    log,
+    prelude::{Entity, Query, ReflectComponent, ReflectDefault, Time},
+    reflect::Reflect,
+    time::{Fixed, Real, Virtual},
+    utils::HashMap,
+    window::{CursorMoved, ReceivedCharacter, WindowFocused},
+};
+use std::marker::PhantomData;
+
+#[allow(missing_docs)]
+#[derive(SystemParam)]
+// IMPORTANT: remember to add the logic to clear event readers to the `clear` method.
+pub struct InputEvents<'w, 's> {
+    pub ev_cursor: EventReader<'w, 's, CursorMoved>,
+    pub ev_mouse_button_input: EventReader<'w, 's, MouseButtonInput>,
+    pub ev_pointer: EventReader<'w, 's, EguiPointerEvent>,
+    pub ev_mouse_motion: EventReader<'w, 's, MouseMotion>,
+    pub ev_mouse_wheel: EventReader<'w, 's, MouseWheel>,
+    pub ev_received_character: EventReader<'w, 's, ReceivedCharacter>,
+    pub ev_keyboard_input: EventReader<'w, 's, KeyboardInput>,
+    pub ev_touch: EventReader<'w, 's, TouchInput>,
+    pub ev_window_focused: EventReader<'w, 's, WindowFocused>,
+    pub ev_gamepad_button: EventReader<'w, 's, GamepadButtonInput>,
+}
+
+impl<'w, 's> InputEvents<'w, 's> {
+    /// Consumes all the events.
+    pub fn clear(&mut self) {
+        self.ev_cursor.read().last();
+        self.ev_mouse_button_input.read().last();
+        self.ev_pointer.read().last();
+        self.ev_mouse_motion.read().last();
+        self.ev_mouse_wheel.read().last();
+        self.ev_received_character.read().last();
+        self.ev_keyboard_input.read().last();
+        self.ev_touch.read().last();
+        self.ev_window_focused.read().last();
+        self.ev_gamepad_button.read().last();
+    }
+}
+
+/// How many frames an input event is kept for while waiting for the Egui context of its window.
+const MAX_PENDING_EVENT_RETRIES: u32 = 2;
+/// How many input events of each kind can be waiting for Egui contexts at once.
+const MAX_PENDING_EVENTS: usize = 256;
+
+/// Input events that targeted windows without an Egui context (for example, the ones created
+/// during the same frame), to be retried during the next frames.
+#[derive(Default)]
+pub struct PendingInputEvents {
+    pointer: PendingEvents<EguiPointerEvent>,
+    mouse_wheel: PendingEvents<MouseWheel>,
+    received_character: PendingEvents<ReceivedCharacter>,
+    keyboard_input: PendingEvents<KeyboardInput>,
+    touch: PendingEvents<TouchInput>,
+}
+
+/// Events of a single kind along with the number of times they were retried.
+struct PendingEvents<E>(Vec<(E, u32)>);
+
+impl<E> Default for PendingEvents<E> {
+    fn default() -> Self {
+        Self(Vec::new())
+    }
+}
+
+impl<E: Clone> PendingEvents<E> {
+    /// Takes the pending events and chains them with the new ones.
+    fn take_with<'a>(
+        &mut self,
+        new_events: impl Iterator<Item = &'a E> + 'a,
+    ) -> impl Iterator<Item = (E, u32)> + 'a
+    where
+        E: 'a,
+    {
+        std::mem::take(&mut self.0)
+            .into_iter()
+            .chain(new_events.map(|event| (event.clone(), 0)))
+    }
+
+    /// Same as [`Self::take_with`], for new events that are already owned.
+    fn take_chained(
+        &mut self,
+        new_events: impl Iterator<Item = E>,
+    ) -> impl Iterator<Item = (E, u32)> {
+        std::mem::take(&mut self.0)
+            .into_iter()
+            .chain(new_events.map(|event| (event, 0)))
+    }
+}
+
+#[allow(missing_docs)]
+#[derive(SystemParam)]
+pub struct InputResources<'w, 's> {
+    #[cfg(all(
+        feature = "manage_clipboard",
+        not(target_os = "android"),
+        not(all(target_arch = "wasm32", not(web_sys_unstable_apis)))
+    ))]
+    pub egui_clipboard: bevy::ecs::system::ResMut<'w, crate::EguiClipboard>,
+    pub modifier_keys_state: ResMut<'w, ModifierKeysState>,
+    pub keys: Res<'w, ButtonInput<KeyCode>>,
+    pub focused_context: ResMut<'w, EguiFocusedContext>,
+    pub text_input_focus: Res<'w, TextInputFocus>,
+    pub input_capture: ResMut<'w, EguiInputCapture>,
+    pub pending_events: Local<'s, PendingInputEvents>,
+    #[system_param(ignore)]
+    _marker: PhantomData<&'w ()>,
+}
+
+#[allow(missing_docs)]
+#[derive(SystemParam)]
+pub struct ContextSystemParams<'w, 's> {
+    pub contexts: Query<'w, 's, EguiContextQuery>,
+    pub is_macos: Local<'s, bool>,
+    pub routing_diagnostics: ResMut<'w, EguiRoutingDiagnostics>,
+    #[system_param(ignore)]
+    _marker: PhantomData<&'s ()>,
+}
+
+impl<'w, 's> ContextSystemParams<'w, 's> {
+    /// Same as [`Self::window_context`], but if the window doesn't have a context (yet), the event
+    /// gets stored to be retried during the next frame instead of being dropped.
+    fn window_context_or_defer<E: Clone>(
+        &mut self,
+        window: Entity,
+        event: &E,
+        retries: u32,
+        pending_events: &mut PendingEvents<E>,
+    ) -> Option<EguiContextQueryItem<'_>> {
+        if !self.contexts.contains(window)
+            && retries < MAX_PENDING_EVENT_RETRIES
+            && pending_events.0.len() < MAX_PENDING_EVENTS
+        {
+            log::debug!("An Egui context for a window ({window:?}) doesn't exist yet, retrying the event during the next frame");
+            pending_events.0.push((event.clone(), retries + 1));
+            return None;
+        }
+        self.window_context::<E>(window)
+    }
+
+    fn window_context<E>(&mut self, window: Entity) -> Option<EguiContextQueryItem<'_>> {
+        match self.contexts.get_mut(window) {
+            Ok(context) => Some(context),
+            Err(err @ QueryEntityError::AliasedMutability(_)) => {
+                panic!("Failed to get an Egui context for a window ({window:?}): {err:?}");
+            }
+            Err(
+                err @ QueryEntityError::NoSuchEntity(_)
+                | err @ QueryEntityError::QueryDoesNotMatch(_),
+            ) => {
+                let event_type = std::any::type_name::<E>();
+                let event_type = event_type.rsplit("::").next().unwrap_or(event_type);
+                let message =
+                    format!("Failed to get an Egui context for a window ({window:?}): {err:?}");
+                if !self.routing_diagnostics.strict {
+                    log::error!("{message}");
+                }
+                self.routing_diagnostics.record_drop(
+                    EguiDroppedEvent {
+                        context: window,
+                        event_type,
+                    },
+                    &message,
+                );
+                None
+            }
+        }
+    }
+}
+
+/// Processes Bevy input and feeds it to Egui.
+pub fn process_input_system(
+    mut input_events: InputEvents,
+    mut input_resources: InputResources,
+    mut context_params: ContextSystemParams,
+    egui_settings: Res<EguiSettings>,
+    time: Res<Time<Real>>,
+    virtual_time: Res<Time<Virtual>>,
+    fixed_time: Res<Time<Fixed>>,
+) {
+    // Test whether it's macOS or OS X.
+    use std::sync::Once;
+    static START: Once = Once::new();
+    START.call_once(|| {
+        // The default for WASM is `false` since the `target_os` is `unknown`.
+        *context_params.is_macos = cfg!(target_os = "macos");
+
+        #[cfg(target_arch = "wasm32")]
+        if let Some(window) = web_sys::window() {
+            let nav = window.navigator();
+            if let Ok(user_agent) = nav.user_agent() {
+                if user_agent.to_ascii_lowercase().contains("mac") {
+                    *context_params.is_macos = true;
+                }
+            }
+        }
+    });
+
+    let routing_diagnostics = &mut *context_params.routing_diagnostics;
+    routing_diagnostics.delivered.clear();
+    routing_diagnostics.dropped.clear();
+    routing_diagnostics.stale_focus = None;
+    routing_diagnostics.strict = egui_settings.strict_input_routing;
+
+    let unfocused_windows =
+        focus::process_window_focused(&mut input_events.ev_window_focused, &mut input_resources);
+    if let Some(focused) = input_resources.focused_context.0 {
+        if !context_params.contexts.contains(focused) {
+            input_resources.focused_context.0 = None;
+            // Closing the focused window gets here, it isn't a routing mistake.
+            context_params.routing_diagnostics.stale_focus = Some(focused);
+            log::debug!(
+                "The focused context ({focused:?}) doesn't exist anymore, clearing `EguiFocusedContext`"
+            );
+        }
+    }
+    touch::process_unfocused_windows(&unfocused_windows, &mut context_params);
+
+    let mut keyboard_input_events = keyboard::update_modifier_keys(
+        &mut input_events.ev_keyboard_input,
+        &mut input_resources.modifier_keys_state,
+    );
+    let modifiers = input_resources
+        .modifier_keys_state
+        .to_egui_modifiers(*context_params.is_macos);
+
+    // Text typed while capturing isn't fed, and captured presses are removed before reaching
+    // the contexts.
+    let capturing_input = input_resources.input_capture.active;
+    let input_capture = &mut input_resources.input_capture;
+    keyboard_input_events.retain(|event| {
+        !input_capture.withholds(
+            capture::CapturedButton::Key(event.key_code),
+            event.state.is_pressed(),
+            modifiers,
+        )
+    });
+    for event in input_events.ev_gamepad_button.read() {
+        input_capture.withholds(
+            capture::CapturedButton::Gamepad(event.button),
+            event.state.is_pressed(),
+            modifiers,
+        );
+    }
+
+    if egui_settings.write_mouse_motion_events {
+        pointer::process_mouse_motion(
+            &mut input_events.ev_mouse_motion,
+            &mut context_params,
+            input_resources.focused_context.0,
+        );
+    }
+    pointer::process_pointer_events(
+        &mut input_events.ev_cursor,
+        &mut input_events.ev_mouse_button_input,
+        &mut input_events.ev_pointer,
+        &mut input_resources.pending_events.pointer,
+        &mut context_params,
+        &mut input_resources.input_capture,
+        &egui_settings,
+        modifiers,
+    );
+    pointer::process_mouse_wheel(
+        &mut input_events.ev_mouse_wheel,
+        &mut input_resources.pending_events.mouse_wheel,
+        &mut context_params,
+        &egui_settings,
+        modifiers,
+    );
+
+    if egui_settings.respect_external_text_focus && input_resources.text_input_focus.is_external() {
+        // Text and keys go to the text input of another plugin, the events are cleared below.
+        let pending_events = &mut input_resources.pending_events;
+        pending_events.received_character.0.clear();
+        pending_events.keyboard_input.0.clear();
+    } else {
+        if capturing_input {
+            // The events are cleared below.
+            input_resources.pending_events.received_character.0.clear();
+        } else {
+            keyboard::process_received_characters(
+                &mut input_events.ev_received_character,
+                &mut input_resources,
+                &mut context_params,
+                modifiers,
+            );
+        }
+        keyboard::process_keyboard_input(
+            &keyboard_input_events,
+            &mut input_resources,
+            &mut context_params,
+            modifiers,
+        );
+    }
+    #[cfg(all(
+        feature = "manage_clipboard",
+        target_arch = "wasm32",
+        web_sys_unstable_apis
+    ))]
+    keyboard::process_web_clipboard_events(&mut input_resources, &mut context_params);
+
+    touch::process_touch_input(
+        &mut input_events.ev_touch,
+        &mut input_resources.pending_events.touch,
+        &mut context_params,
+        &egui_settings,
+        &time,
+        modifiers,
+    );
+    touch::process_touch_long_presses(&mut context_params, &time, modifiers);
+
+    for mut context in context_params.contexts.iter_mut() {
+        context.egui_input.modifiers = modifiers;
+        context.egui_input.time = match context.time_source.copied().unwrap_or_default() {
+            EguiTimeSource::Real => Some(time.elapsed_seconds_f64()),
+            EguiTimeSource::Virtual => Some(virtual_time.elapsed_seconds_f64()),
+            EguiTimeSource::Fixed => Some(fixed_time.elapsed_seconds_f64()),
+            EguiTimeSource::Custom => context.custom_time.map(|time| time.0),
+        };
+    }
+
+    // In some cases, we may skip certain events. For example, we ignore `ReceivedCharacter` events
+    // when alt or ctrl button is pressed. We still want to clear event buffer.
+    input_events.clear();
+}
+
+/// An Egui event for a context.
+///
+/// The events are written into the [`EguiInput`] of the contexts during
+/// [`EguiInputSet::WriteEguiEvents`], after the ones of the built-in input. Custom input can be
+/// fed either by sending the events directly or by registering an [`EguiInputSource`].
+#[derive(Event, Clone, Debug, PartialEq)]
+pub struct EguiInputEvent {
+    /// Entity of the context (a window) the event is for.
+    pub context: Entity,
+    /// The event.
+    pub event: egui::Event,
+    /// What produced the event, see [`EguiLastUserActivity`].
+    pub source: EguiInputEventSource,
+}
+
+impl EguiInputEvent {
+    /// Creates an event produced by the user, its source is picked with
+    /// [`EguiInputEventSource::of_user_event`].
+    pub fn new(context: Entity, event: egui::Event) -> Self {
+        let source = EguiInputEventSource::of_user_event(&event);
+        Self {
+            context,
+            event,
+            source,
+        }
+    }
+}
+
+/// What produced an [`EguiInputEvent`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum EguiInputEventSource {
+    /// The player moved or pressed a pointer (mouse, pen, etc).
+    UserPointer,
+    /// The player typed.
+    UserKeyboard,
+    /// The player touched the screen.
+    UserTouch,
+    /// The event was generated by code (the pointer leaving after the input got suppressed, for
+    /// example), it doesn't count as user activity.
+    Synthetic,
+}
+
+impl EguiInputEventSource {
+    /// Returns the source of an event produced by the user, [`EguiInputEventSource::Synthetic`]
+    /// for the events that the player can't produce directly ([`egui::Event::PointerGone`],
+    /// [`egui::Event::WindowFocused`], etc).
+    pub fn of_user_event(event: &egui::Event) -> Self {
+        use egui::Event;
+
+        match event {
+            Event::PointerMoved(_)
+            | Event::MouseMoved(_)
+            | Event::PointerButton { .. }
+            | Event::Scroll(_)
+            | Event::Zoom(_)
+            | Event::MouseWheel { .. } => Self::UserPointer,
+            Event::Touch { .. } => Self::UserTouch,
+            Event::Copy
+            | Event::Cut
+            | Event::Paste(_)
+            | Event::Text(_)
+            | Event::Key { .. }
+            | Event::CompositionStart
+            | Event::CompositionUpdate(_)
+            | Event::CompositionEnd(_) => Self::UserKeyboard,
+            _ => Self::Synthetic,
+        }
+    }
+
+    /// Returns `true` for the sources other than [`EguiInputEventSource::Synthetic`].
+    pub fn is_user(self) -> bool {
+        self != Self::Synthetic
+    }
+}
+
+/// The last time (elapsed since the startup, in real seconds) the player produced an input event
+/// for any context, to detect idling. Is updated during [`EguiInputSet::WriteEguiEvents`].
+///
+/// Only the events with a user [`EguiInputEventSource`] count. The built-in input is classified
+/// with [`EguiInputEventSource::of_user_event`], and the state written into the
+/// [`EguiInput`] every frame (time, focus, modifiers) never counts.
+#[derive(Resource, Clone, Copy, Debug, Default, PartialEq)]
+pub struct EguiLastUserActivity {
+    /// Time of the last activity, `0.0` until the first one.
+    pub time: f64,
+    /// The context the last activity was for, [`None`] until the first one.
+    pub context: Option<Entity>,
+}
+
+/// A run condition that is `true` if the player produced input for Egui during the last `secs`
+/// seconds (or the app started less than `secs` seconds ago), see [`EguiLastUserActivity`].
+///
+/// ```rust
+/// use bevy::prelude::*;
+/// use bevy_egui::input::egui_user_active_within;
+///
+/// # fn dim_screen_system() {}
+/// # fn build(app: &mut App) {
+/// app.add_systems(
+///     Update,
+///     dim_screen_system.run_if(not(egui_user_active_within(60.0))),
+/// );
+/// # }
+/// ```
+pub fn egui_user_active_within(
+    secs: f64,
+) -> impl FnMut(Res<EguiLastUserActivity>, Res<Time<Real>>) -> bool + Clone {
+    move |last_activity: Res<EguiLastUserActivity>, time: Res<Time<Real>>| {
+        time.elapsed_seconds_f64() - last_activity.time <= secs
+    }
+}
+
+/// The input processing sets, run as a part of [`EguiSet::ProcessInput`].
+#[derive(SystemSet, Clone, Hash, Debug, Eq, PartialEq)]
+pub enum EguiInputSet {
+    /// Reads Bevy input events: the built-in ones and the ones of [`EguiInputSource`]s.
+    ReadBevyEvents,
+    /// Writes [`EguiInputEvent`]s into the [`EguiInput`] of the contexts.
+    WriteEguiEvents,
+}
+
+/// A custom input device that feeds events to the focused Egui context.
+///
+/// The source is read every frame during [`EguiInputSet::ReadBevyEvents`] (after
+/// [`process_input_system`]), and its events are routed to the [`EguiFocusedContext`]. For example, a dial controller that scrolls the
+/// hovered Egui list:
+///
+/// ```rust
+/// use bevy::prelude::*;
+/// use bevy_egui::input::{EguiInputSource, EguiInputSourceAppExt};
+///
+/// #[derive(Resource, Default)]
+/// struct DialTurns(Vec<f32>);
+///
+/// struct DialSource;
+///
+/// impl EguiInputSource for DialSource {
+///     const NAME: &'static str = "dial";
+///     type Param = ResMut<'static, DialTurns>;
+///
+///     fn read(mut turns: ResMut<DialTurns>, events: &mut Vec<egui::Event>) {
+///         for angle in turns.0.drain(..) {
+///             events.push(egui::Event::Scroll(egui::vec2(0.0, angle * 10.0)));
+///         }
+///     }
+/// }
+///
+/// # fn build(app: &mut App) {
+/// app.init_resource::<DialTurns>()
+///     .add_egui_input_source::<DialSource>();
+/// # }
+/// ```
+pub trait EguiInputSource: Send + Sync + 'static {
+    /// Name of the source, which can be listed in [`EguiSettings::disabled_input_sources`] to
+    /// stop reading it.
+    const NAME: &'static str;
+    /// Parameters of the system that reads the source.
+    type Param: SystemParam + 'static;
+
+    /// Reads the input of the frame and pushes the Egui events for the focused context.
+    fn read(param: SystemParamItem<Self::Param>, events: &mut Vec<egui::Event>);
+}
+
+/// Registers [`EguiInputSource`]s.
+pub trait EguiInputSourceAppExt {
+    /// Adds a system reading the source during [`EguiInputSet::ReadBevyEvents`].
+    fn add_egui_input_source<S: EguiInputSource>(&mut self) -> &mut Self;
+}
+
+impl EguiInputSourceAppExt for App {
+    fn add_egui_input_source<S: EguiInputSource>(&mut self) -> &mut Self {
+        let schedule = crate::EguiSchedules::from_app(self).process_input;
+        self.add_systems(
+            schedule,
+            read_egui_input_source_system::<S>
+                .in_set(EguiInputSet::ReadBevyEvents)
+                // The focused context is updated by the built-in input processing.
+                .after(process_input_system)
+                .run_if(input_source_enabled::<S>),
+        )
+    }
+}
+
+fn input_source_enabled<S: EguiInputSource>(egui_settings: Res<EguiSettings>) -> bool {
+    !egui_settings
+        .disabled_input_sources
+        .iter()
+        .any(|name| name == S::NAME)
+}
+
+/// Reads an [`EguiInputSource`] and sends its events to the focused context.
+pub fn read_egui_input_source_system<S: EguiInputSource>(
+    param: StaticSystemParam<S::Param>,
+    focused_context: Res<EguiFocusedContext>,
+    primary_contexts: focus::PrimaryContextQuery,
+    mut input_events: EventWriter<EguiInputEvent>,
+    mut events: Local<Vec<egui::Event>>,
+) {
+    S::read(param.into_inner(), &mut events);
+    if events.is_empty() {
+        return;
+    }
+
+    let Some(context) = focused_context
+        .0
+        .or_else(|| focus::primary_context(&primary_contexts))
+    else {
+        log::debug!(
+            "No Egui context to send the events of the `{}` input source to",
+            S::NAME
+        );
+        events.clear();
+        return;
+    };
+    input_events.send_batch(
+        events
+            .drain(..)
+            .map(|event| EguiInputEvent::new(context, event)),
+    );
+}
+
+/// A function that remaps or drops an Egui event of a context, see [`EguiInputFilters`].
+pub type EguiInputFilter = Box<dyn Fn(Entity, egui::Event) -> Option<egui::Event> + Send + Sync>;
+
+/// Filters applied to every event before it reaches Egui, in the order they were added.
+///
+/// A filter gets the context entity and the event, and returns the event to pass to the next
+/// filter (modified or not), or [`None`] to drop it. The filters run during
+/// [`EguiInputSet::WriteEguiEvents`] over the events of the built-in input, the
+/// [`EguiInputEvent`]s, and anything else pushed into [`EguiInput`] before that. For example,
+/// swapping the primary and secondary buttons for left-handed users:
+///
+/// ```rust
+/// use bevy::prelude::*;
+/// use bevy_egui::input::EguiInputFilters;
+///
+/// fn setup(mut input_filters: ResMut<EguiInputFilters>) {
+///     input_filters.add(|_context, event| match event {
+///         egui::Event::PointerButton { pos, button, pressed, modifiers } => {
+///             let button = match button {
+///                 egui::PointerButton::Primary => egui::PointerButton::Secondary,
+///                 egui::PointerButton::Secondary => egui::PointerButton::Primary,
+///                 button => button,
+///             };
+///             Some(egui::Event::PointerButton { pos, button, pressed, modifiers })
+///         }
+///         event => Some(event),
+///     });
+/// }
+/// ```
+///
+/// Filters are called once per event (pointer moves included), so keep them cheap. Without any
+/// filters, events are written as is.
+#[derive(Resource, Default)]
+pub struct EguiInputFilters(pub Vec<EguiInputFilter>);
+
+impl EguiInputFilters {
+    /// Adds a filter after the existing ones.
+    pub fn add(
+        &mut self,
+        filter: impl Fn(Entity, egui::Event) -> Option<egui::Event> + Send + Sync + 'static,
+    ) -> &mut Self {
+        self.0.push(Box::new(filter));
+        self
+    }
+
+    /// Runs the event of the context through the filters.
+    pub fn apply(&self, context: Entity, event: egui::Event) -> Option<egui::Event> {
+        self.0
+            .iter()
+            .try_fold(event, |event, filter| filter(context, event))
+    }
+}
+
+/// Stops feeding pointer or keyboard events to a context, insert it on the window entity (while
+/// the player drags an item of the game over Egui panels, for example).
+///
+/// The events are dropped during [`EguiInputSet::WriteEguiEvents`], after the
+/// [`EguiInputFilters`]. Suppressing the pointer sends [`egui::Event::PointerGone`] to the
+/// context, so hovered widgets are released and [`EguiWantsInput`](crate::EguiWantsInput) lets
+/// the game see through the context. Lifting the suppression (or removing the component) moves
+/// the pointer back to the position of the cursor.
+#[derive(Component, Clone, Copy, Debug, Default, PartialEq, Eq, Reflect)]
+#[reflect(Component, Default)]
+pub struct EguiInputSuppressed {
+    /// Drops pointer moves, buttons, scrolling, zooming and touches.
+    pub pointer: bool,
+    /// Drops keys and text (IME composition and clipboard events included).
+    pub keyboard: bool,
+}
+
+impl EguiInputSuppressed {
+    /// Returns `true` if the event is dropped.
+    #[must_use]
+    pub fn suppresses(&self, event: &egui::Event) -> bool {
+        use egui::Event;
+
+        match event {
+            Event::PointerMoved(_)
+            | Event::MouseMoved(_)
+            | Event::PointerButton { .. }
+            | Event::PointerGone
+            | Event::Scroll(_)
+            | Event::Zoom(_)
+            | Event::Touch { .. } => self.pointer,
+            Event::Copy
+            | Event::Cut
+            | Event::Paste(_)
+            | Event::Text(_)
+            | Event::Key { .. }
+            | Event::CompositionStart
+            | Event::CompositionUpdate(_)
+            | Event::CompositionEnd(_) => self.keyboard,
+            _ => false,
+        }
+    }
+}
+
+/// Numbers of events accepted into and dropped from the [`EguiInput`] of the contexts during the
+/// last frame, see [`EguiSettings::max_events_per_frame`].
+#[derive(Resource, Clone, Debug, Default)]
+pub struct EguiInputStats {
+    contexts: HashMap<Entity, EguiContextInputStats>,
+    /// Time of the last overflow error logged for a context.
+    last_overflow_logs: HashMap<Entity, f64>,
+}
+
+impl EguiInputStats {
+    /// Returns the stats of a context, [`None`] if it didn't receive any events during the last
+    /// frame.
+    pub fn context(&self, context: Entity) -> Option<EguiContextInputStats> {
+        self.contexts.get(&context).copied()
+    }
+
+    /// Iterates over the stats of the contexts that received events during the last frame.
+    pub fn iter(&self) -> impl Iterator<Item = (Entity, EguiContextInputStats)> + '_ {
+        self.contexts
+            .iter()
+            .map(|(context, stats)| (*context, *stats))
+    }
+}
+
+/// Stats of a context, see [`EguiInputStats`].
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct EguiContextInputStats {
+    /// Events written into the [`EguiInput`] of the context.
+    pub accepted: usize,
+    /// Events dropped over [`EguiSettings::max_events_per_frame`].
+    pub dropped: usize,
+}
+
+/// Input routing of the current frame, to debug events that don't reach the intended contexts.
+/// Is reset during [`EguiInputSet::ReadBevyEvents`], and filled until the end of
+/// [`EguiInputSet::WriteEguiEvents`].
+///
+/// Bevy input events of windows without a context (once the retries for the windows created
+/// during the same frame run out) and [`EguiInputEvent`]s for missing contexts are dropped. With
+/// [`EguiSettings::strict_input_routing`], each drop panics in debug builds and is logged as an
+/// error in release builds. An [`EguiFocusedContext`] whose context doesn't exist anymore (the
+/// focused window got closed, for example) is cleared, and reported in
+/// [`EguiRoutingDiagnostics::stale_focus`] only.
+#[derive(Resource, Clone, Debug, Default)]
+pub struct EguiRoutingDiagnostics {
+    delivered: HashMap<Entity, usize>,
+    dropped: Vec<EguiDroppedEvent>,
+    stale_focus: Option<Entity>,
+    strict: bool,
+}
+
+/// An input event dropped because its context doesn't exist, see [`EguiRoutingDiagnostics`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct EguiDroppedEvent {
+    /// The entity the event was for.
+    pub context: Entity,
+    /// Name of the type of the event (`CursorMoved`, `EguiInputEvent`, etc).
+    pub event_type: &'static str,
+}
+
+impl EguiRoutingDiagnostics {
+    /// Returns the number of events written into the [`EguiInput`] of a context (after
+    /// [`EguiInputFilters`] and [`EguiSettings::max_events_per_frame`]).
+    pub fn delivered(&self, context: Entity) -> usize {
+        self.delivered.get(&context).copied().unwrap_or(0)
+    }
+
+    /// Iterates over the contexts that got events, with the number of events.
+    pub fn iter_delivered(&self) -> impl Iterator<Item = (Entity, usize)> + '_ {
+        self.delivered
+            .iter()
+            .map(|(context, delivered)| (*context, *delivered))
+    }
+
+    /// The events dropped because their context doesn't exist.
+    pub fn dropped(&self) -> &[EguiDroppedEvent] {
+        &self.dropped
+    }
+
+    /// The entity of the [`EguiFocusedContext`] cleared because its context doesn't exist
+    /// anymore.
+    pub fn stale_focus(&self) -> Option<Entity> {
+        self.stale_focus
+    }
+
+    fn record_drop(&mut self, dropped: EguiDroppedEvent, message: &str) {
+        self.dropped.push(dropped);
+        self.report(message);
+    }
+
+    /// Reports a routing mistake in strict mode.
+    fn report(&self, message: &str) {
+        if !self.strict {
+            return;
+        }
+        if cfg!(debug_assertions) {
+            panic!("{message} (`EguiSettings::strict_input_routing` is enabled)");
+        } else {
+            log::error!("{message} (`EguiSettings::strict_input_routing` is enabled)");
+        }
+    }
+}
+
+/// A key, button or touch pressed by an accepted event, whose release is never dropped.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub(crate) enum HeldInput {
+    Key(egui::Key),
+    PointerButton(egui::PointerButton),
+    Touch(egui::TouchId),
+}
+
+impl HeldInput {
+    fn pressed_by(event: &egui::Event) -> Option<Self> {
+        match event {
+            egui::Event::Key {
+                key, pressed: true, ..
+            } => Some(Self::Key(*key)),
+            egui::Event::PointerButton {
+                button,
+                pressed: true,
+                ..
+            } => Some(Self::PointerButton(*button)),
+            egui::Event::Touch {
+                id,
+                phase: egui::TouchPhase::Start,
+                ..
+            } => Some(Self::Touch(*id)),
+            _ => None,
+        }
+    }
+
+    fn released_by(event: &egui::Event) -> Option<Self> {
+        match event {
+            egui::Event::Key {
+                key,
+                pressed: false,
+                ..
+            } => Some(Self::Key(*key)),
+            egui::Event::PointerButton {
+                button,
+                pressed: false,
+                ..
+            } => Some(Self::PointerButton(*button)),
+            egui::Event::Touch {
+                id,
+                phase: egui::TouchPhase::End | egui::TouchPhase::Cancel,
+                ..
+            } => Some(Self::Touch(*id)),
+            _ => None,
+        }
+    }
+}
+
+/// Name of the variant of an event, for the overflow errors.
+fn event_kind(event: &egui::Event) -> &'static str {
+    use egui::Event;
+
+    match event {
+        Event::Copy => "Copy",
+        Event::Cut => "Cut",
+        Event::Paste(_) => "Paste",
+        Event::Text(_) => "Text",
+        Event::Key { .. } => "Key",
+        Event::Zoom(_) => "Zoom",
+        Event::PointerMoved(_) => "PointerMoved",
+        Event::MouseMoved(_) => "MouseMoved",
+        Event::PointerButton { .. } => "PointerButton",
+        Event::PointerGone => "PointerGone",
+        Event::Scroll(_) => "Scroll",
+        Event::MouseWheel { .. } => "MouseWheel",
+        Event::Touch { .. } => "Touch",
+        Event::CompositionStart | Event::CompositionUpdate(_) | Event::CompositionEnd(_) => {
+            "Composition"
+        }
+        _ => "Other",
+    }
+}
+
+/// Writes [`EguiInputEvent`]s into the [`EguiInput`] of the contexts, and applies
+/// [`EguiInputFilters`], [`EguiInputSuppressed`] and [`EguiSettings::max_events_per_frame`] to
+/// the events that haven't been filtered yet.
+#[allow(clippy::too_many_arguments)]
+pub fn write_egui_input_events_system(
+    mut input_events: EventReader<EguiInputEvent>,
+    input_filters: Res<EguiInputFilters>,
+    egui_settings: Res<EguiSettings>,
+    time: Res<Time<Real>>,
+    mut input_stats: ResMut<EguiInputStats>,
+    mut last_user_activity: ResMut<EguiLastUserActivity>,
+    mut routing_diagnostics: ResMut<EguiRoutingDiagnostics>,
+    mut contexts: Query<(
+        Entity,
+        &mut EguiContext,
+        &mut EguiInput,
+        Option<&EguiInputSuppressed>,
+    )>,
+) {
+    let now = time.elapsed_seconds_f64();
+    // The built-in input is the only one in the unfiltered events yet.
+    for (context, ctx, egui_input, _) in contexts.iter() {
+        let filtered = ctx.filtered_input_events.min(egui_input.events.len());
+        if egui_input.events[filtered..]
+            .iter()
+            .any(|event| EguiInputEventSource::of_user_event(event).is_user())
+        {
+            last_user_activity.set_if_neq(EguiLastUserActivity {
+                time: now,
+                context: Some(context),
+            });
+        }
+    }
+    for EguiInputEvent {
+        context,
+        event,
+        source,
+    } in input_events.read()
+    {
+        match contexts.get_mut(*context) {
+            Ok((_, _, mut egui_input, _)) => {
+                if source.is_user() {
+                    last_user_activity.set_if_neq(EguiLastUserActivity {
+                        time: now,
+                        context: Some(*context),
+                    });
+                }
+                egui_input.events.push(event.clone());
+            }
+            Err(err) => {
+                let message = format!(
+                    "Failed to write an Egui input event ({}) for a context ({context:?}): {err:?}",
+                    event_kind(event)
+                );
+                if !routing_diagnostics.strict {
+                    log::debug!("{message}");
+                }
+                routing_diagnostics.record_drop(
+                    EguiDroppedEvent {
+                        context: *context,
+                        event_type: "EguiInputEvent",
+                    },
+                    &message,
+                );
+            }
+        }
+    }
+
+    let input_stats = &mut *input_stats;
+    input_stats.contexts.clear();
+    let max_events = egui_settings.max_events_per_frame;
+    for (context, mut ctx, mut egui_input, suppressed) in contexts.iter_mut() {
+        let suppressed = suppressed.copied().unwrap_or_default();
+        // Events of the frames that skipped the pass have already been filtered.
+        let filtered = ctx.filtered_input_events.min(egui_input.events.len());
+        if filtered == egui_input.events.len() && suppressed.pointer == ctx.pointer_suppressed {
+            continue;
+        }
+        let unfiltered = egui_input.events.split_off(filtered);
+        let mut stats = EguiContextInputStats::default();
+        let mut dropped_kinds: Vec<(&'static str, usize)> = Vec::new();
+        for event in unfiltered {
+            let raw_release = HeldInput::released_by(&event);
+            let Some(event) = input_filters
+                .apply(context, event)
+                .filter(|event| !suppressed.suppresses(event))
+            else {
+                if let Some(released) = raw_release {
+                    ctx.held_inputs.retain(|held| *held != released);
+                }
+                continue;
+            };
+
+            let released = HeldInput::released_by(&event);
+            if let Some(index) = released
+                .and_then(|released| ctx.held_inputs.iter().position(|held| *held == released))
+            {
+                ctx.held_inputs.swap_remove(index);
+            } else if stats.accepted >= max_events {
+                stats.dropped += 1;
+                let kind = event_kind(&event);
+                match dropped_kinds.iter_mut().find(|(other, _)| *other == kind) {
+                    Some((_, count)) => *count += 1,
+                    None => dropped_kinds.push((kind, 1)),
+                }
+                continue;
+            } else if let Some(pressed) = HeldInput::pressed_by(&event) {
+                if !ctx.held_inputs.contains(&pressed) {
+                    ctx.held_inputs.push(pressed);
+                }
+            }
+            stats.accepted += 1;
+            egui_input.events.push(event);
+        }
+        if suppressed.pointer != ctx.pointer_suppressed {
+            ctx.pointer_suppressed = suppressed.pointer;
+            egui_input.events.push(if suppressed.pointer {
+                egui::Event::PointerGone
+            } else {
+                egui::Event::PointerMoved(ctx.mouse_position)
+            });
+        }
+        ctx.filtered_input_events = egui_input.events.len();
+        input_stats.contexts.insert(context, stats);
+        *routing_diagnostics.delivered.entry(context).or_default() += stats.accepted;
+
+        if stats.dropped == 0 {
+            continue;
+        }
+        let last_log = input_stats.last_overflow_logs.get(&context);
+        if last_log.is_some_and(|last_log| now - last_log < 1.0) {
+            continue;
+        }
+        input_stats.last_overflow_logs.insert(context, now);
+        let kinds = dropped_kinds
+            .iter()
+            .map(|(kind, count)| format!("{count} {kind}"))
+            .collect::<Vec<_>>()
+            .join(", ");
+        log::error!(
+            "Dropped {} input events of the context {context:?} over the limit of {max_events} per frame ({kinds}), see `EguiSettings::max_events_per_frame`",
+            stats.dropped,
+        );
+    }
+    input_stats
+        .last_overflow_logs
+        .retain(|context, _| contexts.contains(*context));
+}
+
+pub(crate) fn configure_input_sets(app: &mut App, schedule: InternedScheduleLabel) {
+    app.init_resource::<EguiInputCapture>().add_systems(
+        schedule,
+        capture::withhold_captured_input_system
+            .in_set(EguiInputSet::ReadBevyEvents)
+            .after(process_input_system),
+    );
+    app.configure_sets(
+        schedule,
+        (EguiInputSet::ReadBevyEvents, EguiInputSet::WriteEguiEvents)
+            .chain()
+            .in_set(EguiSet::ProcessInput),
+    );
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        pass_rate::EguiPassRate,
+        test_utils::{headless_app, input_test_app, CapturedEvents},
+    };
+    use bevy::{
+        app::PreUpdate,
+        ecs::query::With,
+        input::{mouse::MouseButton, ButtonState},
+        window::{PrimaryWindow, Window},
+    };
+
+    #[test]
+    fn test_max_events_per_frame() {
+        let (mut app, window) = input_test_app();
+        app.world
+            .resource_mut::<EguiSettings>()
+            .max_events_per_frame = 10;
+        let button = |pressed| egui::Event::PointerButton {
+            pos: egui::pos2(5.0, 5.0),
+            button: egui::PointerButton::Primary,
+            pressed,
+            modifiers: egui::Modifiers::NONE,
+        };
+        let key = |pressed| egui::Event::Key {
+            key: egui::Key::A,
+            physical_key: None,
+            pressed,
+            repeat: false,
+            modifiers: egui::Modifiers::NONE,
+        };
+        let mut events = vec![button(true)];
+        events.extend((0..20).map(|i| egui::Event::PointerMoved(egui::pos2(i as f32, 5.0))));
+        // The button was pressed before the limit was reached, the key after it.
+        events.extend([button(false), key(true), key(false)]);
+        for event in events {
+            app.world.send_event(EguiInputEvent::new(window, event));
+        }
+        app.update();
+
+        let captured = &app.world.resource::<CapturedEvents>().0;
+        assert_eq!(captured.len(), 11);
+        assert_eq!(captured[0], button(true));
+        assert_eq!(captured[10], button(false));
+        assert!(!captured.contains(&key(true)));
+        assert_eq!(
+            app.world.resource::<EguiInputStats>().context(window),
+            Some(EguiContextInputStats {
+                accepted: 11,
+                dropped: 13,
+            })
+        );
+
+        app.update();
+        assert_eq!(app.world.resource::<EguiInputStats>().context(window), None);
+    }
+
+    #[test]
+    fn test_routing_diagnostics() {
+        let (mut app, primary_window) = input_test_app();
+        let window = app.world.spawn(Window::default()).id();
+        app.update();
+        app.world.resource_mut::<EguiFocusedContext>().0 = Some(window);
+        app.world.send_event(EguiInputEvent::new(
+            primary_window,
+            egui::Event::Text("a".to_owned()),
+        ));
+        app.update();
+        let diagnostics = app.world.resource::<EguiRoutingDiagnostics>();
+        assert_eq!(diagnostics.delivered(primary_window), 1);
+        assert!(diagnostics.dropped().is_empty());
+        assert_eq!(diagnostics.stale_focus(), None);
+
+        // The focus of a despawned context gets cleared, and events for it are dropped.
+        app.world.despawn(window);
+        app.world.send_event(EguiInputEvent::new(
+            window,
+            egui::Event::Text("b".to_owned()),
+        ));
+        app.update();
+        assert_eq!(app.world.resource::<EguiFocusedContext>().0, None);
+        let diagnostics = app.world.resource::<EguiRoutingDiagnostics>();
+        assert_eq!(diagnostics.stale_focus(), Some(window));
+        assert_eq!(
+            diagnostics.dropped(),
+            [EguiDroppedEvent {
+                context: window,
+                event_type: "EguiInputEvent",
+            }]
+        );
+        app.update();
+        let diagnostics = app.world.resource::<EguiRoutingDiagnostics>();
+        assert!(diagnostics.dropped().is_empty());
+        assert_eq!(diagnostics.stale_focus(), None);
+
+        // Strict mode panics on drops in debug builds.
+        app.world
+            .resource_mut::<EguiSettings>()
+            .strict_input_routing = true;
+        app.update();
+        app.world.send_event(EguiInputEvent::new(
+            window,
+            egui::Event::Text("c".to_owned()),
+        ));
+        let update = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| app.update()));
+        assert_eq!(update.is_err(), cfg!(debug_assertions));
+    }
+
+    #[test]
+    fn test_input_filters() {
+        let (mut app, window) = input_test_app();
+        app.world
+            .resource_mut::<EguiInputFilters>()
+            .add(|_, event| match event {
+                egui::Event::PointerButton {
+                    pos,
+                    button: egui::PointerButton::Primary,
+                    pressed,
+                    modifiers,
+                } => Some(egui::Event::PointerButton {
+                    pos,
+                    button: egui::PointerButton::Secondary,
+                    pressed,
+                    modifiers,
+                }),
+                event => Some(event),
+            })
+            .add(|_, event| (!matches!(event, egui::Event::Text(_))).then_some(event));
+
+        app.world.send_event(MouseButtonInput {
+            button: MouseButton::Left,
+            state: ButtonState::Pressed,
+            window,
+        });
+        app.world.send_event(ReceivedCharacter {
+            window,
+            char: "a".into(),
+        });
+        app.update();
+        assert_eq!(
+            app.world.resource::<CapturedEvents>().0,
+            [egui::Event::PointerButton {
+                pos: egui::Pos2::ZERO,
+                button: egui::PointerButton::Secondary,
+                pressed: true,
+                modifiers: egui::Modifiers::NONE,
+            }]
+        );
+
+        // Events accumulated while passes are skipped are filtered only once.
+        app.world
+            .entity_mut(window)
+            .insert(EguiPassRate::EveryNFrames(2));
+        app.world.send_event(MouseButtonInput {
+            button: MouseButton::Left,
+            state: ButtonState::Released,
+            window,
+        });
+        app.update();
+        app.update();
+        let captured = &app.world.resource::<CapturedEvents>().0;
+        assert!(captured.iter().any(|event| matches!(
+            event,
+            egui::Event::PointerButton {
+                button: egui::PointerButton::Secondary,
+                pressed: false,
+                ..
+            }
+        )));
+        assert!(!captured.iter().any(|event| matches!(
+            event,
+            egui::Event::PointerButton {
+                button: egui::PointerButton::Primary,
+                ..
+            }
+        )));
+    }
+
+    #[test]
+    fn test_custom_input_source() {
+        use bevy::window::WindowFocused;
+
+        #[derive(Resource, Default)]
+        struct DialTurns(Vec<f32>);
+
+        struct DialSource;
+
+        impl EguiInputSource for DialSource {
+            const NAME: &'static str = "dial";
+            type Param = ResMut<'static, DialTurns>;
+
+            fn read(mut turns: ResMut<DialTurns>, events: &mut Vec<egui::Event>) {
+                for angle in turns.0.drain(..) {
+                    events.push(egui::Event::Scroll(egui::vec2(0.0, angle)));
+                }
+            }
+        }
+
+        #[derive(Resource, Default)]
+        struct CapturedScrolls(Vec<(Entity, egui::Event)>);
+
+        fn capture_scrolls_system(
+            mut captured: ResMut<CapturedScrolls>,
+            egui_inputs: Query<(Entity, &EguiInput)>,
+        ) {
+            captured.0.clear();
+            for (entity, egui_input) in egui_inputs.iter() {
+                for event in &egui_input.events {
+                    if let egui::Event::Scroll(_) = event {
+                        captured.0.push((entity, event.clone()));
+                    }
+                }
+            }
+        }
+
+        let mut app = headless_app();
+        app.init_resource::<DialTurns>()
+            .init_resource::<CapturedScrolls>()
+            .add_egui_input_source::<DialSource>()
+            .add_systems(
+                PreUpdate,
+                capture_scrolls_system
+                    .after(EguiSet::ProcessInput)
+                    .before(EguiSet::BeginFrame),
+            );
+        let second_window = app.world.spawn(Window::default()).id();
+        app.update();
+        let primary_window = app
+            .world
+            .query_filtered::<Entity, With<PrimaryWindow>>()
+            .single(&app.world);
+
+        // Without focus events, the primary context gets the events.
+        app.world.resource_mut::<DialTurns>().0.push(1.0);
+        app.update();
+        assert_eq!(
+            app.world.resource::<CapturedScrolls>().0,
+            vec![(primary_window, egui::Event::Scroll(egui::vec2(0.0, 1.0)))]
+        );
+
+        app.world.send_event(WindowFocused {
+            window: second_window,
+            focused: true,
+        });
+        app.world.resource_mut::<DialTurns>().0.push(2.0);
+        app.update();
+        assert_eq!(
+            app.world.resource::<CapturedScrolls>().0,
+            vec![(second_window, egui::Event::Scroll(egui::vec2(0.0, 2.0)))]
+        );
+
+        app.world
+            .resource_mut::<EguiSettings>()
+            .disabled_input_sources
+            .push("dial".to_owned());
+        app.world.resource_mut::<DialTurns>().0.push(3.0);
+        app.update();
+        assert!(app.world.resource::<CapturedScrolls>().0.is_empty());
+    }
+}
@@ -0,0 +1,425 @@
+//! Mouse cursor, button and wheel input.
+
+use super::{
+    CapturedButton, ContextSystemParams, EguiInputCapture, EguiInputSuppressed, PendingEvents,
+};
+use crate::{EguiContextQuery, EguiSettings};
+use bevy::{
+    ecs::{
+        component::Component,
+        event::{Event, EventReader},
+        system::{Query, Res},
+    },
+    input::{
+        mouse::{MouseButton, MouseButtonInput, MouseMotion, MouseScrollUnit, MouseWheel},
+        ButtonState,
+    },
+    math::Vec2,
+    prelude::Entity,
+    window::{CursorGrabMode, CursorMoved, Window},
+};
+
+/// A cursor move or a mouse button event, for input backends that know the order of the two.
+///
+/// Bevy sends [`CursorMoved`] and [`MouseButtonInput`] as separate events, which loses their
+/// relative order within a frame, so the built-in input presses the buttons after all the moves
+/// of the frame. Backends that deliver both in a single stream (replays, remote or automated
+/// input) can send these events instead: they are processed in the order they were sent, after
+/// the built-in ones, so a press in the middle of a burst of moves happens where the cursor was
+/// at the time.
+#[derive(Event, Clone, Debug, PartialEq)]
+pub enum EguiPointerEvent {
+    /// The cursor moved.
+    CursorMoved(CursorMoved),
+    /// A mouse button was pressed or released.
+    MouseButton(MouseButtonInput),
+}
+
+impl EguiPointerEvent {
+    /// Returns the window the event is for.
+    pub fn window(&self) -> Entity {
+        match self {
+            Self::CursorMoved(event) => event.window,
+            Self::MouseButton(event) => event.window,
+        }
+    }
+}
+
+/// Moves the pointers of the contexts and presses and releases their primary, secondary and
+/// middle buttons, in the order of the events: the [`CursorMoved`] ones, then the
+/// [`MouseButtonInput`] ones, then the [`EguiPointerEvent`]s.
+///
+/// Consecutive moves are merged if [`EguiSettings::coalesce_pointer_moved`] is enabled, a button
+/// event ends the merged move so the button is pressed at the position of the cursor at the time.
+/// Moves of the windows that grab the cursor are skipped if
+/// [`EguiSettings::write_mouse_motion_events`] is enabled.
+#[allow(clippy::too_many_arguments)]
+pub(super) fn process_pointer_events(
+    ev_cursor: &mut EventReader<CursorMoved>,
+    ev_mouse_button_input: &mut EventReader<MouseButtonInput>,
+    ev_pointer: &mut EventReader<EguiPointerEvent>,
+    pending_events: &mut PendingEvents<EguiPointerEvent>,
+    context_params: &mut ContextSystemParams,
+    input_capture: &mut EguiInputCapture,
+    egui_settings: &EguiSettings,
+    modifiers: egui::Modifiers,
+) {
+    let new_events = ev_cursor
+        .read()
+        .cloned()
+        .map(EguiPointerEvent::CursorMoved)
+        .chain(
+            ev_mouse_button_input
+                .read()
+                .cloned()
+                .map(EguiPointerEvent::MouseButton),
+        )
+        .chain(ev_pointer.read().cloned())
+        .filter(|event| match event {
+            EguiPointerEvent::CursorMoved(_) => true,
+            EguiPointerEvent::MouseButton(event) => !input_capture.withholds(
+                CapturedButton::Mouse(event.button),
+                event.state.is_pressed(),
+                modifiers,
+            ),
+        });
+    for (event, retries) in pending_events.take_chained(new_events) {
+        let Some(mut window_context) =
+            context_params.window_context_or_defer(event.window(), &event, retries, pending_events)
+        else {
+            continue;
+        };
+
+        match event {
+            EguiPointerEvent::CursorMoved(event) => {
+                if egui_settings.write_mouse_motion_events
+                    && is_cursor_grabbed(&window_context.window)
+                {
+                    continue;
+                }
+
+                let mouse_position =
+                    window_context.logical_to_egui_pos(event.position, egui_settings);
+                window_context.ctx.mouse_position = mouse_position;
+
+                let events = &mut window_context.egui_input.events;
+                match events.last_mut() {
+                    Some(egui::Event::PointerMoved(last_position))
+                        if egui_settings.coalesce_pointer_moved =>
+                    {
+                        *last_position = mouse_position;
+                    }
+                    _ => events.push(egui::Event::PointerMoved(mouse_position)),
+                }
+            }
+            EguiPointerEvent::MouseButton(event) => {
+                let button = match event.button {
+                    MouseButton::Left => egui::PointerButton::Primary,
+                    MouseButton::Right => egui::PointerButton::Secondary,
+                    MouseButton::Middle => egui::PointerButton::Middle,
+                    _ => continue,
+                };
+                let pressed = match event.state {
+                    ButtonState::Pressed => true,
+                    ButtonState::Released => false,
+                };
+                window_context
+                    .egui_input
+                    .events
+                    .push(egui::Event::PointerButton {
+                        pos: window_context.ctx.mouse_position,
+                        button,
+                        pressed,
+                        modifiers,
+                    });
+            }
+        }
+    }
+}
+
+/// Feeds the raw mouse motion of the frame to the contexts of the windows that grab the cursor
+/// (only to the focused one, if it's known).
+pub(super) fn process_mouse_motion(
+    ev_mouse_motion: &mut EventReader<MouseMotion>,
+    context_params: &mut ContextSystemParams,
+    focused_context: Option<Entity>,
+) {
+    let delta: Vec2 = ev_mouse_motion.read().map(|event| event.delta).sum();
+    if delta == Vec2::ZERO {
+        return;
+    }
+    for mut context in context_params.contexts.iter_mut() {
+        let pixels_per_point = context.computed_scale.pixels_per_point;
+        if !is_cursor_grabbed(&context.window)
+            || focused_context.is_some_and(|focused| focused != context.window_entity)
+            || pixels_per_point <= 0.0
+        {
+            continue;
+        }
+        let delta = delta / pixels_per_point;
+        context
+            .egui_input
+            .events
+            .push(egui::Event::MouseMoved(egui::vec2(delta.x, delta.y)));
+    }
+}
+
+fn is_cursor_grabbed(window: &Window) -> bool {
+    window.cursor.grab_mode != CursorGrabMode::None
+}
+
+/// Scrolls, or zooms if Ctrl (Cmd on macOS) is held.
+pub(super) fn process_mouse_wheel(
+    ev_mouse_wheel: &mut EventReader<MouseWheel>,
+    pending_events: &mut PendingEvents<MouseWheel>,
+    context_params: &mut ContextSystemParams,
+    egui_settings: &EguiSettings,
+    modifiers: egui::Modifiers,
+) {
+    let egui::Modifiers {
+        ctrl,
+        shift,
+        mac_cmd,
+        ..
+    } = modifiers;
+
+    for (event, retries) in pending_events.take_with(ev_mouse_wheel.read()) {
+        let Some(mut window_context) =
+            context_params.window_context_or_defer(event.window, &event, retries, pending_events)
+        else {
+            continue;
+        };
+
+        // https://github.com/emilk/egui/blob/a689b623a669d54ea85708a8c748eb07e23754b0/egui-winit/src/lib.rs#L449
+        let delta = match event.unit {
+            MouseScrollUnit::Line => egui::vec2(event.x, event.y) * 50.0,
+            // Pixel deltas are in physical pixels.
+            MouseScrollUnit::Pixel => {
+                let pixels_per_point = window_context.computed_scale.pixels_per_point;
+                if pixels_per_point > 0.0 {
+                    egui::vec2(event.x, event.y) / pixels_per_point
+                } else {
+                    egui::vec2(event.x, event.y)
+                }
+            }
+        };
+
+        if ctrl || mac_cmd {
+            // Treat as zoom instead.
+            let factor = (delta.y / 200.0).exp();
+            window_context
+                .egui_input
+                .events
+                .push(egui::Event::Zoom(factor));
+        } else if shift && egui_settings.shift_scrolls_horizontally {
+            // Treat as horizontal scrolling.
+            // Note: Mac already fires horizontal scroll events when shift is down.
+            window_context
+                .egui_input
+                .events
+                .push(egui::Event::Scroll(egui::vec2(delta.x + delta.y, 0.0)));
+        } else {
+            window_context
+                .egui_input
+                .events
+                .push(egui::Event::Scroll(delta));
+        }
+    }
+}
+
+/// Overrides the position of the pointer fed into a context, insert it on the window entity (to
+/// make Egui follow a software cursor interpolated between fixed updates, for example).
+///
+/// The override is evaluated at the beginning of the pass, during
+/// [`EguiSet::BeginFrame`](crate::EguiSet::BeginFrame), so systems updating it should run
+/// `.before(EguiSet::BeginFrame)`. While it applies, the pointer moves of the frame (coalesced or
+/// not) are replaced with a single [`egui::Event::PointerMoved`] at the overridden position,
+/// pushed as the last event, and the positions of the pointer button events already queued are
+/// rewritten to it, so clicks land where the overridden pointer is. Touch events aren't rewritten,
+/// only the pointer emulated from them. Once the override stops applying, the pointer moves back
+/// to the position of the cursor.
+///
+/// The override doesn't apply while the pointer of the context is suppressed with
+/// [`EguiInputSuppressed`].
+#[derive(Component, Clone, Copy, Debug, Default, PartialEq)]
+pub struct EguiPointerOverride {
+    /// The position of the pointer in Egui points, the override applies only if it's set.
+    pub position: Option<egui::Pos2>,
+    /// Whether the override applies.
+    pub apply: bool,
+}
+
+impl EguiPointerOverride {
+    /// Returns an override applying a position.
+    pub fn new(position: egui::Pos2) -> Self {
+        Self {
+            position: Some(position),
+            apply: true,
+        }
+    }
+
+    /// Returns the position to apply.
+    pub fn applied_position(&self) -> Option<egui::Pos2> {
+        self.position.filter(|_| self.apply)
+    }
+}
+
+/// Applies the [`EguiPointerOverride`]s to the input of the contexts.
+pub fn apply_pointer_overrides_system(
+    egui_settings: Res<EguiSettings>,
+    mut contexts: Query<(
+        EguiContextQuery,
+        Option<&EguiPointerOverride>,
+        Option<&EguiInputSuppressed>,
+    )>,
+) {
+    for (mut context, pointer_override, suppressed) in contexts.iter_mut() {
+        let suppressed = suppressed.is_some_and(|suppressed| suppressed.pointer);
+        let position = pointer_override
+            .and_then(EguiPointerOverride::applied_position)
+            .filter(|_| !suppressed);
+
+        let Some(position) = position else {
+            if !context.ctx.pointer_overridden {
+                continue;
+            }
+            context.ctx.pointer_overridden = false;
+            if suppressed {
+                continue;
+            }
+            let event = match context.window.cursor_position() {
+                Some(cursor_position) => {
+                    let mouse_position =
+                        context.logical_to_egui_pos(cursor_position, &egui_settings);
+                    context.ctx.mouse_position = mouse_position;
+                    egui::Event::PointerMoved(mouse_position)
+                }
+                None => egui::Event::PointerGone,
+            };
+            context.egui_input.events.push(event);
+            context.ctx.filtered_input_events = context.egui_input.events.len();
+            continue;
+        };
+
+        let events = &mut context.egui_input.events;
+        let events_count = events.len();
+        events.retain(|event| {
+            !matches!(
+                event,
+                egui::Event::PointerMoved(_) | egui::Event::PointerGone
+            )
+        });
+        let mut moved = events.len() != events_count
+            || !context.ctx.pointer_overridden
+            || context.ctx.mouse_position != position;
+        for event in events.iter_mut() {
+            if let egui::Event::PointerButton { pos, .. } = event {
+                *pos = position;
+                moved = true;
+            }
+        }
+        if moved {
+            events.push(egui::Event::PointerMoved(position));
+        }
+        context.ctx.pointer_overridden = true;
+        context.ctx.mouse_position = position;
+        // The synthetic events mustn't go through the input filters again.
+        context.ctx.filtered_input_events = context.egui_input.events.len();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        systems::begin_frame_system,
+        test_utils::{capture_events_system, input_test_app, CapturedEvents},
+        EguiContext, EguiContexts,
+    };
+    use bevy::{
+        app::{App, PreUpdate, Update},
+        ecs::{
+            schedule::IntoSystemConfigs,
+            system::{ResMut, Resource},
+        },
+    };
+
+    #[test]
+    fn test_pointer_override() {
+        #[derive(Resource, Default)]
+        struct WidgetState {
+            hovered: bool,
+            clicks: usize,
+        }
+
+        let (mut app, window) = input_test_app();
+        app.init_resource::<WidgetState>().add_systems(
+            Update,
+            |mut contexts: EguiContexts, mut state: ResMut<WidgetState>| {
+                egui::CentralPanel::default().show(contexts.ctx_mut(), |ui| {
+                    let response = ui.button("button");
+                    state.hovered = response.hovered();
+                    state.clicks += response.clicked() as usize;
+                });
+            },
+        );
+        app.update();
+        let button_pos = egui::pos2(20.0, 14.0);
+        let away_pos = Vec2::new(300.0, 200.0);
+        let click = |app: &mut App| {
+            for state in [ButtonState::Pressed, ButtonState::Released] {
+                app.world.send_event(CursorMoved {
+                    window,
+                    position: away_pos,
+                    delta: None,
+                });
+                app.world.send_event(MouseButtonInput {
+                    button: MouseButton::Left,
+                    state,
+                    window,
+                });
+                app.update();
+            }
+        };
+
+        // The cursor is away from the button, the overridden pointer is above it.
+        app.world
+            .entity_mut(window)
+            .insert(EguiPointerOverride::new(button_pos));
+        click(&mut app);
+        let state = app.world.resource::<WidgetState>();
+        assert!(state.hovered);
+        assert_eq!(state.clicks, 1);
+        // Raw moves are replaced with the overridden one, pushed last.
+        app.add_systems(
+            PreUpdate,
+            capture_events_system
+                .after(apply_pointer_overrides_system)
+                .before(begin_frame_system),
+        );
+        app.world.send_event(CursorMoved {
+            window,
+            position: away_pos,
+            delta: None,
+        });
+        app.update();
+        assert_eq!(
+            app.world.resource::<CapturedEvents>().0,
+            vec![egui::Event::PointerMoved(button_pos)]
+        );
+
+        // The pointer moves back to the cursor once the override stops applying.
+        app.world
+            .get_mut::<EguiPointerOverride>(window)
+            .unwrap()
+            .apply = false;
+        click(&mut app);
+        let state = app.world.resource::<WidgetState>();
+        assert!(!state.hovered);
+        assert_eq!(state.clicks, 1);
+        assert_eq!(
+            app.world.get::<EguiContext>(window).unwrap().mouse_position,
+            egui::pos2(300.0, 200.0)
+        );
+    }
+}
@@ -0,0 +1,213 @@
+//! Touch input, which also emulates the mouse pointer.
+
+use super::{ContextSystemParams, PendingEvents};
+use crate::{EguiContext, EguiSettings, TouchLongPress, TouchLongPressState};
+use bevy::{
+    ecs::event::EventReader,
+    input::touch::{ForceTouch, TouchInput, TouchPhase},
+    prelude::{Entity, Time},
+    time::Real,
+};
+
+/// Feeds touches, and emulates the primary pointer button with the first touch.
+pub(super) fn process_touch_input(
+    ev_touch: &mut EventReader<TouchInput>,
+    pending_events: &mut PendingEvents<TouchInput>,
+    context_params: &mut ContextSystemParams,
+    egui_settings: &EguiSettings,
+    time: &Time<Real>,
+    modifiers: egui::Modifiers,
+) {
+    for (event, retries) in pending_events.take_with(ev_touch.read()) {
+        let Some(mut window_context) =
+            context_params.window_context_or_defer(event.window, &event, retries, pending_events)
+        else {
+            continue;
+        };
+
+        let touch_id = egui::TouchId::from(event.id);
+        let pos = window_context.logical_to_egui_pos(event.position, egui_settings);
+        let long_press_config = window_context.long_press.copied();
+
+        // Emit touch event
+        window_context.egui_input.events.push(egui::Event::Touch {
+            device_id: egui::TouchDeviceId(event.window.to_bits()),
+            id: touch_id,
+            phase: match event.phase {
+                TouchPhase::Started => egui::TouchPhase::Start,
+                TouchPhase::Moved => egui::TouchPhase::Move,
+                TouchPhase::Ended => egui::TouchPhase::End,
+                TouchPhase::Canceled => egui::TouchPhase::Cancel,
+            },
+            pos,
+            force: match event.force {
+                Some(ForceTouch::Normalized(force)) => Some(force as f32),
+                Some(ForceTouch::Calibrated {
+                    force,
+                    max_possible_force,
+                    ..
+                }) => Some((force / max_possible_force) as f32),
+                None => None,
+            },
+        });
+
+        let ctx = &mut *window_context.ctx;
+        match event.phase {
+            TouchPhase::Started => ctx.active_touches.push((event.id, pos)),
+            TouchPhase::Moved => {
+                if let Some((_, touch_pos)) = ctx
+                    .active_touches
+                    .iter_mut()
+                    .find(|(id, _)| *id == event.id)
+                {
+                    *touch_pos = pos;
+                }
+            }
+            TouchPhase::Ended | TouchPhase::Canceled => {
+                ctx.active_touches.retain(|(id, _)| *id != event.id);
+            }
+        }
+
+        // If we're not yet translating a touch, or we're translating this very
+        // touch, …
+        if ctx.pointer_touch_id.is_none() || ctx.pointer_touch_id.unwrap() == event.id {
+            // … emit PointerButton resp. PointerMoved events to emulate mouse.
+            let primary_button = |pressed| egui::Event::PointerButton {
+                pos,
+                button: egui::PointerButton::Primary,
+                pressed,
+                modifiers,
+            };
+            let events = &mut window_context.egui_input.events;
+            match event.phase {
+                TouchPhase::Started => {
+                    ctx.pointer_touch_id = Some(event.id);
+                    // First move the pointer to the right location.
+                    events.push(egui::Event::PointerMoved(pos));
+                    // Then do mouse button input. If long presses are translated into secondary
+                    // clicks, the primary press is delayed until we know it's not a long press.
+                    if long_press_config.is_some() {
+                        ctx.touch_long_press = Some(TouchLongPress {
+                            start_pos: pos,
+                            start_time: time.elapsed_seconds_f64(),
+                            state: TouchLongPressState::Pending,
+                        });
+                    } else {
+                        events.push(primary_button(true));
+                    }
+                }
+                TouchPhase::Moved => {
+                    if let (Some(long_press), Some(config)) =
+                        (&mut ctx.touch_long_press, long_press_config)
+                    {
+                        if long_press.state == TouchLongPressState::Pending
+                            && long_press.start_pos.distance(pos) > config.slop
+                        {
+                            // It's a drag, press the primary button where the touch started.
+                            long_press.state = TouchLongPressState::Primary;
+                            events.push(egui::Event::PointerButton {
+                                pos: long_press.start_pos,
+                                button: egui::PointerButton::Primary,
+                                pressed: true,
+                                modifiers,
+                            });
+                        }
+                    }
+                    events.push(egui::Event::PointerMoved(pos));
+                }
+                TouchPhase::Ended => {
+                    match ctx
+                        .touch_long_press
+                        .take()
+                        .map(|long_press| long_press.state)
+                    {
+                        // A promoted pointer has never pressed the button.
+                        _ if ctx.pointer_touch_promoted => {}
+                        // A tap, emit the delayed press.
+                        Some(TouchLongPressState::Pending) => {
+                            events.push(primary_button(true));
+                            events.push(primary_button(false));
+                        }
+                        // The secondary click has already been emitted.
+                        Some(TouchLongPressState::Secondary) => {}
+                        Some(TouchLongPressState::Primary) | None => {
+                            events.push(primary_button(false));
+                        }
+                    }
+                    // Keep emulating the pointer with the next touch if there's any, without
+                    // pressing the button, as it would be a click the user didn't make.
+                    if let Some(&(id, next_pos)) = ctx.active_touches.first() {
+                        ctx.pointer_touch_id = Some(id);
+                        ctx.pointer_touch_promoted = true;
+                        events.push(egui::Event::PointerMoved(next_pos));
+                    } else {
+                        ctx.pointer_touch_id = None;
+                        ctx.pointer_touch_promoted = false;
+                        events.push(egui::Event::PointerGone);
+                    }
+                }
+                TouchPhase::Canceled => {
+                    reset_touches(ctx);
+                    events.push(egui::Event::PointerGone);
+                }
+            }
+        }
+    }
+}
+
+/// Stops emulating the pointer with touches of the windows that lost focus, as their touches
+/// won't get ended.
+pub(super) fn process_unfocused_windows(
+    unfocused_windows: &[Entity],
+    context_params: &mut ContextSystemParams,
+) {
+    for window in unfocused_windows {
+        let Ok(mut context) = context_params.contexts.get_mut(*window) else {
+            continue;
+        };
+        if context.ctx.pointer_touch_id.is_some() {
+            context.egui_input.events.push(egui::Event::PointerGone);
+        }
+        reset_touches(&mut context.ctx);
+    }
+}
+
+fn reset_touches(ctx: &mut EguiContext) {
+    ctx.pointer_touch_id = None;
+    ctx.pointer_touch_promoted = false;
+    ctx.touch_long_press = None;
+    ctx.active_touches.clear();
+}
+
+/// Emits secondary clicks for touches held long enough, see
+/// [`LongPressConfig`](crate::LongPressConfig).
+pub(super) fn process_touch_long_presses(
+    context_params: &mut ContextSystemParams,
+    time: &Time<Real>,
+    modifiers: egui::Modifiers,
+) {
+    let now = time.elapsed_seconds_f64();
+    for mut context in context_params.contexts.iter_mut() {
+        let Some(config) = context.long_press.copied() else {
+            continue;
+        };
+        let Some(long_press) = &mut context.ctx.touch_long_press else {
+            continue;
+        };
+        if long_press.state != TouchLongPressState::Pending
+            || now - long_press.start_time < config.duration as f64
+        {
+            continue;
+        }
+        long_press.state = TouchLongPressState::Secondary;
+        let pos = long_press.start_pos;
+        for pressed in [true, false] {
+            context.egui_input.events.push(egui::Event::PointerButton {
+                pos,
+                button: egui::PointerButton::Secondary,
+                pressed,
+                modifiers,
+            });
+        }
+    }
+}
@@ -21,12 +21,12 @@
 //!
 //! ```no_run,rust
 //! use bevy::prelude::*;
-//! use bevy_egui::{egui, EguiContexts, EguiPlugin};
+//! use bevy_egui::prelude::*;
 //!
 //! fn main() {
 //!     App::new()
 //!         .add_plugins(DefaultPlugins)
-//!         .add_plugins(EguiPlugin)
+//!         .add_plugins(EguiPlugin::default())
 //!         // Systems that create Egui widgets should be run during the `CoreSet::Update` set,
 //!         // or after the `EguiSet::BeginFrame` system (which belongs to the `CoreSet::PreUpdate` set).
 //!         .add_systems(Update, ui_example_system)
@@ -57,14 +57,52 @@
 ))]
 compile_error!(include_str!("../static/error_web_sys_unstable_apis.txt"));
 
+/// Tessellating the shapes of contexts off the main thread.
+pub mod async_tessellation;
+/// Egui contexts of other plugins painted into the contexts of `bevy_egui`.
+pub mod child_context;
+/// Deprecated aliases and capability constants for crates that support several versions.
+pub mod compat;
 /// Egui render node.
 #[cfg(feature = "render")]
 pub mod egui_node;
+/// Helpers for converting between Bevy and Egui coordinates.
+pub mod helpers;
+/// Feeding Bevy input to Egui contexts.
+pub mod input;
+/// Aggregated interaction metrics for UX research builds.
+#[cfg(feature = "interaction_metrics")]
+pub mod metrics;
+/// Deciding what happens to the URLs that Egui opens.
+pub mod open_url;
+/// Egui panes showing cameras that render to images.
+#[cfg(feature = "render")]
+pub mod pane;
+/// Running the Egui passes of contexts less often than every frame.
+pub mod pass_rate;
 /// Plugin systems for the render app.
 #[cfg(feature = "render")]
 pub mod render_systems;
+/// Insets of the window area kept clear of Egui on mobile devices.
+pub mod safe_area;
+/// Snapshot testing utilities.
+pub mod snapshot;
+/// Style overrides that can be changed from any system.
+pub mod style;
 /// Plugin systems.
 pub mod systems;
+#[cfg(test)]
+mod test_utils;
+/// Bounding the memory taken by the textures managed by Egui.
+#[cfg(feature = "render")]
+pub mod texture_budget;
+/// Egui themes loaded from RON assets.
+#[cfg(feature = "theme")]
+pub mod theme;
+/// Toast notifications queued from any system.
+pub mod toast;
+/// An on-screen keyboard drawn with Egui.
+pub mod virtual_keyboard;
 /// Clipboard management for web
 #[cfg(all(
     feature = "manage_clipboard",
@@ -72,48 +110,124 @@ pub mod systems;
     web_sys_unstable_apis
 ))]
 pub mod web_clipboard;
+/// Observing the size of canvases on the web.
+#[cfg(target_arch = "wasm32")]
+pub mod web_resize;
+/// An index of the widgets of a context, built from the Egui AccessKit tree.
+#[cfg(feature = "accesskit")]
+pub mod widget_index;
 
+#[allow(deprecated)]
+pub use compat::EguiOutput;
 pub use egui;
 
-use crate::systems::*;
+/// The most commonly used types, import them with `use bevy_egui::prelude::*;`.
+///
+/// ```no_run
+/// use bevy::prelude::*;
+/// use bevy_egui::prelude::*;
+///
+/// fn main() {
+///     App::new()
+///         .add_plugins(DefaultPlugins)
+///         .add_plugins(EguiPlugin::default())
+///         .insert_resource(EguiSettings {
+///             scale_factor: 1.5,
+///             ..default()
+///         })
+///         .add_systems(
+///             Update,
+///             (
+///                 ui_system.run_if(primary_context_pass_due),
+///                 read_input_system.after(EguiSet::ProcessInput),
+///             ),
+///         )
+///         .run();
+/// }
+///
+/// fn ui_system(mut contexts: EguiContexts, mut user_textures: ResMut<EguiUserTextures>) {
+///     let texture_id = user_textures.add_image(Handle::default());
+///     egui::Window::new("Hello").show(contexts.ctx_mut(), |ui| {
+///         ui.image(egui::load::SizedTexture::new(texture_id, [64.0, 64.0]));
+///     });
+/// }
+///
+/// fn read_input_system(egui_wants_input: Res<EguiWantsInput>, contexts: Query<&EguiContext>) {
+///     if !egui_wants_input.wants_any_input() {
+///         info!("{} contexts don't want input", contexts.iter().len());
+///     }
+/// }
+/// ```
+pub mod prelude {
+    #[cfg(feature = "render")]
+    pub use crate::EguiUserTextures;
+    pub use crate::{
+        egui,
+        input::EguiInputSet,
+        pass_rate::{primary_context_pass_due, EguiPassRate},
+        EguiContext, EguiContextSettings, EguiContexts, EguiPlugin, EguiPrimaryContext,
+        EguiPrimaryContextCommandsExt, EguiSet, EguiSettings, EguiStartupSet, EguiWantsInput,
+    };
+}
+
+use crate::{
+    async_tessellation::EguiAsyncTessellation,
+    open_url::{EguiOpenUrlBlocked, EguiOpenUrlPolicy, EguiOpenUrlRequested},
+    pass_rate::{EguiForcePass, EguiPassRate},
+    safe_area::EguiSafeArea,
+    systems::*,
+};
 #[cfg(feature = "render")]
 use crate::{
     egui_node::{EguiPipeline, EGUI_SHADER_HANDLE},
     render_systems::{EguiTransforms, ExtractedEguiManagedTextures},
+    texture_budget::{EguiManagedTextureBudget, EguiManagedTextureEvicted},
 };
 #[cfg(all(
     feature = "manage_clipboard",
     not(any(target_arch = "wasm32", target_os = "android"))
 ))]
 use arboard::Clipboard;
+#[cfg(feature = "theme")]
+use bevy::asset::AssetApp;
 #[allow(unused_imports)]
 use bevy::log;
 #[cfg(feature = "render")]
 use bevy::{
     app::Last,
-    asset::{load_internal_asset, AssetEvent, Assets, Handle},
-    ecs::{event::EventReader, system::ResMut},
+    asset::{load_internal_asset, AssetEvent, AssetId, Assets, Handle},
+    ecs::{
+        change_detection::{DetectChangesMut, Mut},
+        event::{EventReader, EventWriter},
+        query::QueryItem,
+        schedule::common_conditions::resource_exists,
+    },
     prelude::Shader,
     render::{
         extract_component::{ExtractComponent, ExtractComponentPlugin},
         extract_resource::{ExtractResource, ExtractResourcePlugin},
+        render_graph::RenderGraph,
         render_resource::SpecializedRenderPipelines,
-        texture::{Image, ImageSampler},
+        renderer::RenderDevice,
+        texture::{Image, ImageSampler, ImageSamplerDescriptor},
         ExtractSchedule, Render, RenderApp, RenderSet,
     },
-    utils::HashMap,
+    utils::{HashMap, HashSet},
 };
 use bevy::{
     app::{App, Plugin, PostUpdate, PreStartup, PreUpdate},
     ecs::{
         query::{QueryData, QueryEntityError},
-        schedule::apply_deferred,
-        system::SystemParam,
+        schedule::{apply_deferred, InternedScheduleLabel, IntoSystemSetConfigs, ScheduleLabel},
+        system::{EntityCommands, SystemParam},
+        world::{EntityWorldMut, World},
     },
     input::InputSystem,
+    math::Vec2,
     prelude::{
-        Added, Commands, Component, Deref, DerefMut, Entity, IntoSystemConfigs, Query, Resource,
-        SystemSet, With, Without,
+        Added, Commands, Component, Deref, DerefMut, Entity, Event, Has, IntoSystemConfigs, Query,
+        ReflectComponent, ReflectDefault, ReflectResource, Res, ResMut, Resource, SystemSet, With,
+        Without,
     },
     reflect::Reflect,
     window::{PrimaryWindow, Window},
@@ -122,14 +236,197 @@ use bevy::{
     feature = "manage_clipboard",
     not(any(target_arch = "wasm32", target_os = "android"))
 ))]
-use std::cell::{RefCell, RefMut};
+use std::{
+    cell::{RefCell, RefMut},
+    sync::atomic::{AtomicBool, Ordering},
+};
 
 /// Adds all Egui resources and render graph nodes.
-pub struct EguiPlugin;
+///
+/// The plugin can be preconfigured with builder methods, which is handy for plugin authors
+/// who ship a configured bundle:
+///
+/// ```no_run,rust
+/// use bevy::prelude::*;
+/// use bevy_egui::{EguiColorMode, EguiContextSettings, EguiPlugin, EguiSettings};
+///
+/// fn main() {
+///     App::new()
+///         .add_plugins(DefaultPlugins)
+///         .add_plugins(
+///             EguiPlugin::default()
+///                 .with_settings(EguiSettings {
+///                     scale_factor: 1.5,
+///                     ..default()
+///                 })
+///                 .with_default_context_settings(EguiContextSettings {
+///                     color_mode: Some(EguiColorMode::Accurate),
+///                     ..default()
+///                 }),
+///         )
+///         .run();
+/// }
+/// ```
+///
+/// The settings can still be changed at runtime by mutating the [`EguiSettings`] and
+/// [`EguiContextSettings`] resources.
+///
+/// The plugin used to be a unit struct: replace `add_plugins(EguiPlugin)` with
+/// `add_plugins(EguiPlugin::default())` when upgrading.
+#[derive(Clone, Debug, Default)]
+pub struct EguiPlugin {
+    settings: Option<EguiSettings>,
+    context_settings: Option<EguiContextSettings>,
+    schedules: EguiSchedules,
+}
+
+impl EguiPlugin {
+    /// Sets the initial value of the [`EguiSettings`] resource.
+    ///
+    /// If it's not set, the plugin keeps the resource inserted before adding the plugin,
+    /// or inserts the default settings.
+    #[must_use]
+    pub fn with_settings(mut self, settings: EguiSettings) -> Self {
+        self.settings = Some(settings);
+        self
+    }
+
+    /// Sets the initial value of the [`EguiContextSettings`] resource, the components inserted on
+    /// the windows that get an Egui context (including the windows created later).
+    ///
+    /// If it's not set, the plugin keeps the resource inserted before adding the plugin,
+    /// or inserts the default settings (no components).
+    #[must_use]
+    pub fn with_default_context_settings(mut self, settings: EguiContextSettings) -> Self {
+        self.context_settings = Some(settings);
+        self
+    }
+
+    /// Sets the schedules the [`EguiSet`]s run in, see [`EguiSchedules`].
+    #[must_use]
+    pub fn with_schedules(mut self, schedules: EguiSchedules) -> Self {
+        self.schedules = schedules;
+        self
+    }
+
+    /// Releases what the plugin holds outside of the app, for apps that get torn down and
+    /// rebuilt while the process keeps running (an embedding launcher, for example).
+    ///
+    /// This removes the Egui components from the windows (their contexts are gone, and the
+    /// [`EguiTornDown`] resource keeps windows spawned afterwards from getting one or an Egui
+    /// pass), the Egui passes from the render graph, and the images of the managed
+    /// textures. User textures are unregistered, the system clipboard is released, and the web
+    /// event listeners and canvas observers are removed.
+    ///
+    /// The resources are kept, so that systems running one more frame don't panic (UI systems
+    /// need to use [`EguiContexts::try_ctx_mut`] for that). The supported sequence is to call
+    /// `teardown`, optionally run the app one more time (to let the render world free the GPU
+    /// resources), and drop it:
+    ///
+    /// ```no_run,rust
+    /// use bevy::prelude::*;
+    /// use bevy_egui::EguiPlugin;
+    ///
+    /// let mut app = App::new();
+    /// app.add_plugins(DefaultPlugins).add_plugins(EguiPlugin::default());
+    /// // Run the game...
+    /// EguiPlugin::teardown(&mut app);
+    /// app.update();
+    /// drop(app);
+    /// ```
+    pub fn teardown(app: &mut App) {
+        let world = &mut app.world;
+        world.insert_resource(EguiTornDown);
+        let windows: Vec<Entity> = world
+            .query_filtered::<Entity, With<EguiContext>>()
+            .iter(world)
+            .collect();
+        for window in windows {
+            world.entity_mut(window).remove::<(
+                EguiContext,
+                EguiRenderOutput,
+                EguiInput,
+                EguiPlatformOutput,
+                WindowSize,
+                EguiComputedScale,
+                EguiTextCursorInfo,
+                EguiSafeArea,
+            )>();
+        }
+
+        #[cfg(feature = "render")]
+        {
+            if let Some(mut managed_textures) = world.get_resource_mut::<EguiManagedTextures>() {
+                let handles: Vec<_> = managed_textures
+                    .drain()
+                    .map(|(_, managed_texture)| managed_texture.handle)
+                    .collect();
+                if let Some(mut image_assets) = world.get_resource_mut::<Assets<Image>>() {
+                    for handle in handles {
+                        image_assets.remove(&handle);
+                    }
+                }
+            }
+            if let Some(mut user_textures) = world.get_resource_mut::<EguiUserTextures>() {
+                *user_textures = EguiUserTextures::default();
+            }
+        }
+        #[cfg(all(feature = "manage_clipboard", not(target_os = "android")))]
+        if let Some(mut clipboard) = world.get_resource_mut::<EguiClipboard>() {
+            *clipboard = EguiClipboard::default();
+        }
+        if let Some(mut child_contexts) =
+            world.get_resource_mut::<child_context::EguiChildContexts>()
+        {
+            *child_contexts = child_context::EguiChildContexts::default();
+        }
+        #[cfg(all(
+            feature = "manage_clipboard",
+            target_arch = "wasm32",
+            web_sys_unstable_apis
+        ))]
+        if let Some(mut subscribed_events) =
+            world.get_non_send_resource_mut::<web_clipboard::SubscribedEvents>()
+        {
+            subscribed_events.unsubscribe_from_events();
+        }
+        #[cfg(target_arch = "wasm32")]
+        if let Some(mut observers) =
+            world.get_non_send_resource_mut::<web_resize::CanvasResizeObservers>()
+        {
+            observers.disconnect();
+        }
+
+        #[cfg(feature = "render")]
+        if let Ok(render_app) = app.get_sub_app_mut(RenderApp) {
+            let render_world = &mut render_app.world;
+            if !render_world.contains_resource::<render_systems::EguiRenderGraphNodes>()
+                || !render_world.contains_resource::<RenderGraph>()
+            {
+                return;
+            }
+            render_world.resource_scope(|render_world, mut render_graph: Mut<RenderGraph>| {
+                let mut render_graph_nodes =
+                    render_world.resource_mut::<render_systems::EguiRenderGraphNodes>();
+                for (_, egui_pass) in render_graph_nodes.drain() {
+                    if let Err(err) = render_graph.remove_node(egui_pass.clone()) {
+                        log::warn!("Failed to remove an Egui pass ({egui_pass:?}): {err}");
+                    }
+                }
+            });
+        }
+    }
+}
+
+/// Is inserted by [`EguiPlugin::teardown`], windows spawned while it exists don't get an Egui
+/// context.
+#[derive(Resource, Clone, Copy, Debug, Default)]
+pub struct EguiTornDown;
 
 /// A resource for storing global UI settings.
-#[derive(Clone, Debug, Resource, Reflect)]
+#[derive(Clone, Debug, PartialEq, Resource, Reflect)]
 #[cfg_attr(feature = "render", derive(ExtractResource))]
+#[reflect(Resource, Default)]
 pub struct EguiSettings {
     /// Global scale factor for Egui widgets (`1.0` by default).
     ///
@@ -149,16 +446,147 @@ pub struct EguiSettings {
     /// If not specified, `_self` will be used. Only matters in a web browser.
     #[cfg(feature = "open_url")]
     pub default_open_url_target: Option<String>,
+    /// Merges consecutive [`egui::Event::PointerMoved`] events of a window into a single one (`true` by default).
+    ///
+    /// High polling rate mice can produce dozens of cursor events per frame, while Egui only cares
+    /// about the latest position. A move is never merged across other events: a mouse button event
+    /// ends it, so the button is pressed where the cursor was at the time (Bevy doesn't keep the
+    /// order of cursor and button events within a frame, see [`input::EguiPointerEvent`] for
+    /// feeding them in order). Disable it if you need every raw cursor position.
+    pub coalesce_pointer_moved: bool,
+    /// Sends [`bevy::window::RequestRedraw`] events when Egui asks for a repaint (`true` by default).
+    ///
+    /// This keeps animations running in the reactive [`bevy::winit::WinitSettings`] modes:
+    /// a redraw is requested right away if Egui needs a repaint immediately, and once the repaint
+    /// delay passes otherwise (a blinking cursor of a focused [`egui::TextEdit`] is a good way to
+    /// check it). Disable it if you manage redraws yourself.
+    pub request_redraws: bool,
+    /// Shows an overlay with the state of all Egui contexts on top of the primary one (`false` by
+    /// default): screen rects, scales, pointer positions (also marked with crosshairs),
+    /// the numbers of input events fed during the frame, and [`EguiWantsInput`].
+    ///
+    /// The overlay doesn't react to the pointer, so it doesn't change the input routing it shows.
+    pub show_debug_overlay: bool,
+    /// Stops feeding text and key events to Egui while a text input of another plugin owns
+    /// the [`input::TextInputFocus`] (`true` by default).
+    pub respect_external_text_focus: bool,
+    /// Names of [`input::EguiInputSource`]s that aren't read (empty by default).
+    pub disabled_input_sources: Vec<String>,
+    /// Feeds raw [`MouseMotion`](bevy::input::mouse::MouseMotion) as
+    /// [`egui::Event::MouseMoved`] to the focused context while its window grabs the cursor
+    /// (`true` by default). Cursor moves of such windows are ignored, so the pointer position
+    /// doesn't change until the cursor is released.
+    pub write_mouse_motion_events: bool,
+    /// Turns mouse wheel scrolling with Shift held into horizontal scrolling, like `egui-winit`
+    /// does (`true` by default). Platforms that already send horizontal deltas while Shift is
+    /// held (macOS) are unaffected.
+    pub shift_scrolls_horizontally: bool,
+    /// Ends the pass of a context that is still open when the next one begins (`true` by default),
+    /// logging an error.
+    ///
+    /// A pass stays open if a panic unwinds through the frame after [`EguiSet::BeginFrame`] and
+    /// the app catches it. The output of such a pass is discarded (textures updates excepted),
+    /// otherwise Egui keeps the stale pass on its stack, which breaks zooming and viewports.
+    pub recover_from_poisoned_pass: bool,
+    /// Observes the size of the canvases with a `ResizeObserver` on the web (`true` by default),
+    /// see [`EguiObservedCanvasSize`]. Turning it off disconnects the observers and removes the
+    /// observed sizes.
+    pub observe_canvas_resizes: bool,
+    /// Maximum number of indices drawn with a single draw call, larger meshes are split into
+    /// several draw calls. [`None`] (the default) uses
+    /// [`egui_node::DEFAULT_MAX_INDICES_PER_DRAW`], or fewer if they don't fit in the largest
+    /// buffer supported by the render device.
+    ///
+    /// Lower it for GPUs that still render garbage when drawing huge meshes (plots with hundreds
+    /// of thousands of points, for example), or raise it to split meshes less often.
+    pub max_indices_per_draw: Option<u32>,
+    /// Maximum number of input events accepted by a context per frame (`4096` by default), the
+    /// other ones are dropped with an error log (once per second at most), see
+    /// [`input::EguiInputStats`].
+    ///
+    /// The releases of accepted key, button and touch presses are accepted over the limit, so
+    /// that no input gets stuck.
+    pub max_events_per_frame: usize,
+    /// Makes input routing mistakes loud, for CI (`false` by default): input events dropped
+    /// because their context doesn't exist panic in debug builds, and are logged as errors in
+    /// release builds. See [`input::EguiRoutingDiagnostics`].
+    pub strict_input_routing: bool,
+}
+
+/// How Egui writes the alpha channel of a window, insert it on the window entity (contexts
+/// without this component use [`EguiWindowAlphaMode::Auto`]).
+///
+/// To let Egui panels float over the desktop, make the window transparent, clear it with a
+/// transparent [`ClearColor`](bevy::render::camera::ClearColor), and pick a composite alpha mode
+/// the platform supports: the window's [`CompositeAlphaMode::PreMultiplied`] matches what Egui
+/// writes (Windows and most Linux compositors support it, [`CompositeAlphaMode::Auto`] picks it
+/// when it's available). On macOS, Bevy needs [`CompositeAlphaMode::PostMultiplied`], the
+/// antialiased edges of shapes painted over fully transparent pixels come out slightly darker
+/// then. On the web, the canvas is composited by the browser with premultiplied alpha.
+///
+/// [`CompositeAlphaMode::PreMultiplied`]: bevy::window::CompositeAlphaMode::PreMultiplied
+/// [`CompositeAlphaMode::PostMultiplied`]: bevy::window::CompositeAlphaMode::PostMultiplied
+/// [`CompositeAlphaMode::Auto`]: bevy::window::CompositeAlphaMode::Auto
+#[derive(Component, Clone, Copy, Debug, Default, PartialEq, Eq, Hash, Reflect)]
+#[cfg_attr(feature = "render", derive(ExtractComponent))]
+#[reflect(Component, Default)]
+pub enum EguiWindowAlphaMode {
+    /// [`EguiWindowAlphaMode::Opaque`] for windows with
+    /// [`CompositeAlphaMode::Opaque`](bevy::window::CompositeAlphaMode::Opaque),
+    /// [`EguiWindowAlphaMode::PreMultiplied`] otherwise.
+    #[default]
+    Auto,
+    /// Egui doesn't write the alpha channel, which keeps the alpha the window was cleared with:
+    /// Egui is as transparent as what's below it.
+    Opaque,
+    /// Egui blends premultiplied colors and alpha over what's below, so the window is as opaque
+    /// as the Egui shapes where nothing is below.
+    PreMultiplied,
+}
+
+/// How the colors of a context are computed when rendering, insert it on the window entity
+/// (contexts without this component use [`EguiColorMode::Standard`]).
+///
+/// Switching modes uploads the managed textures of the context again, as they're stored in a
+/// different format in each mode.
+#[derive(Component, Clone, Copy, Debug, Default, PartialEq, Eq, Hash, Reflect)]
+#[cfg_attr(feature = "render", derive(ExtractComponent))]
+#[reflect(Component, Default)]
+pub enum EguiColorMode {
+    /// Converts colors to linear space and multiplies them with texture colors there. Managed
+    /// textures are uploaded as sRGB textures, with their colors unmultiplied.
+    #[default]
+    Standard,
+    /// Multiplies colors with texture colors in gamma space, the way Egui's reference renderer
+    /// does, and converts the result to linear space at the end. Managed textures are uploaded
+    /// as `Rgba8Unorm` textures with their premultiplied colors as is, which avoids rounding
+    /// errors: opaque colors (such as color swatches) are rendered with their exact values.
+    ///
+    /// Blending with what's below still happens in linear space, so translucent colors can differ
+    /// slightly from Egui's reference renderer.
+    Accurate,
+}
+
+/// Translates the touch long presses of a context into secondary clicks, so that context menus
+/// can be opened on touch-only devices, insert it on the window entity.
+///
+/// While it's inserted, the primary button press of a touch is emitted only once the touch moves
+/// farther than [`LongPressConfig::slop`] or ends before [`LongPressConfig::duration`].
+#[derive(Component, Clone, Copy, Debug, PartialEq, Reflect)]
+#[reflect(Component, Default)]
+pub struct LongPressConfig {
+    /// How long a touch needs to be held, in seconds (`0.5` by default).
+    pub duration: f32,
+    /// How far a touch can move (in logical points) to still be considered a long press (`8.0` by default).
+    pub slop: f32,
 }
 
-// Just to keep the PartialEq
-impl PartialEq for EguiSettings {
-    #[allow(clippy::let_and_return)]
-    fn eq(&self, other: &Self) -> bool {
-        let eq = self.scale_factor == other.scale_factor;
-        #[cfg(feature = "open_url")]
-        let eq = eq && self.default_open_url_target == other.default_open_url_target;
-        eq
+impl Default for LongPressConfig {
+    fn default() -> Self {
+        Self {
+            duration: 0.5,
+            slop: 8.0,
+        }
     }
 }
 
@@ -168,10 +596,162 @@ impl Default for EguiSettings {
             scale_factor: 1.0,
             #[cfg(feature = "open_url")]
             default_open_url_target: None,
+            coalesce_pointer_moved: true,
+            request_redraws: true,
+            show_debug_overlay: false,
+            respect_external_text_focus: true,
+            disabled_input_sources: Vec::new(),
+            write_mouse_motion_events: true,
+            shift_scrolls_horizontally: true,
+            recover_from_poisoned_pass: true,
+            observe_canvas_resizes: true,
+            max_indices_per_draw: None,
+            max_events_per_frame: 4096,
+            strict_input_routing: false,
+        }
+    }
+}
+
+/// Per-context settings that are inserted as components on the windows getting an Egui context,
+/// unless the windows already have them, see [`EguiPlugin::with_default_context_settings`].
+///
+/// The settings are read by [`setup_new_windows_system`] when a context is created, so changing
+/// the resource affects the windows created afterwards. To change the settings of an existing
+/// context, change its components.
+#[derive(Clone, Debug, Default, PartialEq, Resource)]
+pub struct EguiContextSettings {
+    /// Inserted as an [`EguiPassRate`] component.
+    pub pass_rate: Option<EguiPassRate>,
+    /// Inserted as an [`EguiDpiMode`] component.
+    pub dpi_mode: Option<EguiDpiMode>,
+    /// Inserted as an [`EguiColorMode`] component.
+    pub color_mode: Option<EguiColorMode>,
+    /// Inserted as an [`EguiWindowAlphaMode`] component.
+    pub window_alpha_mode: Option<EguiWindowAlphaMode>,
+    /// Inserted as a [`LongPressConfig`] component.
+    pub long_press: Option<LongPressConfig>,
+}
+
+impl EguiContextSettings {
+    /// Inserts the settings that are set and that the entity doesn't have yet.
+    pub fn insert_missing(&self, entity: &mut EntityWorldMut) {
+        fn insert_missing<T: Component + Copy>(entity: &mut EntityWorldMut, value: Option<T>) {
+            if let Some(value) = value {
+                if !entity.contains::<T>() {
+                    entity.insert(value);
+                }
+            }
         }
+        insert_missing(entity, self.pass_rate);
+        insert_missing(entity, self.dpi_mode);
+        insert_missing(entity, self.color_mode);
+        insert_missing(entity, self.window_alpha_mode);
+        insert_missing(entity, self.long_press);
+    }
+}
+
+/// Overrides the Bevy cursor icons that are set when Egui requests a cursor.
+///
+/// Cursors that aren't mapped fall back to the default mapping. The map can be changed at runtime.
+///
+/// ```rust
+/// use bevy::{prelude::*, window::CursorIcon};
+/// use bevy_egui::EguiCursorMap;
+///
+/// fn setup_cursors(mut cursor_map: ResMut<EguiCursorMap>) {
+///     // Show a crosshair instead of a pointing hand when hovering buttons.
+///     cursor_map.insert(egui::CursorIcon::PointingHand, CursorIcon::Crosshair);
+/// }
+/// ```
+///
+/// Note that Bevy 0.13 supports only system cursors, so custom cursor images can't be mapped yet.
+#[derive(Clone, Debug, Default, Resource)]
+pub struct EguiCursorMap {
+    // `egui::CursorIcon` doesn't implement `Hash`, its variants have no fields though.
+    cursors:
+        bevy::utils::HashMap<std::mem::Discriminant<egui::CursorIcon>, bevy::window::CursorIcon>,
+}
+
+impl EguiCursorMap {
+    /// Maps an Egui cursor to a Bevy cursor, returns the previously mapped cursor.
+    pub fn insert(
+        &mut self,
+        egui_cursor: egui::CursorIcon,
+        cursor: bevy::window::CursorIcon,
+    ) -> Option<bevy::window::CursorIcon> {
+        self.cursors
+            .insert(std::mem::discriminant(&egui_cursor), cursor)
+    }
+
+    /// Removes the mapping for an Egui cursor, so that the default one is used.
+    pub fn remove(&mut self, egui_cursor: egui::CursorIcon) -> Option<bevy::window::CursorIcon> {
+        self.cursors.remove(&std::mem::discriminant(&egui_cursor))
+    }
+
+    /// Returns the Bevy cursor mapped to an Egui cursor.
+    #[must_use]
+    pub fn get(&self, egui_cursor: egui::CursorIcon) -> Option<bevy::window::CursorIcon> {
+        self.cursors
+            .get(&std::mem::discriminant(&egui_cursor))
+            .copied()
     }
 }
 
+/// Whether Egui wants to use the pointer or keyboard input in the current frame, combined for
+/// all contexts.
+///
+/// Is updated right after [`begin_frame_system`] (during [`EguiSet::BeginFrame`]), so systems
+/// running in [`bevy::app::Update`] can ignore input that Egui is going to consume in the same
+/// frame. For example, a key press that goes into a focused text field won't trigger a hotkey:
+///
+/// ```rust
+/// use bevy::prelude::*;
+/// use bevy_egui::EguiWantsInput;
+///
+/// fn hotkeys_system(keys: Res<ButtonInput<KeyCode>>, egui_wants_input: Res<EguiWantsInput>) {
+///     if egui_wants_input.wants_keyboard_input {
+///         return;
+///     }
+///     if keys.just_pressed(KeyCode::KeyI) {
+///         // Open the inventory.
+///     }
+/// }
+/// ```
+///
+/// The values are based on the state Egui kept from the previous frame (focused widgets, areas
+/// and their rects) and the input of the current frame. This means that widgets that appear
+/// or get focused by the UI code of the current frame aren't accounted for until the next one.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Resource, Reflect)]
+#[reflect(Resource, Default)]
+pub struct EguiWantsInput {
+    /// The pointer is over an Egui area, see [`egui::Context::is_pointer_over_area`].
+    pub is_pointer_over_area: bool,
+    /// Egui is interested in the pointer (it's over an area or a widget is being dragged),
+    /// see [`egui::Context::wants_pointer_input`].
+    pub wants_pointer_input: bool,
+    /// A widget that uses the keyboard (a text field, for example) has focus,
+    /// see [`egui::Context::wants_keyboard_input`].
+    pub wants_keyboard_input: bool,
+}
+
+impl EguiWantsInput {
+    /// Returns `true` if Egui wants either pointer or keyboard input.
+    #[must_use]
+    pub fn wants_any_input(&self) -> bool {
+        self.wants_pointer_input || self.wants_keyboard_input
+    }
+}
+
+/// Limits of the render device, which get passed to Egui.
+///
+/// Is inserted by the plugin when the render device is created. If the resource doesn't exist
+/// (for example, if the `render` feature is disabled), Egui uses its default limits.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Resource, Reflect)]
+pub struct EguiRenderLimits {
+    /// Maximum width and height of a texture, is written to [`egui::RawInput::max_texture_side`].
+    pub max_texture_side: usize,
+}
+
 /// Is used for storing Egui context input..
 ///
 /// It gets reset during the [`EguiSet::ProcessInput`] system.
@@ -181,15 +761,71 @@ pub struct EguiInput(pub egui::RawInput);
 /// A resource for accessing clipboard.
 ///
 /// The resource is available only if `manage_clipboard` feature is enabled.
+///
+/// If the system clipboard is unavailable (for example, on Wayland compositors without the
+/// required protocols), the contents are stored in an internal buffer, so that copying and pasting
+/// still works within the app.
 #[cfg(all(feature = "manage_clipboard", not(target_os = "android")))]
-#[derive(Default, Resource)]
+#[derive(Resource)]
 pub struct EguiClipboard {
     #[cfg(not(target_arch = "wasm32"))]
-    clipboard: thread_local::ThreadLocal<Option<RefCell<Clipboard>>>,
+    clipboard: thread_local::ThreadLocal<Option<RefCell<Box<dyn ClipboardProvider>>>>,
+    #[cfg(not(target_arch = "wasm32"))]
+    new_provider: fn() -> Result<Box<dyn ClipboardProvider>, String>,
+    /// Stores the contents if the system clipboard is unavailable.
+    #[cfg(not(target_arch = "wasm32"))]
+    fallback_contents: Option<String>,
+    #[cfg(not(target_arch = "wasm32"))]
+    init_error_logged: AtomicBool,
     #[cfg(all(target_arch = "wasm32", web_sys_unstable_apis))]
     clipboard: web_clipboard::WebClipboard,
 }
 
+#[cfg(all(feature = "manage_clipboard", not(target_os = "android")))]
+impl Default for EguiClipboard {
+    fn default() -> Self {
+        Self {
+            #[cfg(not(target_arch = "wasm32"))]
+            clipboard: Default::default(),
+            #[cfg(not(target_arch = "wasm32"))]
+            new_provider: || {
+                let clipboard = Clipboard::new().map_err(|err| err.to_string())?;
+                Ok(Box::new(clipboard))
+            },
+            #[cfg(not(target_arch = "wasm32"))]
+            fallback_contents: None,
+            #[cfg(not(target_arch = "wasm32"))]
+            init_error_logged: AtomicBool::new(false),
+            #[cfg(all(target_arch = "wasm32", web_sys_unstable_apis))]
+            clipboard: Default::default(),
+        }
+    }
+}
+
+/// Abstracts the system clipboard, so that it can be replaced in tests.
+#[cfg(all(
+    feature = "manage_clipboard",
+    not(any(target_arch = "wasm32", target_os = "android"))
+))]
+trait ClipboardProvider: Send {
+    fn get_text(&mut self) -> Result<String, String>;
+    fn set_text(&mut self, contents: &str) -> Result<(), String>;
+}
+
+#[cfg(all(
+    feature = "manage_clipboard",
+    not(any(target_arch = "wasm32", target_os = "android"))
+))]
+impl ClipboardProvider for Clipboard {
+    fn get_text(&mut self) -> Result<String, String> {
+        Clipboard::get_text(self).map_err(|err| err.to_string())
+    }
+
+    fn set_text(&mut self, contents: &str) -> Result<(), String> {
+        Clipboard::set_text(self, contents).map_err(|err| err.to_string())
+    }
+}
+
 #[cfg(all(
     feature = "manage_clipboard",
     not(target_os = "android"),
@@ -222,6 +858,22 @@ impl EguiClipboard {
         self.get_contents_impl()
     }
 
+    /// Returns `false` if the system clipboard couldn't be initialized, in which case
+    /// the contents are shared only within the app.
+    #[must_use]
+    #[cfg(not(target_arch = "wasm32"))]
+    pub fn is_system_clipboard_available(&self) -> bool {
+        self.get().is_some()
+    }
+
+    /// Returns `false` if the system clipboard couldn't be initialized, in which case
+    /// the contents are shared only within the app.
+    #[must_use]
+    #[cfg(all(target_arch = "wasm32", web_sys_unstable_apis))]
+    pub fn is_system_clipboard_available(&self) -> bool {
+        true
+    }
+
     /// Receives a clipboard event sent by the `copy`/`cut`/`paste` listeners.
     #[cfg(all(target_arch = "wasm32", web_sys_unstable_apis))]
     pub fn try_receive_clipboard_event(&self) -> Option<web_clipboard::WebClipboardEvent> {
@@ -230,8 +882,12 @@ impl EguiClipboard {
 
     #[cfg(not(target_arch = "wasm32"))]
     fn set_contents_impl(&mut self, contents: &str) {
+        if !self.is_system_clipboard_available() {
+            self.fallback_contents = Some(contents.to_owned());
+            return;
+        }
         if let Some(mut clipboard) = self.get() {
-            if let Err(err) = clipboard.set_text(contents.to_owned()) {
+            if let Err(err) = clipboard.set_text(contents) {
                 log::error!("Failed to set clipboard contents: {:?}", err);
             }
         }
@@ -244,13 +900,16 @@ impl EguiClipboard {
 
     #[cfg(not(target_arch = "wasm32"))]
     fn get_contents_impl(&mut self) -> Option<String> {
-        if let Some(mut clipboard) = self.get() {
-            match clipboard.get_text() {
-                Ok(contents) => return Some(contents),
-                Err(err) => log::error!("Failed to get clipboard contents: {:?}", err),
-            }
+        let Some(mut clipboard) = self.get() else {
+            return self.fallback_contents.clone();
         };
-        None
+        match clipboard.get_text() {
+            Ok(contents) => Some(contents),
+            Err(err) => {
+                log::error!("Failed to get clipboard contents: {:?}", err);
+                None
+            }
+        }
     }
 
     #[cfg(all(target_arch = "wasm32", web_sys_unstable_apis))]
@@ -260,13 +919,17 @@ impl EguiClipboard {
     }
 
     #[cfg(not(target_arch = "wasm32"))]
-    fn get(&self) -> Option<RefMut<Clipboard>> {
+    fn get(&self) -> Option<RefMut<'_, Box<dyn ClipboardProvider>>> {
         self.clipboard
             .get_or(|| {
-                Clipboard::new()
+                (self.new_provider)()
                     .map(RefCell::new)
                     .map_err(|err| {
-                        log::error!("Failed to initialize clipboard: {:?}", err);
+                        // The initialization is retried for every thread, but it's enough
+                        // to report the error once.
+                        if !self.init_error_logged.swap(true, Ordering::Relaxed) {
+                            log::error!("Failed to initialize clipboard, copying and pasting will work only within the app: {:?}", err);
+                        }
                     })
                     .ok()
             })
@@ -276,8 +939,10 @@ impl EguiClipboard {
 }
 
 /// Is used for storing Egui shapes and textures delta.
+///
+/// Empty outputs (see [`EguiRenderOutput::is_empty`]) aren't extracted to the render world,
+/// so contexts that show nothing don't get a render pass.
 #[derive(Component, Clone, Default, Debug)]
-#[cfg_attr(feature = "render", derive(ExtractComponent))]
 pub struct EguiRenderOutput {
     /// Pairs of rectangles and paint commands.
     ///
@@ -295,20 +960,348 @@ impl EguiRenderOutput {
     }
 }
 
-/// Is used for storing Egui output.
+#[cfg(feature = "render")]
+impl ExtractComponent for EguiRenderOutput {
+    type QueryData = &'static Self;
+    type QueryFilter = ();
+    type Out = Self;
+
+    fn extract_component(render_output: QueryItem<'_, Self::QueryData>) -> Option<Self> {
+        (!render_output.is_empty()).then(|| render_output.clone())
+    }
+}
+
+/// Pre-tessellated primitives painted along with the Egui shapes of a context, insert it on
+/// the window entity.
+///
+/// The primitives go through the same rendering as the tessellated Egui shapes: clip rects are in
+/// Egui points, and meshes can use the managed textures of the context or the user textures of
+/// [`EguiUserTextures`](crate::EguiUserTextures). The vector is emptied by the pass that
+/// consumes it (during [`EguiSet::ProcessOutput`]), so the primitives have to be pushed for every
+/// pass, like Egui shapes.
+#[derive(Component, Clone, Default)]
+pub struct EguiExtraPaintJobs {
+    /// The primitives to paint during the next pass.
+    pub primitives: Vec<egui::ClippedPrimitive>,
+    /// Whether the primitives are painted below or above the Egui shapes.
+    pub placement: EguiExtraPaintJobsPlacement,
+}
+
+/// Where [`EguiExtraPaintJobs`] are painted relative to the Egui shapes.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum EguiExtraPaintJobsPlacement {
+    /// Below the Egui shapes.
+    Underlay,
+    /// Above the Egui shapes.
+    #[default]
+    Overlay,
+}
+
+/// A function that modifies the shapes of a context before they get tessellated, see
+/// [`EguiShapeFilters`].
+pub type EguiShapeFilter =
+    Box<dyn Fn(&mut Vec<egui::epaint::ClippedShape>, &egui::Context) + Send + Sync>;
+
+/// Filters applied to the shapes of a context between ending the pass and tessellation, insert it
+/// on the window entity.
+///
+/// The filters run in the order they were added, during [`EguiSet::ProcessOutput`], for every
+/// pass of the context (contexts skipping passes because of [`EguiPassRate`] don't run them). They
+/// can drop, modify or add shapes, for example, rounding the corners of all rects:
+///
+/// ```rust
+/// use bevy::{prelude::*, window::PrimaryWindow};
+/// use bevy_egui::EguiShapeFilters;
+///
+/// fn setup(mut commands: Commands, windows: Query<Entity, With<PrimaryWindow>>) {
+///     let mut filters = EguiShapeFilters::default();
+///     filters.add(|shapes, _ctx| {
+///         for clipped_shape in shapes {
+///             if let egui::Shape::Rect(rect) = &mut clipped_shape.shape {
+///                 rect.rounding = egui::Rounding::same(8.0);
+///             }
+///         }
+///     });
+///     commands.entity(windows.single()).insert(filters);
+/// }
+/// ```
+#[derive(Component, Default)]
+pub struct EguiShapeFilters(pub Vec<EguiShapeFilter>);
+
+impl EguiShapeFilters {
+    /// Adds a filter after the existing ones.
+    pub fn add(
+        &mut self,
+        filter: impl Fn(&mut Vec<egui::epaint::ClippedShape>, &egui::Context) + Send + Sync + 'static,
+    ) -> &mut Self {
+        self.0.push(Box::new(filter));
+        self
+    }
+
+    /// Runs the shapes of the context through the filters.
+    pub fn apply(&self, shapes: &mut Vec<egui::epaint::ClippedShape>, ctx: &egui::Context) {
+        for filter in &self.0 {
+            filter(shapes, ctx);
+        }
+    }
+}
+
+/// Skips tessellating the shapes of a context, insert it on the window entity.
+///
+/// Only the [`EguiExtraPaintJobs`] of the context get painted then, which allows tessellating
+/// the shapes (read with [`EguiShapeFilters`]) in a custom way or rendering them with a custom
+/// renderer.
+#[derive(Component, Clone, Copy, Debug, Default)]
+pub struct EguiSkipTessellation;
+
+/// Overrides the tessellation options of a context, insert it on the window entity.
+///
+/// Feathering (anti-aliasing the edges of shapes) smears thin strokes on targets with few pixels
+/// per point, turning it off makes them crisp (and jagged). Text is aligned to physical pixels by
+/// default, like in `eframe`, which keeps it sharp at fractional scale factors (125%, 150%).
+#[derive(Component, Clone, Copy, Debug, Default, PartialEq, Reflect)]
+#[reflect(Component, Default)]
+pub struct EguiTessellationSettings {
+    /// Overrides [`egui::epaint::TessellationOptions::feathering`], [`None`] keeps the value of
+    /// the context.
+    pub feathering: Option<bool>,
+    /// Turns feathering off while the context renders with fewer pixels per point than the
+    /// threshold (see [`EguiComputedScale::pixels_per_point`]), and on otherwise (unless
+    /// [`EguiTessellationSettings::feathering`] is `Some(false)`).
+    pub feathering_auto_disable_below_ppp: Option<f32>,
+    /// Overrides [`egui::epaint::TessellationOptions::round_text_to_pixels`], [`None`] keeps the
+    /// value of the context.
+    pub round_text_to_pixels: Option<bool>,
+}
+
+impl EguiTessellationSettings {
+    /// Returns whether a context that renders with the given pixels per point is feathered,
+    /// [`None`] if the value of the context is kept.
+    #[must_use]
+    pub fn feathering(&self, pixels_per_point: f32) -> Option<bool> {
+        match self.feathering_auto_disable_below_ppp {
+            Some(threshold) if pixels_per_point < threshold => Some(false),
+            Some(_) => Some(self.feathering.unwrap_or(true)),
+            None => self.feathering,
+        }
+    }
+}
+
+/// Lays out text in the next pass of a context without painting it, so that the glyphs get
+/// rasterized into the font atlas ahead of time. Insert it on the window entity during a loading
+/// screen, it's removed once the pass has run.
+///
+/// The first frame that shows a lot of new text (a pause menu opened for the first time, for
+/// example) otherwise rasterizes the glyphs and uploads the grown font atlas all at once. The
+/// render pipelines don't need to be prewarmed: they're specialized for the format of every
+/// window as soon as the window exists, whether the context shows anything or not. The atlas is
+/// rebuilt when the scale factor of the context changes, which undoes the prewarming.
+///
+/// ```rust
+/// use bevy::{prelude::*, window::PrimaryWindow};
+/// use bevy_egui::EguiPrewarm;
+///
+/// fn loading_screen_setup_system(
+///     mut commands: Commands,
+///     primary_window: Query<Entity, With<PrimaryWindow>>,
+/// ) {
+///     commands.entity(primary_window.single()).insert(EguiPrewarm {
+///         texts: vec![EguiPrewarm::ASCII.to_owned(), "Паўза · Pause · Pausa".to_owned()],
+///         ..Default::default()
+///     });
+/// }
+/// ```
+#[derive(Component, Clone, Debug, PartialEq)]
+pub struct EguiPrewarm {
+    /// Texts containing the glyphs to rasterize ([`EguiPrewarm::ASCII`] by default).
+    pub texts: Vec<String>,
+    /// Fonts (families and sizes) the texts are laid out with, in addition to the fonts of the
+    /// text styles of the context (see [`egui::Style::text_styles`]).
+    pub font_ids: Vec<egui::FontId>,
+}
+
+impl EguiPrewarm {
+    /// The printable ASCII characters.
+    pub const ASCII: &'static str =
+        " !\"#$%&'()*+,-./0123456789:;<=>?@ABCDEFGHIJKLMNOPQRSTUVWXYZ[\\]^_`abcdefghijklmnopqrstuvwxyz{|}~";
+}
+
+impl Default for EguiPrewarm {
+    fn default() -> Self {
+        Self {
+            texts: vec![Self::ASCII.to_owned()],
+            font_ids: Vec::new(),
+        }
+    }
+}
+
+/// Rects of the areas (windows, popups, tooltips, etc) of a context, insert it on the window
+/// entity to opt in.
+///
+/// The map is rebuilt by every pass (during [`EguiSet::ProcessOutput`]), so systems can check
+/// what Egui covers (to hide markers that are behind windows, for example) during the next
+/// frame's [`Update`] without calling into the context. Panels and the other background
+/// layers aren't included. Positions are in Egui points, use [`helpers::physical_to_egui_pos`]
+/// or [`helpers::world_to_egui_pos`] to convert window or world positions.
+#[derive(Component, Clone, Debug, Default)]
+pub struct EguiOcclusionMap {
+    /// The visible layers and their rects, back to front.
+    pub layers: Vec<(egui::LayerId, egui::Rect)>,
+}
+
+impl EguiOcclusionMap {
+    /// Returns `true` if a layer covers the position.
+    pub fn is_point_covered(&self, pos: egui::Pos2) -> bool {
+        self.covering_layer(pos).is_some()
+    }
+
+    /// Returns the topmost layer that covers the position.
+    pub fn covering_layer(&self, pos: egui::Pos2) -> Option<egui::LayerId> {
+        self.layers
+            .iter()
+            .rev()
+            .find(|(_, rect)| rect.contains(pos))
+            .map(|(layer_id, _)| *layer_id)
+    }
+
+    /// Rebuilds the map from the areas of the context.
+    pub fn update(&mut self, ctx: &egui::Context) {
+        self.layers.clear();
+        ctx.memory(|memory| {
+            let areas = memory.areas();
+            self.layers.extend(
+                memory
+                    .layer_ids()
+                    .filter(|layer_id| {
+                        layer_id.order != egui::Order::Background && areas.is_visible(layer_id)
+                    })
+                    .filter_map(|layer_id| Some((layer_id, memory.area_rect(layer_id.id)?))),
+            );
+        });
+    }
+}
+
+/// Tells whether the last pass of a context changed what gets painted, insert it on the window
+/// entity to opt in (hashing the paint jobs isn't free).
+///
+/// Apps that render only on changes can skip presenting frames when neither the game view nor
+/// the UI changed, see also [`EguiAnyOutputChanged`]. The component is updated during
+/// [`EguiSet::ProcessOutput`], after the tessellation.
+#[derive(Component, Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct EguiOutputChanged {
+    /// Whether the paint jobs of the last pass differ from the ones of the pass before it, or
+    /// textures were updated. Paint callbacks always count as changes. `false` if the context
+    /// skipped the pass.
+    pub changed: bool,
+    /// Hash of the paint jobs of the last pass.
+    pub shapes_hash: u64,
+}
+
+impl EguiOutputChanged {
+    /// Compares the paint jobs of a pass with the ones of the previous pass.
+    pub fn update(
+        &mut self,
+        paint_jobs: &[egui::ClippedPrimitive],
+        textures_delta: &egui::TexturesDelta,
+    ) {
+        use std::hash::{BuildHasher, Hash, Hasher};
+
+        let mut hasher = bevy::utils::FixedState.build_hasher();
+        let mut has_callbacks = false;
+        for egui::ClippedPrimitive {
+            clip_rect,
+            primitive,
+        } in paint_jobs
+        {
+            for value in [
+                clip_rect.min.x,
+                clip_rect.min.y,
+                clip_rect.max.x,
+                clip_rect.max.y,
+            ] {
+                value.to_bits().hash(&mut hasher);
+            }
+            match primitive {
+                egui::epaint::Primitive::Mesh(mesh) => {
+                    mesh.texture_id.hash(&mut hasher);
+                    mesh.indices.hash(&mut hasher);
+                    hasher.write(bevy::core::cast_slice(&mesh.vertices));
+                }
+                egui::epaint::Primitive::Callback(_) => has_callbacks = true,
+            }
+        }
+        let shapes_hash = hasher.finish();
+        self.changed =
+            shapes_hash != self.shapes_hash || has_callbacks || !textures_delta.is_empty();
+        self.shapes_hash = shapes_hash;
+    }
+}
+
+/// Whether the last pass of any context with [`EguiOutputChanged`] changed what gets painted.
+#[derive(Resource, Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct EguiAnyOutputChanged(pub bool);
+
+/// Is used for storing the platform output of Egui (copied text, cursor icon, IME state, etc).
+///
+/// Not to be confused with [`EguiRenderOutput`], which stores what gets painted.
 #[derive(Component, Clone, Default)]
-pub struct EguiOutput {
+pub struct EguiPlatformOutput {
     /// The field gets updated during the [`EguiSet::ProcessOutput`] system (belonging to [`PostUpdate`]).
     pub platform_output: egui::PlatformOutput,
 }
 
 /// A component for storing `bevy_egui` context.
 #[derive(Clone, Component, Default)]
-#[cfg_attr(feature = "render", derive(ExtractComponent))]
 pub struct EguiContext {
     ctx: egui::Context,
     mouse_position: egui::Pos2,
     pointer_touch_id: Option<u64>,
+    /// Whether the pointer emulated with a touch was promoted from another touch (when the first
+    /// touch lifted), such a pointer never presses the primary button.
+    pointer_touch_promoted: bool,
+    /// Touches that haven't lifted yet, in the order they started, with their last positions.
+    active_touches: Vec<(u64, egui::Pos2)>,
+    touch_long_press: Option<TouchLongPress>,
+    /// The repaint delay requested by the last frame, see [`EguiSettings::request_redraws`].
+    repaint_delay: Option<std::time::Duration>,
+    /// Whether the context runs a pass during the current frame, see [`EguiPassRate`].
+    pass_due: bool,
+    /// Time (elapsed since the startup, in seconds) of the last pass.
+    last_pass_time: Option<f64>,
+    /// The number of frames skipped since the last pass.
+    frames_since_pass: u32,
+    /// The number of events in [`EguiInput`] that went through [`input::EguiInputFilters`].
+    filtered_input_events: usize,
+    /// Whether the pass has begun and hasn't ended yet.
+    pass_open: bool,
+    /// Whether the pointer events are dropped, see [`input::EguiInputSuppressed`].
+    pointer_suppressed: bool,
+    /// Whether the pointer position is overridden, see [`input::EguiPointerOverride`].
+    pointer_overridden: bool,
+    /// Keys, buttons and touches pressed by accepted events, see
+    /// [`EguiSettings::max_events_per_frame`].
+    held_inputs: Vec<input::HeldInput>,
+    /// Textures freed by the last pass tessellated with [`EguiAsyncTessellation`], which are
+    /// freed with the next pass. Kept here, so they aren't lost if the component gets removed.
+    delayed_texture_frees: Vec<egui::TextureId>,
+}
+
+/// Tracks a touch that may become a long press, see [`LongPressConfig`].
+#[derive(Clone, Copy, Debug)]
+struct TouchLongPress {
+    start_pos: egui::Pos2,
+    start_time: f64,
+    state: TouchLongPressState,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum TouchLongPressState {
+    /// Neither the primary nor the secondary button has been pressed yet.
+    Pending,
+    /// The touch moved farther than the slop radius and got translated into a primary press.
+    Primary,
+    /// The touch was held long enough and got translated into a secondary click.
+    Secondary,
 }
 
 impl EguiContext {
@@ -340,28 +1333,155 @@ impl EguiContext {
     pub fn get_mut(&mut self) -> &mut egui::Context {
         &mut self.ctx
     }
+
+    /// Returns `true` if the context runs an Egui pass during the current frame.
+    ///
+    /// It's always `true` for contexts without an [`EguiPassRate`], UI systems of other contexts
+    /// need to check it (or use the [`primary_context_pass_due`](pass_rate::primary_context_pass_due) run condition).
+    #[must_use]
+    pub fn is_pass_due(&self) -> bool {
+        self.pass_due
+    }
 }
 
-#[derive(SystemParam)]
-/// A helper SystemParam that provides a way to get `[EguiContext]` with less boilerplate and
-/// combines a proxy interface to the [`EguiUserTextures`] resource.
-pub struct EguiContexts<'w, 's> {
-    q: Query<
-        'w,
-        's,
+/// A schedule that draws the UI of a context, insert it on the window entity.
+///
+/// The schedule runs during the passes of the context (before [`EguiSet::ProcessOutput`]), and
+/// panics of its systems are caught: the context gets marked with [`EguiContextPoisoned`] and an
+/// [`EguiContextPoisonedEvent`] is sent instead of aborting the app.
+///
+/// ```no_run
+/// use bevy::{ecs::schedule::ScheduleLabel, prelude::*, window::PrimaryWindow};
+/// use bevy_egui::{prelude::*, EguiUiSchedule};
+///
+/// #[derive(ScheduleLabel, Clone, Debug, PartialEq, Eq, Hash)]
+/// struct InventoryUi;
+///
+/// fn setup(mut commands: Commands, windows: Query<Entity, With<PrimaryWindow>>) {
+///     commands
+///         .entity(windows.single())
+///         .insert(EguiUiSchedule(InventoryUi.intern()));
+/// }
+///
+/// fn inventory_ui_system(mut contexts: EguiContexts) {
+///     egui::Window::new("Inventory").show(contexts.ctx_mut(), |ui| {
+///         ui.label("Empty");
+///     });
+/// }
+///
+/// App::new()
+///     .add_plugins(DefaultPlugins)
+///     .add_plugins(EguiPlugin::default())
+///     .init_schedule(InventoryUi)
+///     .add_systems(Startup, setup)
+///     .add_systems(InventoryUi, inventory_ui_system)
+///     .run();
+/// ```
+#[derive(Component, Clone, Copy, Debug, PartialEq, Eq)]
+pub struct EguiUiSchedule(pub InternedScheduleLabel);
+
+/// Marks a context whose [`EguiUiSchedule`] panicked, the context doesn't run passes (and its
+/// schedule doesn't run) until the marker is removed.
+///
+/// Removing the marker doesn't reset the state of the context (the memory of its widgets),
+/// call [`egui::Context::memory_mut`] for that if needed.
+#[derive(Component, Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct EguiContextPoisoned;
+
+/// Is sent when the [`EguiUiSchedule`] of a context panics (so the game can show a fallback).
+#[derive(Event, Clone, Debug, PartialEq, Eq)]
+pub struct EguiContextPoisonedEvent {
+    /// The window entity of the context.
+    pub context: Entity,
+    /// The panic message.
+    pub message: String,
+}
+
+/// Scale factor that a context renders with, insert it on the window entity to override the one
+/// reported by the window (contexts without this component use [`EguiDpiMode::Native`]).
+///
+/// When a window spans monitors with different scale factors, the window reports the scale factor
+/// of one of them, and it changes as the window moves. Forcing a scale keeps the rendering
+/// consistent on mixed-DPI setups, and rounding it avoids blurry text with fractional scale
+/// factors (such as 1.25). [`EguiSettings::scale_factor`] still applies on top of the mode.
+#[derive(Component, Clone, Copy, Debug, Default, PartialEq, Reflect)]
+#[reflect(Component, Default)]
+pub enum EguiDpiMode {
+    /// Uses [`Window::scale_factor`].
+    #[default]
+    Native,
+    /// Uses the given scale factor regardless of the monitor. Non-positive values mean
+    /// [`EguiDpiMode::Native`].
+    ForceScale(f32),
+    /// Rounds [`Window::scale_factor`] to the nearest integer, but not below `1.0`.
+    RoundToNearestInteger,
+}
+
+impl EguiDpiMode {
+    /// Returns the scale factor to render with, given the scale factor of the window.
+    #[must_use]
+    pub fn scale_factor(&self, window_scale_factor: f32) -> f32 {
+        match *self {
+            EguiDpiMode::ForceScale(scale_factor) if scale_factor > 0.0 => scale_factor,
+            EguiDpiMode::Native | EguiDpiMode::ForceScale(_) => window_scale_factor,
+            EguiDpiMode::RoundToNearestInteger => window_scale_factor.round().max(1.0),
+        }
+    }
+}
+
+/// Clock that a context reads the time from, insert it on the window entity (contexts without
+/// this component use [`EguiTimeSource::Real`]).
+///
+/// Egui animations (spinners, tooltip delays, the blinking text cursor) follow the time passed in
+/// [`egui::RawInput::time`]. Following [`Time<Virtual>`](bevy::time::Virtual) makes them freeze
+/// while the game is paused and slow down with the game, which suits in-world UI screens.
+/// Egui derives frame durations from the difference between the times of consecutive frames, so
+/// they follow the same clock.
+#[derive(Component, Clone, Copy, Debug, Default, PartialEq, Eq, Reflect)]
+#[reflect(Component, Default)]
+pub enum EguiTimeSource {
+    /// [`Time<Real>`](bevy::time::Real), which keeps running while the game is paused.
+    #[default]
+    Real,
+    /// [`Time<Virtual>`](bevy::time::Virtual), which can be paused and scaled.
+    Virtual,
+    /// [`Time<Fixed>`](bevy::time::Fixed), which advances in fixed steps of the fixed timestep.
+    Fixed,
+    /// The [`EguiCustomTime`] component of the window entity, which the application updates
+    /// itself. While the component is missing, Egui advances the time by
+    /// [`egui::RawInput::predicted_dt`] every pass.
+    Custom,
+}
+
+/// Time (in seconds) that a context with [`EguiTimeSource::Custom`] reads, insert it on the window
+/// entity and update it every frame.
+#[derive(Component, Clone, Copy, Debug, Default, PartialEq, Reflect)]
+#[reflect(Component, Default)]
+pub struct EguiCustomTime(pub f64);
+
+#[derive(SystemParam)]
+/// A helper SystemParam that provides a way to get `[EguiContext]` with less boilerplate and
+/// combines a proxy interface to the [`EguiUserTextures`] resource.
+pub struct EguiContexts<'w, 's> {
+    #[allow(clippy::type_complexity)]
+    q: Query<
+        'w,
+        's,
         (
             Entity,
             &'static mut EguiContext,
-            Option<&'static PrimaryWindow>,
+            Has<PrimaryWindow>,
+            Has<EguiPrimaryContext>,
         ),
         With<Window>,
     >,
+    computed_scales: Query<'w, 's, &'static EguiComputedScale>,
     #[cfg(feature = "render")]
     user_textures: ResMut<'w, EguiUserTextures>,
 }
 
 impl<'w, 's> EguiContexts<'w, 's> {
-    /// Egui context of the primary window.
+    /// Egui context of the primary window (or of the entity marked with [`EguiPrimaryContext`]).
     #[must_use]
     pub fn ctx_mut(&mut self) -> &mut egui::Context {
         self.try_ctx_mut()
@@ -371,15 +1491,8 @@ impl<'w, 's> EguiContexts<'w, 's> {
     /// Fallible variant of [`EguiContexts::ctx_mut`].
     #[must_use]
     pub fn try_ctx_mut(&mut self) -> Option<&mut egui::Context> {
-        self.q
-            .iter_mut()
-            .find_map(|(_window_entity, ctx, primary_window)| {
-                if primary_window.is_some() {
-                    Some(ctx.into_inner().get_mut())
-                } else {
-                    None
-                }
-            })
+        let primary_context = self.primary_context_entity()?;
+        self.try_ctx_for_window_mut(primary_context)
     }
 
     /// Egui context of a specific window.
@@ -393,15 +1506,13 @@ impl<'w, 's> EguiContexts<'w, 's> {
     #[must_use]
     #[track_caller]
     pub fn try_ctx_for_window_mut(&mut self, window: Entity) -> Option<&mut egui::Context> {
-        self.q
-            .iter_mut()
-            .find_map(|(window_entity, ctx, _primary_window)| {
-                if window_entity == window {
-                    Some(ctx.into_inner().get_mut())
-                } else {
-                    None
-                }
-            })
+        self.q.iter_mut().find_map(|(window_entity, ctx, ..)| {
+            if window_entity == window {
+                Some(ctx.into_inner().get_mut())
+            } else {
+                None
+            }
+        })
     }
 
     /// Allows to get multiple contexts at the same time. This function is useful when you want
@@ -413,10 +1524,10 @@ impl<'w, 's> EguiContexts<'w, 's> {
     ) -> Result<[&mut egui::Context; N], QueryEntityError> {
         self.q
             .get_many_mut(ids)
-            .map(|arr| arr.map(|(_window_entity, ctx, _primary_window)| ctx.into_inner().get_mut()))
+            .map(|arr| arr.map(|(_window_entity, ctx, ..)| ctx.into_inner().get_mut()))
     }
 
-    /// Egui context of the primary window.
+    /// Egui context of the primary window (or of the entity marked with [`EguiPrimaryContext`]).
     ///
     /// Even though the mutable borrow isn't necessary, as the context is wrapped into `RwLock`,
     /// using the immutable getter is gated with the `immutable_ctx` feature. Using the immutable
@@ -444,15 +1555,7 @@ impl<'w, 's> EguiContexts<'w, 's> {
     #[cfg(feature = "immutable_ctx")]
     #[must_use]
     pub fn try_ctx(&self) -> Option<&egui::Context> {
-        self.q
-            .iter()
-            .find_map(|(_window_entity, ctx, primary_window)| {
-                if primary_window.is_some() {
-                    Some(ctx.get())
-                } else {
-                    None
-                }
-            })
+        self.try_ctx_for_window(self.primary_context_entity()?)
     }
 
     /// Egui context of a specific window.
@@ -484,17 +1587,108 @@ impl<'w, 's> EguiContexts<'w, 's> {
     #[track_caller]
     #[cfg(feature = "immutable_ctx")]
     pub fn try_ctx_for_window(&self, window: Entity) -> Option<&egui::Context> {
+        self.q.iter().find_map(|(window_entity, ctx, ..)| {
+            if window_entity == window {
+                Some(ctx.get())
+            } else {
+                None
+            }
+        })
+    }
+
+    /// Returns the entity of the context used by [`EguiContexts::ctx_mut`]: the one marked with
+    /// [`EguiPrimaryContext`] if there's any, or the one of the [`PrimaryWindow`] otherwise.
+    #[must_use]
+    pub fn primary_context_entity(&self) -> Option<Entity> {
         self.q
             .iter()
-            .find_map(|(window_entity, ctx, _primary_window)| {
-                if window_entity == window {
-                    Some(ctx.get())
-                } else {
-                    None
-                }
+            .filter(|(_, _, _, is_primary_context)| *is_primary_context)
+            .map(|(entity, ..)| entity)
+            .min_by_key(|entity| entity.index())
+            .or_else(|| {
+                self.q.iter().find_map(|(entity, _, is_primary_window, _)| {
+                    is_primary_window.then_some(entity)
+                })
+            })
+    }
+
+    /// Iterates over all the contexts, with their entities and kinds.
+    ///
+    /// For example, drawing a label in the corner of every context:
+    ///
+    /// ```rust
+    /// use bevy_egui::EguiContexts;
+    ///
+    /// fn corner_label_system(mut contexts: EguiContexts) {
+    ///     for (entity, kind, ctx) in contexts.iter_mut() {
+    ///         egui::Area::new(egui::Id::new(("corner_label", entity)))
+    ///             .anchor(egui::Align2::RIGHT_BOTTOM, [-4.0, -4.0])
+    ///             .show(ctx, |ui| ui.label(format!("{kind:?}")));
+    ///     }
+    /// }
+    /// ```
+    pub fn iter_mut(
+        &mut self,
+    ) -> impl Iterator<Item = (Entity, EguiContextKind, &mut egui::Context)> + '_ {
+        self.q
+            .iter_mut()
+            .map(|(entity, ctx, is_primary_window, _)| {
+                (
+                    entity,
+                    EguiContextKind::new(is_primary_window),
+                    ctx.into_inner().get_mut(),
+                )
             })
     }
 
+    /// Same as [`EguiContexts::iter_mut`], but also returns the [`EguiUserTextures`], so that
+    /// images can be added while iterating over the contexts.
+    #[cfg(feature = "render")]
+    pub fn iter_mut_with_user_textures(
+        &mut self,
+    ) -> (
+        impl Iterator<Item = (Entity, EguiContextKind, &mut egui::Context)> + '_,
+        &mut EguiUserTextures,
+    ) {
+        let contexts = self
+            .q
+            .iter_mut()
+            .map(|(entity, ctx, is_primary_window, _)| {
+                (
+                    entity,
+                    EguiContextKind::new(is_primary_window),
+                    ctx.into_inner().get_mut(),
+                )
+            });
+        (contexts, &mut self.user_textures)
+    }
+
+    /// Returns the number of contexts.
+    #[must_use]
+    pub fn len(&self) -> usize {
+        self.q.iter().len()
+    }
+
+    /// Returns `true` if there are no contexts.
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.q.is_empty()
+    }
+
+    /// Returns `true` if the entity has a context.
+    #[must_use]
+    pub fn contains(&self, entity: Entity) -> bool {
+        self.q.contains(entity)
+    }
+
+    /// Returns the pixels per point and the logical size Egui uses for a context this frame.
+    ///
+    /// Returns [`None`] if the context isn't initialized yet.
+    #[must_use]
+    pub fn computed_scale(&self, window: Entity) -> Option<EguiComputedScale> {
+        self.computed_scales.get(window).ok().copied()
+    }
+
     /// Can accept either a strong or a weak handle.
     ///
     /// You may want to pass a weak handle if you control removing texture assets in your
@@ -507,7 +1701,20 @@ impl<'w, 's> EguiContexts<'w, 's> {
         self.user_textures.add_image(image)
     }
 
-    /// Removes the image handle and an Egui texture id associated with it.
+    /// Same as [`EguiContexts::add_image`], but Egui will sample the image with the passed
+    /// sampler instead of the one of the image asset.
+    ///
+    /// See [`EguiUserTextures::add_image_with_sampler`].
+    #[cfg(feature = "render")]
+    pub fn add_image_with_sampler(
+        &mut self,
+        image: Handle<Image>,
+        sampler: ImageSamplerDescriptor,
+    ) -> egui::TextureId {
+        self.user_textures.add_image_with_sampler(image, sampler)
+    }
+
+    /// Removes the image handle and Egui texture ids associated with it.
     #[cfg(feature = "render")]
     #[track_caller]
     pub fn remove_image(&mut self, image: &Handle<Image>) -> Option<egui::TextureId> {
@@ -524,13 +1731,92 @@ impl<'w, 's> EguiContexts<'w, 's> {
 }
 
 /// A resource for storing `bevy_egui` user textures.
+///
+/// Plugins that track the ids of their textures themselves can register them in a namespace of
+/// their own (see [`EguiUserTextures::namespace`]), whose ids never collide with the ids of the
+/// other namespaces, whatever these plugins do with them.
 #[derive(Clone, Resource, Default, ExtractResource)]
 #[cfg(feature = "render")]
 pub struct EguiUserTextures {
+    textures: HashMap<Handle<Image>, u64>,
+    /// Textures sampled with a custom sampler instead of the one of their image.
+    /// An image can be registered with several different samplers.
+    sampled_textures: HashMap<Handle<Image>, Vec<(u64, ImageSamplerDescriptor)>>,
+    /// Incremented every time a registered image changes.
+    generations: HashMap<AssetId<Image>, u64>,
+    last_texture_id: u64,
+    /// The namespaces, the first one gets the ids of namespace `1`.
+    namespaces: Vec<UserTextureNamespace>,
+}
+
+/// Textures registered in a namespace, see [`EguiUserTextures::namespace`].
+#[cfg(feature = "render")]
+#[derive(Clone)]
+struct UserTextureNamespace {
+    name: String,
     textures: HashMap<Handle<Image>, u64>,
     last_texture_id: u64,
 }
 
+/// Number of bits of the user texture ids that count the textures of a namespace, the bits above
+/// identify the namespace.
+#[cfg(feature = "render")]
+const USER_TEXTURE_NAMESPACE_SHIFT: u32 = 48;
+
+/// A namespace of [`EguiUserTextures`], returned by [`EguiUserTextures::namespace`].
+#[cfg(feature = "render")]
+pub struct EguiUserTextureNamespace<'a> {
+    user_textures: &'a mut EguiUserTextures,
+    index: usize,
+}
+
+#[cfg(feature = "render")]
+impl EguiUserTextureNamespace<'_> {
+    /// The name of the namespace.
+    pub fn name(&self) -> &str {
+        &self.user_textures.namespaces[self.index].name
+    }
+
+    /// Same as [`EguiUserTextures::add_image`], the id is allocated in the namespace.
+    pub fn add_image(&mut self, image: Handle<Image>) -> egui::TextureId {
+        let namespace_bits = (self.index as u64 + 1) << USER_TEXTURE_NAMESPACE_SHIFT;
+        let namespace = &mut self.user_textures.namespaces[self.index];
+        let id = *namespace.textures.entry(image.clone()).or_insert_with(|| {
+            let id = namespace_bits | namespace.last_texture_id;
+            log::debug!(
+                "Add a new image to the namespace {:?} (id: {}, handle: {:?})",
+                namespace.name,
+                id,
+                image
+            );
+            namespace.last_texture_id += 1;
+            id
+        });
+        egui::TextureId::User(id)
+    }
+
+    /// Removes the image handle from the namespace, the registrations of the other namespaces
+    /// are kept.
+    pub fn remove_image(&mut self, image: &Handle<Image>) -> Option<egui::TextureId> {
+        let id = self.user_textures.namespaces[self.index]
+            .textures
+            .remove(image)?;
+        if !self.user_textures.is_registered(image) {
+            self.user_textures.generations.remove(&image.id());
+        }
+        Some(egui::TextureId::User(id))
+    }
+
+    /// Returns the texture id of the image in the namespace.
+    #[must_use]
+    pub fn image_id(&self, image: &Handle<Image>) -> Option<egui::TextureId> {
+        self.user_textures.namespaces[self.index]
+            .textures
+            .get(image)
+            .map(|&id| egui::TextureId::User(id))
+    }
+}
+
 #[cfg(feature = "render")]
 impl EguiUserTextures {
     /// Can accept either a strong or a weak handle.
@@ -550,13 +1836,198 @@ impl EguiUserTextures {
         egui::TextureId::User(id)
     }
 
-    /// Removes the image handle and an Egui texture id associated with it.
+    /// Same as [`EguiUserTextures::add_image`], but Egui will sample the image with the passed
+    /// sampler instead of the one of the image asset.
+    ///
+    /// This allows, for example, to render pixel-art icons with nearest filtering in the UI
+    /// without changing the image used elsewhere. Registering the same image with a different
+    /// sampler gives a different texture id.
+    pub fn add_image_with_sampler(
+        &mut self,
+        image: Handle<Image>,
+        sampler: ImageSamplerDescriptor,
+    ) -> egui::TextureId {
+        let samplers = self.sampled_textures.entry(image.clone()).or_default();
+        if let Some((id, _)) = samplers
+            .iter()
+            .find(|(_, existing)| existing.as_wgpu() == sampler.as_wgpu())
+        {
+            return egui::TextureId::User(*id);
+        }
+
+        let id = self.last_texture_id;
+        log::debug!(
+            "Add a new image with a custom sampler (id: {}, handle: {:?})",
+            id,
+            image
+        );
+        self.last_texture_id += 1;
+        samplers.push((id, sampler));
+        egui::TextureId::User(id)
+    }
+
+    /// Returns a namespace, created the first time its name is used. The ids of a namespace
+    /// never collide with the ids of the other namespaces (nor with the ones of the methods of
+    /// [`EguiUserTextures`], which make the namespace `0`), so a plugin that keeps ids around
+    /// after removing their images can only reference its own textures.
+    ///
+    /// ```rust
+    /// use bevy::prelude::*;
+    /// use bevy_egui::EguiUserTextures;
+    ///
+    /// #[derive(Resource)]
+    /// struct MinimapImage(Handle<Image>);
+    ///
+    /// fn minimap_system(mut user_textures: ResMut<EguiUserTextures>, image: Res<MinimapImage>) {
+    ///     let texture_id = user_textures.namespace("minimap").add_image(image.0.clone_weak());
+    ///     # let _ = texture_id;
+    /// }
+    /// ```
+    ///
+    /// # Panics
+    ///
+    /// Panics if more than 65535 namespaces are created.
+    pub fn namespace(&mut self, name: &str) -> EguiUserTextureNamespace<'_> {
+        let index = match self
+            .namespaces
+            .iter()
+            .position(|namespace| namespace.name == name)
+        {
+            Some(index) => index,
+            None => {
+                assert!(
+                    self.namespaces.len() < u16::MAX as usize,
+                    "Too many user texture namespaces"
+                );
+                self.namespaces.push(UserTextureNamespace {
+                    name: name.to_owned(),
+                    textures: HashMap::default(),
+                    last_texture_id: 0,
+                });
+                self.namespaces.len() - 1
+            }
+        };
+        EguiUserTextureNamespace {
+            user_textures: self,
+            index,
+        }
+    }
+
+    /// Returns the name of the namespace a user texture id belongs to, [`None`] for the ids of
+    /// the namespace `0` (and the ids of namespaces that don't exist).
+    #[must_use]
+    pub fn namespace_of(&self, id: u64) -> Option<&str> {
+        let index = (id >> USER_TEXTURE_NAMESPACE_SHIFT).checked_sub(1)?;
+        self.namespaces
+            .get(index as usize)
+            .map(|namespace| namespace.name.as_str())
+    }
+
+    /// Returns `true` if the image is registered in any namespace.
+    fn is_registered(&self, image: &Handle<Image>) -> bool {
+        self.textures.contains_key(image)
+            || self.sampled_textures.contains_key(image)
+            || self
+                .namespaces
+                .iter()
+                .any(|namespace| namespace.textures.contains_key(image))
+    }
+
+    /// Removes an image from all the namespaces, once its asset is removed.
+    fn remove_image_from_all_namespaces(&mut self, image: &Handle<Image>) {
+        self.remove_image(image);
+        for namespace in &mut self.namespaces {
+            namespace.textures.remove(image);
+        }
+        self.generations.remove(&image.id());
+    }
+
+    /// Removes the image handle and Egui texture ids associated with it, including the ones
+    /// added with custom samplers. The registrations of the namespaces are kept.
+    ///
+    /// Returns the texture id added via [`EguiUserTextures::add_image`].
     pub fn remove_image(&mut self, image: &Handle<Image>) -> Option<egui::TextureId> {
         let id = self.textures.remove(image);
-        log::debug!("Remove image (id: {:?}, handle: {:?})", id, image);
+        let sampled = self.sampled_textures.remove(image);
+        if !self.is_registered(image) {
+            self.generations.remove(&image.id());
+        }
+        log::debug!(
+            "Remove image (id: {:?}, ids with custom samplers: {:?}, handle: {:?})",
+            id,
+            sampled
+                .iter()
+                .flatten()
+                .map(|(id, _)| *id)
+                .collect::<Vec<_>>(),
+            image
+        );
         id.map(egui::TextureId::User)
     }
 
+    /// Returns the number of registered texture ids, including the ones added with custom
+    /// samplers.
+    #[must_use]
+    pub fn len(&self) -> usize {
+        self.textures.len()
+            + self.sampled_textures.values().map(Vec::len).sum::<usize>()
+            + self
+                .namespaces
+                .iter()
+                .map(|namespace| namespace.textures.len())
+                .sum::<usize>()
+    }
+
+    /// Returns `true` if no textures are registered.
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Returns the number of images the internal maps can hold without reallocating.
+    #[must_use]
+    pub fn capacity(&self) -> usize {
+        self.textures
+            .capacity()
+            .max(self.sampled_textures.capacity())
+            .max(self.generations.capacity())
+    }
+
+    /// Shrinks the internal maps as much as possible, which is useful after removing lots of
+    /// textures in a burst.
+    ///
+    /// Removed texture ids are never reused (ids only grow), so this can't make live ids collide.
+    pub fn shrink_to_fit(&mut self) {
+        self.textures.shrink_to_fit();
+        self.sampled_textures.shrink_to_fit();
+        self.generations.shrink_to_fit();
+        for namespace in &mut self.namespaces {
+            namespace.textures.shrink_to_fit();
+        }
+    }
+
+    /// Returns `true` if the user texture id was added and hasn't been removed.
+    #[must_use]
+    pub fn contains_texture_id(&self, id: u64) -> bool {
+        if let Some(index) = (id >> USER_TEXTURE_NAMESPACE_SHIFT).checked_sub(1) {
+            return self
+                .namespaces
+                .get(index as usize)
+                .is_some_and(|namespace| {
+                    namespace
+                        .textures
+                        .values()
+                        .any(|&texture_id| texture_id == id)
+                });
+        }
+        self.textures.values().any(|&texture_id| texture_id == id)
+            || self
+                .sampled_textures
+                .values()
+                .flatten()
+                .any(|&(texture_id, _)| texture_id == id)
+    }
+
     /// Returns an associated Egui texture id.
     #[must_use]
     pub fn image_id(&self, image: &Handle<Image>) -> Option<egui::TextureId> {
@@ -564,11 +2035,49 @@ impl EguiUserTextures {
             .get(image)
             .map(|&id| egui::TextureId::User(id))
     }
+
+    /// Returns a texture id associated with the image and the sampler, if it was added
+    /// via [`EguiUserTextures::add_image_with_sampler`].
+    #[must_use]
+    pub fn image_id_with_sampler(
+        &self,
+        image: &Handle<Image>,
+        sampler: &ImageSamplerDescriptor,
+    ) -> Option<egui::TextureId> {
+        self.sampled_textures
+            .get(image)?
+            .iter()
+            .find(|(_, existing)| existing.as_wgpu() == sampler.as_wgpu())
+            .map(|(id, _)| egui::TextureId::User(*id))
+    }
+
+    /// Marks a registered image as changed, which makes its bind groups get recreated.
+    ///
+    /// Images modified via [`Assets`] (including hot-reloaded ones) are marked automatically,
+    /// call this if the underlying GPU texture of an image is updated some other way.
+    pub fn mark_changed(&mut self, image: &Handle<Image>) {
+        if self.is_registered(image) {
+            *self.generations.entry(image.id()).or_default() += 1;
+        }
+    }
+
+    /// Returns how many times a registered image has changed (`0` for images that haven't
+    /// changed or aren't registered).
+    #[must_use]
+    pub fn generation(&self, image: &Handle<Image>) -> u64 {
+        self.generations
+            .get(&image.id())
+            .copied()
+            .unwrap_or_default()
+    }
 }
 
 /// Stores physical size and scale factor, is used as a helper to calculate logical size.
-#[derive(Component, Debug, Default, Clone, Copy, PartialEq)]
-#[cfg_attr(feature = "render", derive(ExtractComponent))]
+///
+/// Isn't extracted as a component, the render world reads it from
+/// `render_systems::ExtractedEguiRenderTargets`.
+#[derive(Component, Debug, Default, Clone, Copy, PartialEq, Reflect)]
+#[reflect(Component, Default)]
 pub struct WindowSize {
     /// Physical width
     pub physical_width: f32,
@@ -578,6 +2087,20 @@ pub struct WindowSize {
     pub scale_factor: f32,
 }
 
+/// Size of the canvas of a window, as observed in the browser, which a context uses instead of
+/// the size of the window.
+///
+/// On the web, the canvas can be resized by CSS (a flexbox container changing, the browser zoom,
+/// etc) without Bevy getting a window resize right away, which leaves the screen rect and the
+/// pointer positions of the context wrong until the next one. With
+/// [`EguiSettings::observe_canvas_resizes`], the component is kept up to date from a
+/// `ResizeObserver` during [`EguiSet::InitContexts`], so the context follows the canvas in the
+/// same frame. The [`Window`] itself isn't changed (resizing it would make `bevy_winit` set a
+/// fixed size on the canvas, breaking `fit_canvas_to_parent`).
+#[derive(Component, Debug, Default, Clone, Copy, PartialEq, Reflect)]
+#[reflect(Component, Default)]
+pub struct EguiObservedCanvasSize(pub WindowSize);
+
 impl WindowSize {
     fn new(physical_width: f32, physical_height: f32, scale_factor: f32) -> Self {
         Self {
@@ -600,71 +2123,481 @@ impl WindowSize {
     }
 }
 
-/// The names of `bevy_egui` nodes.
-pub mod node {
-    /// The main egui pass.
-    pub const EGUI_PASS: &str = "egui_pass";
+/// Scale and size that Egui uses for a context, as computed during [`EguiSet::InitContexts`].
+///
+/// Prefer reading this component over recomputing the values from [`Window::scale_factor`] and
+/// [`EguiSettings::scale_factor`]: the render world uses the same values, so coordinates derived
+/// from it always match what gets painted (it's extracted into
+/// `render_systems::ExtractedEguiRenderTargets` when it changes).
+#[derive(Component, Debug, Default, Clone, Copy, PartialEq, Reflect)]
+#[reflect(Component, Default)]
+pub struct EguiComputedScale {
+    /// Physical pixels per Egui point (window scale factor, as adjusted by the [`EguiDpiMode`],
+    /// multiplied by [`EguiSettings::scale_factor`]).
+    pub pixels_per_point: f32,
+    /// Size of the context's screen rect in Egui points.
+    pub logical_size: Vec2,
 }
 
-#[derive(SystemSet, Clone, Hash, Debug, Eq, PartialEq)]
-/// The `bevy_egui` plugin startup system sets.
-pub enum EguiStartupSet {
-    /// Initializes Egui contexts for available windows.
-    InitContexts,
+/// Marks the context that [`EguiContexts::ctx_mut`] and other primary context getters return,
+/// instead of the context of the [`PrimaryWindow`].
+///
+/// If several entities have the marker, the one with the lowest index is used.
+#[derive(Component, Debug, Default, Clone, Copy, Reflect)]
+#[reflect(Component, Default)]
+pub struct EguiPrimaryContext;
+
+/// Moves the [`EguiPrimaryContext`] marker with [`EntityCommands`].
+///
+/// ```rust
+/// use bevy::prelude::*;
+/// use bevy_egui::EguiPrimaryContextCommandsExt;
+///
+/// fn focus_editor_window_system(mut commands: Commands, windows: Query<(Entity, &Window)>) {
+///     for (entity, window) in windows.iter() {
+///         if window.title == "Editor" {
+///             commands.entity(entity).set_primary_egui_context();
+///         }
+///     }
+/// }
+/// ```
+pub trait EguiPrimaryContextCommandsExt {
+    /// Makes the context of the entity the primary one: inserts [`EguiPrimaryContext`] on it and
+    /// removes it from the other entities, once the commands are applied.
+    fn set_primary_egui_context(&mut self) -> &mut Self;
 }
 
-/// The `bevy_egui` plugin system sets.
-#[derive(SystemSet, Clone, Hash, Debug, Eq, PartialEq)]
-pub enum EguiSet {
-    /// Initializes Egui contexts for newly created windows.
-    InitContexts,
-    /// Reads Egui inputs (keyboard, mouse, etc) and writes them into the [`EguiInput`] resource.
-    ///
-    /// To modify the input, you can hook your system like this:
-    ///
-    /// `system.after(EguiSet::ProcessInput).before(EguiSet::BeginFrame)`.
-    ProcessInput,
-    /// Begins the `egui` frame.
-    BeginFrame,
-    /// Processes the [`EguiOutput`] resource.
-    ProcessOutput,
+impl EguiPrimaryContextCommandsExt for EntityCommands<'_> {
+    fn set_primary_egui_context(&mut self) -> &mut Self {
+        self.add(|entity: Entity, world: &mut World| {
+            let others: Vec<Entity> = world
+                .query_filtered::<Entity, With<EguiPrimaryContext>>()
+                .iter(world)
+                .filter(|&other| other != entity)
+                .collect();
+            for other in others {
+                world.entity_mut(other).remove::<EguiPrimaryContext>();
+            }
+            if let Some(mut entity) = world.get_entity_mut(entity) {
+                entity.insert(EguiPrimaryContext);
+            }
+        })
+    }
 }
 
-impl Plugin for EguiPlugin {
-    fn build(&self, app: &mut App) {
-        app.register_type::<EguiSettings>();
+/// A stable identity of a context, insert it along with the [`Window`] to keep Egui memory
+/// (window positions, collapsing states, widget data) when the window gets recreated.
+///
+/// When a context with an id is despawned, its [`egui::Memory`] is parked in the
+/// [`EguiContextRegistry`], and a context created later with the same id starts with it.
+/// The id has to be present when the context is created, adding it later has no effect.
+#[derive(Component, Clone, Debug, PartialEq, Eq, Hash, Reflect)]
+#[reflect(Component)]
+pub struct EguiContextId(pub String);
 
-        let world = &mut app.world;
-        world.init_resource::<EguiSettings>();
-        #[cfg(feature = "render")]
-        world.init_resource::<EguiManagedTextures>();
-        #[cfg(all(feature = "manage_clipboard", not(target_os = "android")))]
-        world.init_resource::<EguiClipboard>();
-        #[cfg(all(
-            feature = "manage_clipboard",
-            target_arch = "wasm32",
-            web_sys_unstable_apis
-        ))]
-        world.init_non_send_resource::<web_clipboard::SubscribedEvents>();
-        #[cfg(feature = "render")]
-        world.init_resource::<EguiUserTextures>();
-        #[cfg(feature = "render")]
-        app.add_plugins(ExtractResourcePlugin::<EguiUserTextures>::default());
-        #[cfg(feature = "render")]
-        app.add_plugins(ExtractResourcePlugin::<ExtractedEguiManagedTextures>::default());
-        #[cfg(feature = "render")]
-        app.add_plugins(ExtractResourcePlugin::<EguiSettings>::default());
-        #[cfg(feature = "render")]
-        app.add_plugins(ExtractComponentPlugin::<EguiContext>::default());
-        #[cfg(feature = "render")]
-        app.add_plugins(ExtractComponentPlugin::<WindowSize>::default());
-        #[cfg(feature = "render")]
-        app.add_plugins(ExtractComponentPlugin::<EguiRenderOutput>::default());
+/// Tracks the contexts with an [`EguiContextId`] and keeps the memories of the despawned ones.
+#[derive(Resource)]
+pub struct EguiContextRegistry {
+    /// The maximum number of parked memories (`16` by default). The ones parked earliest are
+    /// dropped first.
+    pub max_parked: usize,
+    live: bevy::utils::HashMap<String, (Entity, egui::Context)>,
+    /// Memories of despawned contexts, from the earliest parked to the latest.
+    parked: Vec<(String, egui::Memory)>,
+}
 
-        #[cfg(all(
-            feature = "manage_clipboard",
-            target_arch = "wasm32",
-            web_sys_unstable_apis
+impl Default for EguiContextRegistry {
+    fn default() -> Self {
+        Self {
+            max_parked: 16,
+            live: Default::default(),
+            parked: Vec::new(),
+        }
+    }
+}
+
+impl EguiContextRegistry {
+    /// Returns `true` if the memory of a despawned context with the id is parked.
+    #[must_use]
+    pub fn is_parked(&self, id: &str) -> bool {
+        self.parked.iter().any(|(parked_id, _)| parked_id == id)
+    }
+
+    /// Returns the number of parked memories.
+    #[must_use]
+    pub fn parked_len(&self) -> usize {
+        self.parked.len()
+    }
+
+    /// Drops the parked memory of the id, returns `false` if there's none.
+    pub fn remove_parked(&mut self, id: &str) -> bool {
+        let len = self.parked.len();
+        self.parked.retain(|(parked_id, _)| parked_id != id);
+        self.parked.len() != len
+    }
+
+    /// Drops all the parked memories.
+    pub fn clear_parked(&mut self) {
+        self.parked.clear();
+    }
+
+    /// Registers a new context, restoring the parked memory of its id.
+    fn register(&mut self, id: &str, entity: Entity, ctx: &egui::Context) {
+        if let Some((live_entity, _)) = self.live.get(id) {
+            if *live_entity != entity {
+                log::warn!("Entities {live_entity:?} and {entity:?} have the same `EguiContextId` ({id:?}), the memory of the latter won't be kept");
+                return;
+            }
+        }
+        if let Some(index) = self
+            .parked
+            .iter()
+            .position(|(parked_id, _)| parked_id == id)
+        {
+            let (_, memory) = self.parked.remove(index);
+            ctx.memory_mut(|ctx_memory| *ctx_memory = memory);
+        }
+        self.live.insert(id.to_owned(), (entity, ctx.clone()));
+    }
+
+    /// Parks the memories of the contexts that don't exist anymore.
+    fn park_despawned(&mut self, exists: impl Fn(Entity) -> bool) {
+        let despawned: Vec<String> = self
+            .live
+            .iter()
+            .filter(|(_, (entity, _))| !exists(*entity))
+            .map(|(id, _)| id.clone())
+            .collect();
+        for id in despawned {
+            let (_, ctx) = self.live.remove(&id).unwrap();
+            self.parked.retain(|(parked_id, _)| *parked_id != id);
+            self.parked.push((id, ctx.memory(|memory| memory.clone())));
+        }
+        let excess = self.parked.len().saturating_sub(self.max_parked);
+        self.parked.drain(..excess);
+    }
+}
+
+/// Kind of the render target an Egui context is attached to, see [`EguiContextInitialized`] and
+/// [`EguiContexts::iter_mut`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum EguiContextKind {
+    /// The context belongs to the [`PrimaryWindow`].
+    PrimaryWindow,
+    /// The context belongs to a secondary window.
+    Window,
+}
+
+impl EguiContextKind {
+    pub(crate) fn new(is_primary_window: bool) -> Self {
+        if is_primary_window {
+            EguiContextKind::PrimaryWindow
+        } else {
+            EguiContextKind::Window
+        }
+    }
+}
+
+/// Is sent once for every Egui context, during the first [`EguiSet::InitContexts`] (or
+/// [`EguiStartupSet::InitContexts`]) run in which the context has a valid screen rect.
+///
+/// Use it for configuring a context exactly once (installing fonts, setting style, etc.),
+/// including for windows created at runtime.
+#[derive(Event, Debug, Clone, Copy, PartialEq, Eq)]
+pub struct EguiContextInitialized {
+    /// Entity that holds the context.
+    pub entity: Entity,
+    /// Kind of the context.
+    pub kind: EguiContextKind,
+}
+
+/// Is sent when the size or the scale factor of a context's render target changes, including
+/// the first time the context gets a valid size.
+///
+/// The size of a render target with zero width or height isn't passed to Egui, so no event is sent
+/// until it becomes valid.
+#[derive(Event, Debug, Clone, Copy, PartialEq)]
+pub struct EguiRenderTargetResized {
+    /// Entity that holds the context.
+    pub entity: Entity,
+    /// Previous size, is zeroed if the context hasn't had a valid size before.
+    pub old: WindowSize,
+    /// New size.
+    pub new: WindowSize,
+}
+
+/// Marks an Egui context that has been initialized and is ready to be used.
+///
+/// Gets inserted at the same time as [`EguiContextInitialized`] is sent, so
+/// `Added<EguiContextReady>` can be used instead of reading the event.
+#[derive(Component, Debug, Default, Clone, Copy)]
+pub struct EguiContextReady;
+
+/// Geometry of the text cursor of a context, is updated during [`EguiSet::ProcessOutput`].
+///
+/// Can be used for positioning custom on-screen keyboards. The rects are in Egui points,
+/// use [`helpers::egui_to_physical_rect`] to convert them into physical window pixels.
+#[derive(Component, Debug, Default, Clone, Copy, PartialEq, Reflect)]
+#[reflect(Component, Default)]
+pub struct EguiTextCursorInfo {
+    /// Whether an editable text field has keyboard focus.
+    pub active: bool,
+    /// Where the primary cursor (caret) is, a very thin rect.
+    #[reflect(ignore)]
+    pub caret_rect: Option<egui::Rect>,
+    /// Where the focused text field is located.
+    #[reflect(ignore)]
+    pub text_edit_rect: Option<egui::Rect>,
+}
+
+/// Whether a context enables the IME of its window only while an Egui text field has focus,
+/// insert it on the window entity (contexts without this component use
+/// [`EguiWindowImeMode::Managed`]).
+///
+/// Some input methods (fcitx5 on Linux, for example) show their pre-edit popup on any key press
+/// while [`Window::ime_enabled`] is set, so it's better to keep it disabled unless text is being
+/// edited, like `eframe` does.
+#[derive(Component, Clone, Copy, Debug, Default, PartialEq, Eq, Reflect)]
+#[reflect(Component, Default)]
+pub enum EguiWindowImeMode {
+    /// Sets [`Window::ime_enabled`] during [`EguiSet::ProcessOutput`], depending on whether an
+    /// editable text field has focus (see [`EguiTextCursorInfo::active`]).
+    #[default]
+    Managed,
+    /// Leaves [`Window::ime_enabled`] to the application, for apps with text inputs outside Egui.
+    Unmanaged,
+}
+
+/// The names of `bevy_egui` nodes.
+pub mod node {
+    /// The main egui pass.
+    pub const EGUI_PASS: &str = "egui_pass";
+}
+
+/// The schedules the [`EguiSet`]s (and the systems of the `bevy_egui` plugins) run in, for apps
+/// with a custom main schedule. Is set with [`EguiPlugin::with_schedules`] and inserted as a
+/// resource by [`EguiPlugin`].
+///
+/// The sets are placed in [`PreUpdate`] and [`PostUpdate`] by default. Sets that share a schedule
+/// are ordered like the variants of [`EguiSet`], but ordering the schedules themselves is up to
+/// the app: the schedules have to run in the order of the fields, and to run in every frame.
+/// UI systems go between [`EguiSet::BeginFrame`] and [`EguiSet::ProcessOutput`], which ends
+/// the pass.
+/// The other plugins of the crate ([`toast::EguiToastPlugin`], for example) follow the
+/// schedules if they're added after [`EguiPlugin`].
+///
+/// ```no_run,rust
+/// use bevy::{app::MainScheduleOrder, ecs::schedule::ScheduleLabel, prelude::*};
+/// use bevy_egui::{EguiPlugin, EguiSchedules, EguiSet};
+///
+/// #[derive(ScheduleLabel, Clone, Debug, PartialEq, Eq, Hash)]
+/// struct UiSchedule;
+///
+/// let mut app = App::new();
+/// app.add_plugins(DefaultPlugins).init_schedule(UiSchedule);
+/// app.world
+///     .resource_mut::<MainScheduleOrder>()
+///     .insert_after(Update, UiSchedule);
+/// app.add_plugins(EguiPlugin::default().with_schedules(EguiSchedules {
+///     begin_frame: UiSchedule.intern(),
+///     process_output: UiSchedule.intern(),
+///     ..Default::default()
+/// }));
+/// # fn ui_system() {}
+/// app.add_systems(
+///     UiSchedule,
+///     ui_system
+///         .after(EguiSet::BeginFrame)
+///         .before(EguiSet::ProcessOutput),
+/// );
+/// app.run();
+/// ```
+#[derive(Resource, Clone, Debug, PartialEq, Eq)]
+pub struct EguiSchedules {
+    /// The schedule of [`EguiSet::InitContexts`] ([`PreUpdate`] by default).
+    pub init_contexts: InternedScheduleLabel,
+    /// The schedule of [`EguiSet::ProcessInput`] ([`PreUpdate`] by default).
+    pub process_input: InternedScheduleLabel,
+    /// The schedule of [`EguiSet::BeginFrame`] ([`PreUpdate`] by default).
+    pub begin_frame: InternedScheduleLabel,
+    /// The schedule of [`EguiSet::ProcessOutput`] ([`PostUpdate`] by default), the Egui textures
+    /// are updated in it as well.
+    pub process_output: InternedScheduleLabel,
+}
+
+impl Default for EguiSchedules {
+    fn default() -> Self {
+        Self {
+            init_contexts: PreUpdate.intern(),
+            process_input: PreUpdate.intern(),
+            begin_frame: PreUpdate.intern(),
+            process_output: PostUpdate.intern(),
+        }
+    }
+}
+
+impl EguiSchedules {
+    /// Returns the schedules and sets, in the order they run.
+    fn stages(&self) -> [(InternedScheduleLabel, EguiSet); 4] {
+        [
+            (self.init_contexts, EguiSet::InitContexts),
+            (self.process_input, EguiSet::ProcessInput),
+            (self.begin_frame, EguiSet::BeginFrame),
+            (self.process_output, EguiSet::ProcessOutput),
+        ]
+    }
+
+    /// Returns an error if a schedule is used by sets that aren't consecutive, it would have to
+    /// run both before and after another schedule.
+    pub fn validate(&self) -> Result<(), String> {
+        let stages = self.stages();
+        for (i, (schedule, set)) in stages.iter().enumerate() {
+            let mut later_stages = stages[i + 1..].iter();
+            let Some((between_schedule, between_set)) =
+                later_stages.find(|(other, _)| other != schedule)
+            else {
+                break;
+            };
+            if let Some((_, other_set)) = later_stages.find(|(other, _)| other == schedule) {
+                return Err(format!(
+                    "{set:?} and {other_set:?} run in {schedule:?}, but {between_set:?}, which has to run between them, runs in {between_schedule:?}",
+                ));
+            }
+        }
+        Ok(())
+    }
+
+    /// The schedules of the plugins added after [`EguiPlugin`].
+    pub(crate) fn from_app(app: &App) -> Self {
+        app.world
+            .get_resource::<EguiSchedules>()
+            .cloned()
+            .unwrap_or_default()
+    }
+}
+
+#[derive(SystemSet, Clone, Hash, Debug, Eq, PartialEq)]
+/// The `bevy_egui` plugin startup system sets.
+pub enum EguiStartupSet {
+    /// Initializes Egui contexts for available windows.
+    InitContexts,
+}
+
+/// The `bevy_egui` plugin system sets.
+#[derive(SystemSet, Clone, Hash, Debug, Eq, PartialEq)]
+pub enum EguiSet {
+    /// Initializes Egui contexts for newly created windows.
+    InitContexts,
+    /// Reads Egui inputs (keyboard, mouse, etc) and writes them into the [`EguiInput`] resource.
+    /// Consists of the [`input::EguiInputSet`] sets.
+    ///
+    /// To modify the input, you can hook your system like this:
+    ///
+    /// `system.after(EguiSet::ProcessInput).before(EguiSet::BeginFrame)`.
+    ProcessInput,
+    /// Begins the `egui` frame.
+    BeginFrame,
+    /// Processes the [`EguiPlatformOutput`] and [`EguiRenderOutput`] of the contexts.
+    ProcessOutput,
+}
+
+impl Plugin for EguiPlugin {
+    fn build(&self, app: &mut App) {
+        app.register_type::<EguiSettings>()
+            .register_type::<EguiColorMode>()
+            .register_type::<EguiWindowAlphaMode>()
+            .register_type::<LongPressConfig>()
+            .register_type::<Option<bool>>()
+            .register_type::<Option<f32>>()
+            .register_type::<Option<u32>>()
+            .register_type::<Option<String>>()
+            .register_type::<Vec<String>>()
+            .register_type::<EguiSafeArea>()
+            .register_type::<EguiPassRate>()
+            .register_type::<EguiDpiMode>()
+            .register_type::<EguiTimeSource>()
+            .register_type::<EguiCustomTime>()
+            .register_type::<EguiWindowImeMode>()
+            .register_type::<EguiTessellationSettings>()
+            .register_type::<EguiWantsInput>()
+            .register_type::<EguiRenderLimits>()
+            .register_type::<WindowSize>()
+            .register_type::<EguiObservedCanvasSize>()
+            .register_type::<EguiComputedScale>()
+            .register_type::<EguiPrimaryContext>()
+            .register_type::<EguiContextId>()
+            .register_type::<EguiTextCursorInfo>()
+            .register_type::<input::EguiInputSuppressed>();
+        app.add_event::<EguiContextInitialized>()
+            .add_event::<EguiRenderTargetResized>()
+            .add_event::<input::EguiInputEvent>()
+            .add_event::<input::EguiPointerEvent>()
+            .add_event::<EguiForcePass>()
+            .add_event::<EguiContextPoisonedEvent>()
+            .add_event::<EguiOpenUrlRequested>()
+            .add_event::<EguiOpenUrlBlocked>();
+        #[cfg(feature = "render")]
+        app.add_event::<EguiManagedTextureUpdated>()
+            .add_event::<EguiManagedTextureFreed>()
+            .add_event::<EguiManagedTextureEvicted>();
+
+        let world = &mut app.world;
+        if let Some(settings) = &self.settings {
+            world.insert_resource(settings.clone());
+        } else {
+            world.init_resource::<EguiSettings>();
+        }
+        if let Some(context_settings) = &self.context_settings {
+            world.insert_resource(context_settings.clone());
+        } else {
+            world.init_resource::<EguiContextSettings>();
+        }
+        world.init_resource::<EguiCursorMap>();
+        world.init_resource::<EguiContextRegistry>();
+        world.init_resource::<EguiWantsInput>();
+        world.init_resource::<EguiAnyOutputChanged>();
+        world.init_resource::<ModifierKeysState>();
+        world.init_resource::<input::EguiFocusedContext>();
+        world.init_resource::<input::TextInputFocus>();
+        world.init_resource::<input::EguiInputFilters>();
+        world.init_resource::<input::EguiInputStats>();
+        world.init_resource::<input::EguiLastUserActivity>();
+        world.init_resource::<input::EguiRoutingDiagnostics>();
+        world.init_resource::<EguiOpenUrlPolicy>();
+        world.init_resource::<child_context::EguiChildContexts>();
+        #[cfg(feature = "render")]
+        world.init_resource::<EguiManagedTextures>();
+        #[cfg(feature = "render")]
+        world.init_resource::<EguiTextureStats>();
+        #[cfg(all(feature = "manage_clipboard", not(target_os = "android")))]
+        world.init_resource::<EguiClipboard>();
+        #[cfg(all(
+            feature = "manage_clipboard",
+            target_arch = "wasm32",
+            web_sys_unstable_apis
+        ))]
+        world.init_non_send_resource::<web_clipboard::SubscribedEvents>();
+        #[cfg(target_arch = "wasm32")]
+        world.init_non_send_resource::<web_resize::CanvasResizeObservers>();
+        #[cfg(feature = "render")]
+        world.init_resource::<EguiUserTextures>();
+        #[cfg(feature = "render")]
+        app.add_plugins(ExtractResourcePlugin::<EguiUserTextures>::default());
+        #[cfg(feature = "render")]
+        app.add_plugins(ExtractResourcePlugin::<ExtractedEguiManagedTextures>::default());
+        #[cfg(feature = "render")]
+        app.add_plugins(ExtractResourcePlugin::<EguiSettings>::default());
+        #[cfg(feature = "render")]
+        app.add_plugins(ExtractComponentPlugin::<EguiRenderOutput>::default());
+        #[cfg(feature = "render")]
+        app.add_plugins(ExtractComponentPlugin::<EguiColorMode>::default());
+        #[cfg(feature = "render")]
+        app.add_plugins(ExtractComponentPlugin::<EguiWindowAlphaMode>::default());
+
+        #[cfg(all(
+            feature = "manage_clipboard",
+            target_arch = "wasm32",
+            web_sys_unstable_apis
         ))]
         app.add_systems(PreStartup, web_clipboard::startup_setup_web_events);
         app.add_systems(
@@ -673,44 +2606,193 @@ impl Plugin for EguiPlugin {
                 setup_new_windows_system,
                 apply_deferred,
                 update_window_contexts_system,
+                notify_initialized_contexts_system,
             )
                 .chain()
                 .in_set(EguiStartupSet::InitContexts),
         );
+        let schedules = &self.schedules;
+        if let Err(err) = schedules.validate() {
+            panic!("Invalid EguiSchedules: {err}");
+        }
+        app.insert_resource(schedules.clone());
+        let stages = schedules.stages();
+        for ((schedule, set), (next_schedule, next_set)) in stages.iter().zip(&stages[1..]) {
+            if schedule == next_schedule {
+                app.configure_sets(*schedule, set.clone().before(next_set.clone()));
+            }
+        }
         app.add_systems(
-            PreUpdate,
+            schedules.init_contexts,
             (
+                park_despawned_contexts_system,
                 setup_new_windows_system,
                 apply_deferred,
                 update_window_contexts_system,
+                notify_initialized_contexts_system,
+                warn_multiple_primary_contexts_system,
             )
                 .chain()
                 .in_set(EguiSet::InitContexts),
         );
+        #[cfg(target_arch = "wasm32")]
         app.add_systems(
-            PreUpdate,
+            schedules.init_contexts,
+            web_resize::read_canvas_resizes_system
+                .in_set(EguiSet::InitContexts)
+                .after(setup_new_windows_system)
+                .before(update_window_contexts_system),
+        );
+        input::configure_input_sets(app, schedules.process_input);
+        app.add_systems(
+            schedules.process_input,
             process_input_system
-                .in_set(EguiSet::ProcessInput)
+                .in_set(input::EguiInputSet::ReadBevyEvents)
                 .after(InputSystem)
                 .after(EguiSet::InitContexts),
         );
         app.add_systems(
-            PreUpdate,
-            begin_frame_system
+            schedules.process_input,
+            input::write_egui_input_events_system.in_set(input::EguiInputSet::WriteEguiEvents),
+        );
+        app.add_systems(
+            schedules.begin_frame,
+            (
+                schedule_egui_passes_system,
+                begin_frame_system,
+                write_egui_wants_input_system,
+            )
+                .chain()
                 .in_set(EguiSet::BeginFrame)
                 .after(EguiSet::ProcessInput),
         );
         app.add_systems(
-            PostUpdate,
+            schedules.begin_frame,
+            input::pointer::apply_pointer_overrides_system
+                .in_set(EguiSet::BeginFrame)
+                .after(EguiSet::ProcessInput)
+                .before(schedule_egui_passes_system),
+        );
+        app.add_systems(
+            schedules.begin_frame,
+            child_context::begin_child_frames_system
+                .in_set(EguiSet::BeginFrame)
+                .after(schedule_egui_passes_system)
+                .before(begin_frame_system),
+        );
+        app.add_systems(
+            schedules.process_output,
             process_output_system.in_set(EguiSet::ProcessOutput),
         );
+        app.add_systems(
+            schedules.process_output,
+            child_context::end_child_frames_system
+                .in_set(EguiSet::ProcessOutput)
+                .after(process_output_system),
+        );
+        app.add_systems(
+            schedules.process_output,
+            run_ui_schedules_system.before(EguiSet::ProcessOutput),
+        );
+        app.add_systems(
+            schedules.process_output,
+            prewarm_fonts_system.before(EguiSet::ProcessOutput),
+        );
+        app.add_systems(
+            schedules.process_output,
+            debug_overlay_system
+                .before(EguiSet::ProcessOutput)
+                .run_if(|egui_settings: Res<EguiSettings>| egui_settings.show_debug_overlay),
+        );
+        #[cfg(feature = "theme")]
+        app.init_asset::<theme::EguiTheme>()
+            .init_asset_loader::<theme::EguiThemeLoader>()
+            .add_systems(
+                schedules.begin_frame,
+                theme::apply_egui_themes_system
+                    .after(EguiSet::InitContexts)
+                    .before(EguiSet::BeginFrame),
+            );
+        let apply_style_overrides_system = style::apply_style_overrides_system
+            .after(EguiSet::InitContexts)
+            .before(EguiSet::BeginFrame);
+        // Overrides are applied on top of themes.
+        #[cfg(feature = "theme")]
+        let apply_style_overrides_system =
+            apply_style_overrides_system.after(theme::apply_egui_themes_system);
+        app.add_systems(schedules.begin_frame, apply_style_overrides_system);
+        #[cfg(feature = "accesskit")]
+        app.add_systems(
+            schedules.begin_frame,
+            widget_index::enable_widget_index_system
+                .after(EguiSet::InitContexts)
+                .before(EguiSet::BeginFrame),
+        )
+        .add_systems(
+            schedules.process_output,
+            widget_index::update_widget_index_system.after(EguiSet::ProcessOutput),
+        );
+        // Image assets don't exist if the app is built without `RenderPlugin` (in headless tests,
+        // for example), Egui textures aren't needed then.
         #[cfg(feature = "render")]
         app.add_systems(
-            PostUpdate,
-            update_egui_textures_system.after(EguiSet::ProcessOutput),
+            schedules.process_output,
+            update_egui_textures_system
+                .after(EguiSet::ProcessOutput)
+                .run_if(resource_exists::<Assets<Image>>),
+        );
+        #[cfg(feature = "render")]
+        app.add_systems(
+            Last,
+            free_egui_textures_system.run_if(resource_exists::<Assets<Image>>),
         );
         #[cfg(feature = "render")]
-        app.add_systems(Last, free_egui_textures_system)
+        app.add_systems(
+            PostUpdate,
+            pane::resize_pane_viewports_system
+                .before(bevy::render::camera::CameraUpdateSystem)
+                .run_if(resource_exists::<Assets<Image>>),
+        );
+    }
+
+    #[cfg(feature = "render")]
+    fn finish(&self, app: &mut App) {
+        if app.world.contains_resource::<Assets<Shader>>() {
+            load_internal_asset!(app, EGUI_SHADER_HANDLE, "egui.wgsl", Shader::from_wgsl);
+        }
+
+        if let Some(render_device) = app.world.get_resource::<RenderDevice>() {
+            let max_texture_side = render_device.limits().max_texture_dimension_2d as usize;
+            app.world
+                .insert_resource(EguiRenderLimits { max_texture_side });
+        }
+
+        let Ok(render_app) = app.get_sub_app_mut(RenderApp) else {
+            return;
+        };
+        if !render_app.world.contains_resource::<RenderDevice>() {
+            return;
+        }
+        render_app
+            .init_resource::<egui_node::EguiPipeline>()
+            .init_resource::<SpecializedRenderPipelines<EguiPipeline>>()
+            .init_resource::<EguiTransforms>()
+            .init_resource::<render_systems::EguiUserTextureSamplers>()
+            .init_resource::<render_systems::EguiTextureBindGroups>()
+            .init_resource::<render_systems::EguiTextureBindGroupKeys>()
+            .init_resource::<render_systems::ExtractedEguiRenderTargets>()
+            .init_resource::<render_systems::EguiRenderGraphNodes>()
+            .add_systems(
+                ExtractSchedule,
+                (
+                    (
+                        render_systems::teardown_despawned_windows_render_system,
+                        render_systems::setup_new_windows_render_system,
+                    )
+                        .chain(),
+                    render_systems::extract_egui_render_targets_system,
+                ),
+            )
             .add_systems(
                 Render,
                 render_systems::prepare_egui_transforms_system.in_set(RenderSet::Prepare),
@@ -723,35 +2805,6 @@ impl Plugin for EguiPlugin {
                 Render,
                 render_systems::queue_pipelines_system.in_set(RenderSet::Queue),
             );
-
-        #[cfg(feature = "render")]
-        load_internal_asset!(app, EGUI_SHADER_HANDLE, "egui.wgsl", Shader::from_wgsl);
-    }
-
-    #[cfg(feature = "render")]
-    fn finish(&self, app: &mut App) {
-        if let Ok(render_app) = app.get_sub_app_mut(RenderApp) {
-            render_app
-                .init_resource::<egui_node::EguiPipeline>()
-                .init_resource::<SpecializedRenderPipelines<EguiPipeline>>()
-                .init_resource::<EguiTransforms>()
-                .add_systems(
-                    ExtractSchedule,
-                    render_systems::setup_new_windows_render_system,
-                )
-                .add_systems(
-                    Render,
-                    render_systems::prepare_egui_transforms_system.in_set(RenderSet::Prepare),
-                )
-                .add_systems(
-                    Render,
-                    render_systems::queue_bind_groups_system.in_set(RenderSet::Queue),
-                )
-                .add_systems(
-                    Render,
-                    render_systems::queue_pipelines_system.in_set(RenderSet::Queue),
-                );
-        }
     }
 }
 
@@ -768,52 +2821,243 @@ pub struct EguiContextQuery {
     /// Egui shapes and textures delta.
     pub render_output: &'static mut EguiRenderOutput,
     /// Encapsulates [`egui::PlatformOutput`].
-    pub egui_output: &'static mut EguiOutput,
+    pub egui_output: &'static mut EguiPlatformOutput,
     /// Stores physical size of the window and its scale factor.
     pub window_size: &'static mut WindowSize,
+    /// Pixels per point and logical size used by Egui for the window.
+    pub computed_scale: &'static mut EguiComputedScale,
+    /// Geometry of the active text cursor.
+    pub text_cursor_info: &'static mut EguiTextCursorInfo,
+    /// Insets of the window area that Egui shouldn't use.
+    pub safe_area: &'static EguiSafeArea,
     /// [`Window`] component.
     pub window: &'static mut Window,
+    /// Overrides the scale factor of the window.
+    pub dpi_mode: Option<&'static EguiDpiMode>,
+    /// Overrides the clock that the context reads the time from.
+    pub time_source: Option<&'static EguiTimeSource>,
+    /// Time of a context with [`EguiTimeSource::Custom`].
+    pub custom_time: Option<&'static EguiCustomTime>,
+    /// Whether the context manages [`Window::ime_enabled`].
+    pub ime_mode: Option<&'static EguiWindowImeMode>,
+    /// Translates touch long presses into secondary clicks.
+    pub long_press: Option<&'static LongPressConfig>,
+    /// Primitives to paint along with the Egui shapes.
+    pub extra_paint_jobs: Option<&'static mut EguiExtraPaintJobs>,
+    /// Filters applied to the shapes before tessellation.
+    pub shape_filters: Option<&'static EguiShapeFilters>,
+    /// Whether the context has [`EguiSkipTessellation`].
+    pub skip_tessellation: Has<EguiSkipTessellation>,
+    /// Tessellation task of the context.
+    pub async_tessellation: Option<&'static mut EguiAsyncTessellation>,
+    /// Size of the canvas, observed in the browser.
+    pub canvas_size: Option<&'static EguiObservedCanvasSize>,
+    /// Tessellation options of the context.
+    pub tessellation_settings: Option<&'static EguiTessellationSettings>,
+    /// Rects of the areas, rebuilt by every pass.
+    pub occlusion_map: Option<&'static mut EguiOcclusionMap>,
+    /// Whether the last pass changed what gets painted.
+    pub output_changed: Option<&'static mut EguiOutputChanged>,
+}
+
+impl EguiContextQueryItem<'_> {
+    /// Scale factor of the window (or of the [`EguiObservedCanvasSize`]) after applying the
+    /// [`EguiDpiMode`].
+    pub fn dpi_scale_factor(&self) -> f32 {
+        let window_scale_factor = self
+            .canvas_size
+            .map_or(self.window.scale_factor(), |canvas_size| {
+                canvas_size.0.scale_factor
+            });
+        self.dpi_mode.map_or(window_scale_factor, |dpi_mode| {
+            dpi_mode.scale_factor(window_scale_factor)
+        })
+    }
+
+    /// Converts a position in logical window pixels (as reported by cursor and touch events) into
+    /// Egui points.
+    pub fn logical_to_egui_pos(&self, position: Vec2, egui_settings: &EguiSettings) -> egui::Pos2 {
+        // Equals `1.0` unless the DPI mode overrides the scale factor of the window.
+        let dpi_ratio = self.window.scale_factor() / self.dpi_scale_factor();
+        let (x, y): (f32, f32) = (position * dpi_ratio / egui_settings.scale_factor).into();
+        egui::pos2(x, y)
+    }
 }
 
 /// Contains textures allocated and painted by Egui.
+///
+/// The map is keyed by the context entity and the texture id, its iteration order is unspecified.
+/// To mirror the textures, use [`EguiManagedTextureUpdated`] and [`EguiManagedTextureFreed`]
+/// events, which are sent in the order the texture deltas are applied.
 #[cfg(feature = "render")]
 #[derive(Resource, Deref, DerefMut, Default)]
 pub struct EguiManagedTextures(pub HashMap<(Entity, u64), EguiManagedTexture>);
 
+/// Texture counters, updated once per frame (in [`Last`]).
+#[cfg(feature = "render")]
+#[derive(Resource, Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct EguiTextureStats {
+    /// Number of texture ids registered in [`EguiUserTextures`].
+    pub user_textures: usize,
+    /// Number of textures in [`EguiManagedTextures`].
+    pub managed_textures: usize,
+    /// Estimated memory taken by the pixels of the managed textures (4 bytes per pixel), not
+    /// counting the copies in [`Assets<Image>`] and on the GPU.
+    pub managed_texture_bytes: usize,
+}
+
 /// Represents a texture allocated and painted by Egui.
 #[cfg(feature = "render")]
 pub struct EguiManagedTexture {
     /// Assets store handle.
     pub handle: Handle<Image>,
     /// Stored in full so we can do partial updates (which bevy doesn't support).
+    ///
+    /// Its size never exceeds [`EguiRenderLimits::max_texture_side`], as Egui is told the limit.
     pub color_image: egui::ColorImage,
 }
 
+/// Is sent when a texture managed by Egui gets created or updated, in the same frame the texture
+/// delta is applied to [`EguiManagedTextures`].
+///
+/// Allows custom renderers to mirror Egui textures (uploading only the changed rects).
+#[cfg(feature = "render")]
+#[derive(Event, Debug, Clone)]
+pub struct EguiManagedTextureUpdated {
+    /// Entity of the context (window) the texture belongs to.
+    pub context: Entity,
+    /// Id of the texture ([`egui::TextureId::Managed`]).
+    pub texture_id: u64,
+    /// Position of the updated rect, [`None`] if the whole texture was (re)created.
+    pub pos: Option<[usize; 2]>,
+    /// Size of the updated rect, or of the whole texture if `pos` is [`None`].
+    pub size: [usize; 2],
+    /// Handle of the image that contains the whole updated texture.
+    pub handle: Handle<Image>,
+}
+
+/// Is sent when a texture managed by Egui gets freed (or evicted, see
+/// [`EguiManagedTextureBudget`]).
+#[cfg(feature = "render")]
+#[derive(Event, Debug, Clone, Copy, PartialEq, Eq)]
+pub struct EguiManagedTextureFreed {
+    /// Entity of the context (window) the texture belonged to.
+    pub context: Entity,
+    /// Id of the texture ([`egui::TextureId::Managed`]).
+    pub texture_id: u64,
+}
+
 /// Adds bevy_egui components to newly created windows.
+#[allow(clippy::type_complexity)]
 pub fn setup_new_windows_system(
     mut commands: Commands,
-    new_windows: Query<Entity, (Added<Window>, Without<EguiContext>)>,
+    new_windows: Query<(Entity, Option<&EguiContextId>), (Added<Window>, Without<EguiContext>)>,
+    mut registry: ResMut<EguiContextRegistry>,
+    context_settings: Res<EguiContextSettings>,
+    torn_down: Option<Res<EguiTornDown>>,
 ) {
-    for window in new_windows.iter() {
+    if torn_down.is_some() {
+        return;
+    }
+    for (window, context_id) in new_windows.iter() {
+        let context = EguiContext::default();
+        if let Some(EguiContextId(id)) = context_id {
+            registry.register(id, window, &context.ctx);
+        }
         commands.entity(window).insert((
-            EguiContext::default(),
+            context,
             EguiRenderOutput::default(),
             EguiInput::default(),
-            EguiOutput::default(),
+            EguiPlatformOutput::default(),
             WindowSize::default(),
+            EguiComputedScale::default(),
+            EguiTextCursorInfo::default(),
+            EguiSafeArea::default(),
         ));
+        if *context_settings != EguiContextSettings::default() {
+            let context_settings = context_settings.clone();
+            commands.add(move |world: &mut World| {
+                if let Some(mut entity) = world.get_entity_mut(window) {
+                    context_settings.insert_missing(&mut entity);
+                }
+            });
+        }
+    }
+}
+
+/// Parks the memories of despawned contexts with an [`EguiContextId`], see
+/// [`EguiContextRegistry`].
+pub fn park_despawned_contexts_system(
+    mut registry: ResMut<EguiContextRegistry>,
+    contexts: Query<(), With<EguiContext>>,
+) {
+    if registry.live.is_empty() {
+        return;
     }
+    registry.park_despawned(|entity| contexts.contains(entity));
 }
 
 /// Updates textures painted by Egui.
 #[cfg(feature = "render")]
+#[allow(clippy::too_many_arguments, clippy::type_complexity)]
 pub fn update_egui_textures_system(
-    mut egui_render_output: Query<(Entity, &mut EguiRenderOutput), With<Window>>,
+    mut egui_render_output: Query<
+        (
+            Entity,
+            &mut EguiRenderOutput,
+            Option<&mut EguiManagedTextureBudget>,
+            Option<&EguiColorMode>,
+        ),
+        With<Window>,
+    >,
     mut egui_managed_textures: ResMut<EguiManagedTextures>,
     mut image_assets: ResMut<Assets<Image>>,
+    mut updated_events: EventWriter<EguiManagedTextureUpdated>,
+    mut evicted_events: EventWriter<EguiManagedTextureEvicted>,
+    mut freed_events: EventWriter<EguiManagedTextureFreed>,
+    mut last_color_modes: bevy::ecs::system::Local<HashMap<Entity, EguiColorMode>>,
 ) {
-    for (window_id, mut egui_render_output) in egui_render_output.iter_mut() {
+    last_color_modes.retain(|window_id, _| egui_render_output.contains(*window_id));
+    for (window_id, mut egui_render_output, mut budget, color_mode) in egui_render_output.iter_mut()
+    {
+        let color_mode = color_mode.copied().unwrap_or_default();
+        // Textures are uploaded in a different format in each mode.
+        if last_color_modes
+            .insert(window_id, color_mode)
+            .is_some_and(|last| last != color_mode)
+        {
+            for (&(_, texture_id), managed_texture) in egui_managed_textures
+                .iter_mut()
+                .filter(|((context, _), _)| *context == window_id)
+            {
+                let sampler = image_assets
+                    .get(&managed_texture.handle)
+                    .map(|image| image.sampler.clone())
+                    .unwrap_or_default();
+                let image = egui_node::color_image_as_bevy_image(
+                    &managed_texture.color_image,
+                    sampler,
+                    color_mode,
+                );
+                managed_texture.handle = image_assets.add(image);
+                updated_events.send(EguiManagedTextureUpdated {
+                    context: window_id,
+                    texture_id,
+                    pos: None,
+                    size: managed_texture.color_image.size,
+                    handle: managed_texture.handle.clone(),
+                });
+            }
+        }
+
         let set_textures = std::mem::take(&mut egui_render_output.textures_delta.set);
+        if let Some(budget) = &mut budget {
+            for texture_id in &egui_render_output.textures_delta.free {
+                if let egui::TextureId::Managed(texture_id) = texture_id {
+                    budget.forget(*texture_id);
+                }
+            }
+        }
 
         for (texture_id, image_delta) in set_textures {
             let color_image = egui_node::as_color_image(image_delta.image);
@@ -826,31 +3070,109 @@ pub fn update_egui_textures_system(
             let sampler = ImageSampler::Descriptor(
                 egui_node::texture_options_as_sampler_descriptor(&image_delta.options),
             );
-            if let Some(pos) = image_delta.pos {
+            let mut size = color_image.size;
+            let mut pos = image_delta.pos;
+            let evicted = budget
+                .as_mut()
+                .and_then(|budget| pos.and_then(|_| budget.take_evicted(texture_id)));
+            let handle = if let Some(mut evicted) = evicted {
+                // Partial update of an evicted texture, uploaded in full.
+                update_image_rect(&mut evicted.color_image, pos.unwrap(), &color_image);
+                pos = None;
+                size = evicted.color_image.size;
+                texture_budget::EvictedTexture { sampler, ..evicted }.upload(
+                    window_id,
+                    texture_id,
+                    color_mode,
+                    &mut egui_managed_textures,
+                    &mut image_assets,
+                )
+            } else if let Some(pos) = pos {
                 // Partial update.
                 if let Some(managed_texture) =
                     egui_managed_textures.get_mut(&(window_id, texture_id))
                 {
                     // TODO: when bevy supports it, only update the part of the texture that changes.
                     update_image_rect(&mut managed_texture.color_image, pos, &color_image);
-                    let image =
-                        egui_node::color_image_as_bevy_image(&managed_texture.color_image, sampler);
+                    let image = egui_node::color_image_as_bevy_image(
+                        &managed_texture.color_image,
+                        sampler,
+                        color_mode,
+                    );
                     managed_texture.handle = image_assets.add(image);
+                    managed_texture.handle.clone()
                 } else {
                     log::warn!("Partial update of a missing texture (id: {:?})", texture_id);
+                    continue;
                 }
             } else {
                 // Full update.
-                let image = egui_node::color_image_as_bevy_image(&color_image, sampler);
+                let image = egui_node::color_image_as_bevy_image(&color_image, sampler, color_mode);
                 let handle = image_assets.add(image);
                 egui_managed_textures.insert(
                     (window_id, texture_id),
                     EguiManagedTexture {
-                        handle,
+                        handle: handle.clone(),
                         color_image,
                     },
                 );
+                handle
+            };
+            if let Some(budget) = &mut budget {
+                budget.touch(texture_id);
+            }
+            updated_events.send(EguiManagedTextureUpdated {
+                context: window_id,
+                texture_id,
+                pos,
+                size,
+                handle,
+            });
+        }
+
+        if let Some(budget) = &mut budget {
+            // In paint order, so that the least recently painted ones get evicted first.
+            let mut painted = HashSet::new();
+            let painted_in_order: Vec<u64> = egui_render_output
+                .paint_jobs
+                .iter()
+                .filter_map(|paint_job| match &paint_job.primitive {
+                    egui::epaint::Primitive::Mesh(mesh) => match mesh.texture_id {
+                        egui::TextureId::Managed(texture_id) => Some(texture_id),
+                        egui::TextureId::User(_) => None,
+                    },
+                    egui::epaint::Primitive::Callback(_) => None,
+                })
+                .filter(|&texture_id| painted.insert(texture_id))
+                .collect();
+            for texture_id in painted_in_order {
+                if let Some(evicted) = budget.take_evicted(texture_id) {
+                    let size = evicted.color_image.size;
+                    let handle = evicted.upload(
+                        window_id,
+                        texture_id,
+                        color_mode,
+                        &mut egui_managed_textures,
+                        &mut image_assets,
+                    );
+                    updated_events.send(EguiManagedTextureUpdated {
+                        context: window_id,
+                        texture_id,
+                        pos: None,
+                        size,
+                        handle,
+                    });
+                }
+                budget.touch(texture_id);
             }
+            budget.enforce(
+                window_id,
+                &painted,
+                &mut egui_managed_textures,
+                &mut image_assets,
+                &mut evicted_events,
+                &mut freed_events,
+            );
         }
     }
 
@@ -870,6 +3192,8 @@ fn free_egui_textures_system(
     mut egui_managed_textures: ResMut<EguiManagedTextures>,
     mut image_assets: ResMut<Assets<Image>>,
     mut image_events: EventReader<AssetEvent<Image>>,
+    mut freed_events: EventWriter<EguiManagedTextureFreed>,
+    mut texture_stats: ResMut<EguiTextureStats>,
 ) {
     for (window_id, mut egui_render_output) in egui_render_output.iter_mut() {
         let free_textures = std::mem::take(&mut egui_render_output.textures_delta.free);
@@ -878,26 +3202,72 @@ fn free_egui_textures_system(
                 let managed_texture = egui_managed_textures.remove(&(window_id, texture_id));
                 if let Some(managed_texture) = managed_texture {
                     image_assets.remove(managed_texture.handle);
+                    freed_events.send(EguiManagedTextureFreed {
+                        context: window_id,
+                        texture_id,
+                    });
                 }
             }
         }
     }
 
+    // Contexts that got despawned never send free deltas for their textures.
+    let orphaned: Vec<_> = egui_managed_textures
+        .keys()
+        .filter(|(window_id, _)| !egui_render_output.contains(*window_id))
+        .copied()
+        .collect();
+    for (window_id, texture_id) in orphaned {
+        if let Some(managed_texture) = egui_managed_textures.remove(&(window_id, texture_id)) {
+            image_assets.remove(managed_texture.handle);
+            freed_events.send(EguiManagedTextureFreed {
+                context: window_id,
+                texture_id,
+            });
+        }
+    }
+
     for image_event in image_events.read() {
-        if let AssetEvent::Removed { id } = image_event {
-            egui_user_textures.remove_image(&Handle::<Image>::Weak(*id));
+        match image_event {
+            AssetEvent::Removed { id } => {
+                egui_user_textures.remove_image_from_all_namespaces(&Handle::<Image>::Weak(*id));
+            }
+            AssetEvent::Modified { id } => {
+                egui_user_textures.mark_changed(&Handle::<Image>::Weak(*id));
+            }
+            _ => {}
         }
     }
+
+    texture_stats.set_if_neq(EguiTextureStats {
+        user_textures: egui_user_textures.len(),
+        managed_textures: egui_managed_textures.len(),
+        managed_texture_bytes: egui_managed_textures
+            .values()
+            .map(|texture| texture.color_image.pixels.len() * 4)
+            .sum(),
+    });
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::test_utils::*;
     use bevy::{
-        app::PluginGroup,
-        render::{settings::WgpuSettings, RenderPlugin},
-        winit::WinitPlugin,
-        DefaultPlugins,
+        app::{Last, Update},
+        ecs::{
+            event::{Events, ManualEventReader},
+            system::RunSystemOnce,
+        },
+        input::{
+            mouse::MouseButtonInput,
+            touch::{TouchInput, TouchPhase},
+            ButtonState,
+        },
+        math::Vec2,
+        prelude::{Changed, Local, MouseButton, Or, Res, ResMut, World},
+        time::{Time, TimeUpdateStrategy, Virtual},
+        window::{CursorMoved, ReceivedCharacter, RequestRedraw, WindowResolution},
     };
 
     #[test]
@@ -907,22 +3277,2593 @@ mod tests {
 
     #[test]
     fn test_headless_mode() {
-        App::new()
-            .add_plugins(
-                DefaultPlugins
-                    .set(RenderPlugin {
-                        render_creation: bevy::render::settings::RenderCreation::Automatic(
-                            WgpuSettings {
-                                backends: None,
-                                ..Default::default()
-                            },
-                        ),
-                        ..Default::default()
-                    })
-                    .build()
-                    .disable::<WinitPlugin>(),
-            )
-            .add_plugins(EguiPlugin)
-            .update();
+        headless_app().update();
+    }
+
+    #[test]
+    fn test_pointer_moved_coalescing() {
+        use crate::input::EguiPointerEvent;
+
+        // Ten moves with a press after the fifth one, sent in order.
+        let send_moves_and_press = |app: &mut App, window| {
+            for i in 0..10 {
+                app.world
+                    .send_event(EguiPointerEvent::CursorMoved(CursorMoved {
+                        window,
+                        position: Vec2::new(i as f32 * 10.0, 5.0),
+                        delta: None,
+                    }));
+                if i == 4 {
+                    app.world
+                        .send_event(EguiPointerEvent::MouseButton(MouseButtonInput {
+                            button: MouseButton::Left,
+                            state: ButtonState::Pressed,
+                            window,
+                        }));
+                }
+            }
+            app.update();
+        };
+        let press = |pos| egui::Event::PointerButton {
+            pos,
+            button: egui::PointerButton::Primary,
+            pressed: true,
+            modifiers: egui::Modifiers::default(),
+        };
+        let (p5, p10) = (egui::pos2(40.0, 5.0), egui::pos2(90.0, 5.0));
+
+        let (mut app, window) = input_test_app();
+        send_moves_and_press(&mut app, window);
+        assert_eq!(
+            app.world.resource::<CapturedEvents>().0,
+            vec![
+                egui::Event::PointerMoved(p5),
+                press(p5),
+                egui::Event::PointerMoved(p10)
+            ]
+        );
+
+        // The built-in button events are processed after the cursor ones.
+        send_moves_and_click(&mut app, window);
+        assert_eq!(
+            app.world.resource::<CapturedEvents>().0,
+            vec![egui::Event::PointerMoved(p10), press(p10)]
+        );
+
+        app.world
+            .resource_mut::<EguiSettings>()
+            .coalesce_pointer_moved = false;
+        send_moves_and_press(&mut app, window);
+
+        let events = &app.world.resource::<CapturedEvents>().0;
+        assert_eq!(events.len(), 11, "{events:?}");
+        assert_eq!(events[3], egui::Event::PointerMoved(egui::pos2(30.0, 5.0)));
+        assert_eq!(events[5], press(p5));
+    }
+
+    #[test]
+    fn test_mouse_motion_while_grabbed() {
+        use bevy::{input::mouse::MouseMotion, window::CursorGrabMode};
+
+        let (mut app, window) = input_test_app();
+        app.world
+            .get_mut::<Window>(window)
+            .unwrap()
+            .resolution
+            .set_scale_factor_override(Some(2.0));
+        app.world
+            .get_mut::<Window>(window)
+            .unwrap()
+            .cursor
+            .grab_mode = CursorGrabMode::Locked;
+        app.update();
+
+        app.world.send_event(MouseMotion {
+            delta: Vec2::new(4.0, 2.0),
+        });
+        app.world.send_event(MouseMotion {
+            delta: Vec2::new(6.0, -8.0),
+        });
+        send_moves_and_click(&mut app, window);
+
+        let events = &app.world.resource::<CapturedEvents>().0;
+        assert_eq!(events.len(), 2, "{events:?}");
+        assert_eq!(events[0], egui::Event::MouseMoved(egui::vec2(5.0, -3.0)));
+        assert!(matches!(
+            events[1],
+            egui::Event::PointerButton { pos, .. } if pos == egui::Pos2::ZERO
+        ));
+        assert_eq!(
+            app.world.get::<EguiContext>(window).unwrap().mouse_position,
+            egui::Pos2::ZERO
+        );
+
+        // Without the grab, cursor moves are fed as usual and the raw motion is ignored.
+        app.world
+            .get_mut::<Window>(window)
+            .unwrap()
+            .cursor
+            .grab_mode = CursorGrabMode::None;
+        app.world.send_event(MouseMotion {
+            delta: Vec2::new(4.0, 2.0),
+        });
+        send_moves_and_click(&mut app, window);
+        let events = &app.world.resource::<CapturedEvents>().0;
+        assert_eq!(events[0], egui::Event::PointerMoved(egui::pos2(90.0, 5.0)));
+        assert!(!events
+            .iter()
+            .any(|event| matches!(event, egui::Event::MouseMoved(_))));
+    }
+
+    #[test]
+    fn test_computed_scale() {
+        let (mut app, window) = input_test_app();
+        app.world
+            .get_mut::<Window>(window)
+            .unwrap()
+            .resolution
+            .set_scale_factor_override(Some(2.0));
+        app.world.resource_mut::<EguiSettings>().scale_factor = 1.5;
+        app.update();
+
+        let computed_scale = *app.world.get::<EguiComputedScale>(window).unwrap();
+        let physical_size = {
+            let window = app.world.get::<Window>(window).unwrap();
+            Vec2::new(
+                window.physical_width() as f32,
+                window.physical_height() as f32,
+            )
+        };
+        assert_eq!(computed_scale.pixels_per_point, 3.0);
+        assert_eq!(computed_scale.logical_size, physical_size / 3.0);
+        assert_eq!(
+            helpers::physical_to_egui_pos(Vec2::new(300.0, 600.0), &computed_scale),
+            egui::pos2(100.0, 200.0)
+        );
+
+        let ctx = app.world.get::<EguiContext>(window).unwrap().ctx.clone();
+        assert_eq!(ctx.pixels_per_point(), computed_scale.pixels_per_point);
+    }
+
+    #[test]
+    fn test_scale_factor_change() {
+        use bevy::window::WindowScaleFactorChanged;
+
+        fn physical_size(app: &App, window: Entity) -> Vec2 {
+            let window = app.world.get::<Window>(window).unwrap();
+            Vec2::new(
+                window.physical_width() as f32,
+                window.physical_height() as f32,
+            )
+        }
+
+        let (mut app, window) = input_test_app();
+        app.world
+            .get_mut::<Window>(window)
+            .unwrap()
+            .set_physical_cursor_position(Some(bevy::math::DVec2::new(200.0, 100.0)));
+        app.world.send_event(CursorMoved {
+            window,
+            position: Vec2::new(200.0, 100.0),
+            delta: None,
+        });
+        app.update();
+        let mouse_position =
+            |app: &App| app.world.get::<EguiContext>(window).unwrap().mouse_position;
+        assert_eq!(mouse_position(&app), egui::pos2(200.0, 100.0));
+
+        // The cursor doesn't move, but the window goes to a monitor with a different scale factor.
+        app.world
+            .get_mut::<Window>(window)
+            .unwrap()
+            .resolution
+            .set_scale_factor(2.0);
+        app.world.send_event(WindowScaleFactorChanged {
+            window,
+            scale_factor: 2.0,
+        });
+        app.update();
+        assert_eq!(mouse_position(&app), egui::pos2(100.0, 50.0));
+        assert_eq!(
+            app.world.resource::<CapturedEvents>().0,
+            [egui::Event::PointerMoved(egui::pos2(100.0, 50.0))]
+        );
+        let ctx = app.world.get::<EguiContext>(window).unwrap().ctx.clone();
+        assert_eq!(
+            ctx.screen_rect().size(),
+            egui::vec2(physical_size(&app, window).x, physical_size(&app, window).y) / 2.0
+        );
+
+        // Fractional scale factors get rounded.
+        app.world
+            .entity_mut(window)
+            .insert(EguiDpiMode::RoundToNearestInteger);
+        app.world
+            .get_mut::<Window>(window)
+            .unwrap()
+            .resolution
+            .set_scale_factor(1.25);
+        app.update();
+        let computed_scale = *app.world.get::<EguiComputedScale>(window).unwrap();
+        assert_eq!(computed_scale.pixels_per_point, 1.0);
+        assert_eq!(computed_scale.logical_size, physical_size(&app, window));
+        assert_eq!(mouse_position(&app), egui::pos2(200.0, 100.0));
+
+        // Cursor events are converted with the forced scale factor too.
+        app.world
+            .entity_mut(window)
+            .insert(EguiDpiMode::ForceScale(4.0));
+        app.world.send_event(CursorMoved {
+            window,
+            position: Vec2::new(80.0, 40.0),
+            delta: None,
+        });
+        app.update();
+        assert_eq!(
+            app.world
+                .get::<EguiComputedScale>(window)
+                .unwrap()
+                .pixels_per_point,
+            4.0
+        );
+        assert_eq!(mouse_position(&app), egui::pos2(25.0, 12.5));
+    }
+
+    #[test]
+    fn test_context_initialized_events() {
+        let mut app = headless_app();
+        let mut reader = ManualEventReader::<EguiContextInitialized>::default();
+        app.update();
+
+        let primary_window = app
+            .world
+            .query_filtered::<Entity, With<PrimaryWindow>>()
+            .single(&app.world);
+        let events = app.world.resource::<Events<EguiContextInitialized>>();
+        assert_eq!(
+            reader.read(events).copied().collect::<Vec<_>>(),
+            [EguiContextInitialized {
+                entity: primary_window,
+                kind: EguiContextKind::PrimaryWindow,
+            }]
+        );
+        assert!(app.world.get::<EguiContextReady>(primary_window).is_some());
+
+        // A window without a valid size doesn't get initialized until it's resized.
+        let window = app
+            .world
+            .spawn(Window {
+                resolution: WindowResolution::new(0.0, 0.0),
+                ..Default::default()
+            })
+            .id();
+        app.update();
+        app.update();
+        let events = app.world.resource::<Events<EguiContextInitialized>>();
+        assert_eq!(reader.read(events).count(), 0);
+        assert!(app.world.get::<EguiContextReady>(window).is_none());
+
+        app.world
+            .get_mut::<Window>(window)
+            .unwrap()
+            .resolution
+            .set(200.0, 100.0);
+        app.update();
+        app.update();
+        let events = app.world.resource::<Events<EguiContextInitialized>>();
+        assert_eq!(
+            reader.read(events).copied().collect::<Vec<_>>(),
+            [EguiContextInitialized {
+                entity: window,
+                kind: EguiContextKind::Window,
+            }]
+        );
+        assert!(app.world.get::<EguiContextReady>(window).is_some());
+    }
+
+    #[test]
+    fn test_events_for_new_window_are_not_dropped() {
+        fn spawn_window_with_input(world: &mut World, mut spawned: Local<bool>) {
+            if std::mem::replace(&mut *spawned, true) {
+                return;
+            }
+            // The context for this window gets created only during the next frame.
+            let window = world.spawn(Window::default()).id();
+            world.send_event(CursorMoved {
+                window,
+                position: Vec2::new(10.0, 20.0),
+                delta: None,
+            });
+            world.send_event(MouseButtonInput {
+                button: MouseButton::Left,
+                state: ButtonState::Pressed,
+                window,
+            });
+        }
+
+        let (mut app, _) = input_test_app();
+        app.add_systems(
+            PreUpdate,
+            spawn_window_with_input
+                .after(EguiSet::InitContexts)
+                .before(EguiSet::ProcessInput),
+        );
+        app.update();
+        assert!(app.world.resource::<CapturedEvents>().0.is_empty());
+
+        app.update();
+        let events = &app.world.resource::<CapturedEvents>().0;
+        assert_eq!(events.len(), 2, "{events:?}");
+        assert_eq!(events[0], egui::Event::PointerMoved(egui::pos2(10.0, 20.0)));
+        assert!(matches!(
+            events[1],
+            egui::Event::PointerButton { pressed: true, .. }
+        ));
+    }
+
+    #[test]
+    fn test_primary_context_marker() {
+        fn primary_context(app: &mut App) -> egui::Context {
+            app.world
+                .run_system_once(|mut contexts: EguiContexts| contexts.ctx_mut().clone())
+        }
+        fn window_context(app: &App, window: Entity) -> egui::Context {
+            app.world.get::<EguiContext>(window).unwrap().ctx.clone()
+        }
+
+        let (mut app, primary_window) = input_test_app();
+        let first = app.world.spawn(Window::default()).id();
+        let second = app.world.spawn(Window::default()).id();
+        app.update();
+
+        // Without the marker, the primary window context is used.
+        assert_eq!(
+            primary_context(&mut app),
+            window_context(&app, primary_window)
+        );
+
+        app.world.run_system_once(move |mut commands: Commands| {
+            commands.entity(second).set_primary_egui_context();
+        });
+        assert_eq!(primary_context(&mut app), window_context(&app, second));
+
+        // Moving the marker removes it from the previously marked entity.
+        app.world.run_system_once(move |mut commands: Commands| {
+            commands.entity(first).set_primary_egui_context();
+        });
+        assert!(app.world.get::<EguiPrimaryContext>(second).is_none());
+        assert_eq!(primary_context(&mut app), window_context(&app, first));
+
+        // With several markers, the entity with the lowest index wins.
+        app.world.entity_mut(second).insert(EguiPrimaryContext);
+        app.update();
+        assert_eq!(primary_context(&mut app), window_context(&app, first));
+
+        app.world.entity_mut(first).remove::<EguiPrimaryContext>();
+        app.world.entity_mut(second).remove::<EguiPrimaryContext>();
+        assert_eq!(
+            primary_context(&mut app),
+            window_context(&app, primary_window)
+        );
+    }
+
+    #[test]
+    fn test_iter_contexts() {
+        let (mut app, primary_window) = input_test_app();
+        let window = app.world.spawn(Window::default()).id();
+        app.update();
+
+        let mut contexts = app.world.run_system_once(|mut contexts: EguiContexts| {
+            contexts
+                .iter_mut()
+                .map(|(entity, kind, ctx)| (entity, kind, ctx.clone()))
+                .collect::<Vec<_>>()
+        });
+        contexts.sort_by_key(|(entity, ..)| *entity);
+        assert_eq!(
+            contexts
+                .iter()
+                .map(|(entity, kind, _)| (*entity, *kind))
+                .collect::<Vec<_>>(),
+            [
+                (primary_window, EguiContextKind::PrimaryWindow),
+                (window, EguiContextKind::Window)
+            ]
+        );
+        assert_eq!(
+            contexts[1].2,
+            app.world.get::<EguiContext>(window).unwrap().ctx
+        );
+
+        // Marking a context as the primary one doesn't change its kind.
+        app.world.entity_mut(window).insert(EguiPrimaryContext);
+        let (len, contains_window, window_kind) =
+            app.world
+                .run_system_once(move |mut contexts: EguiContexts| {
+                    let kind = contexts
+                        .iter_mut()
+                        .find_map(|(entity, kind, _)| (entity == window).then_some(kind));
+                    (contexts.len(), contexts.contains(window), kind)
+                });
+        assert_eq!(len, 2);
+        assert!(contains_window);
+        assert_eq!(window_kind, Some(EguiContextKind::Window));
+    }
+
+    #[test]
+    fn test_context_id_keeps_memory() {
+        fn spawn_tool_window(app: &mut App, id: &str) -> Entity {
+            let window = app
+                .world
+                .spawn((Window::default(), EguiContextId(id.to_owned())))
+                .id();
+            app.update();
+            window
+        }
+        fn ctx(app: &App, window: Entity) -> egui::Context {
+            app.world.get::<EguiContext>(window).unwrap().ctx.clone()
+        }
+        let value_id = egui::Id::new("value");
+        let value = |ctx: egui::Context| ctx.data(|data| data.get_temp::<u32>(value_id));
+
+        let (mut app, primary_window) = input_test_app();
+        let window = spawn_tool_window(&mut app, "tools");
+        let other_window = spawn_tool_window(&mut app, "other");
+        ctx(&app, window).data_mut(|data| data.insert_temp(value_id, 42u32));
+        ctx(&app, other_window).data_mut(|data| data.insert_temp(value_id, 7u32));
+
+        app.world.despawn(window);
+        app.update();
+        assert!(app
+            .world
+            .resource::<EguiContextRegistry>()
+            .is_parked("tools"));
+
+        let window = spawn_tool_window(&mut app, "tools");
+        assert_eq!(value(ctx(&app, window)), Some(42));
+        assert!(!app
+            .world
+            .resource::<EguiContextRegistry>()
+            .is_parked("tools"));
+        // Other contexts are unaffected.
+        assert_eq!(value(ctx(&app, other_window)), Some(7));
+        assert_eq!(value(ctx(&app, primary_window)), None);
+        let new_window = spawn_tool_window(&mut app, "new");
+        assert_eq!(value(ctx(&app, new_window)), None);
+
+        // Parked memories are bounded and can be cleared.
+        app.world.resource_mut::<EguiContextRegistry>().max_parked = 1;
+        app.world.despawn(window);
+        app.update();
+        app.world.despawn(other_window);
+        app.update();
+        let registry = app.world.resource::<EguiContextRegistry>();
+        assert!(!registry.is_parked("tools"));
+        assert!(registry.is_parked("other"));
+        app.world
+            .resource_mut::<EguiContextRegistry>()
+            .clear_parked();
+        let window = spawn_tool_window(&mut app, "other");
+        assert_eq!(value(ctx(&app, window)), None);
+    }
+
+    #[test]
+    fn test_managed_texture_update_events() {
+        let (mut app, window) = input_test_app();
+        let mut reader = app
+            .world
+            .resource::<Events<EguiManagedTextureUpdated>>()
+            .get_reader_current();
+
+        let texture_id = 1000;
+        let set_texture = |app: &mut App, pos: Option<[usize; 2]>, size, color| {
+            let image_delta = egui::epaint::ImageDelta {
+                image: egui::ColorImage::new(size, color).into(),
+                options: Default::default(),
+                pos,
+            };
+            app.world
+                .get_mut::<EguiRenderOutput>(window)
+                .unwrap()
+                .textures_delta
+                .set
+                .push((egui::TextureId::Managed(texture_id), image_delta));
+            app.world.run_system_once(update_egui_textures_system);
+        };
+        set_texture(&mut app, None, [8, 8], egui::Color32::TRANSPARENT);
+        set_texture(&mut app, Some([2, 4]), [3, 2], egui::Color32::RED);
+
+        let events = app.world.resource::<Events<EguiManagedTextureUpdated>>();
+        let events: Vec<_> = reader.read(events).cloned().collect();
+        assert_eq!(events.len(), 2, "{events:?}");
+        assert_eq!(
+            (events[0].context, events[0].texture_id, events[0].pos),
+            (window, texture_id, None)
+        );
+        assert_eq!(events[0].size, [8, 8]);
+        assert_eq!(events[1].pos, Some([2, 4]));
+        assert_eq!(events[1].size, [3, 2]);
+
+        let managed_textures = app.world.resource::<EguiManagedTextures>();
+        let managed_texture = &managed_textures[&(window, texture_id)];
+        assert_eq!(managed_texture.handle, events[1].handle);
+        assert_eq!(managed_texture.color_image.size, [8, 8]);
+        assert_eq!(managed_texture.color_image[(3, 5)], egui::Color32::RED);
+        assert_eq!(
+            managed_texture.color_image[(1, 5)],
+            egui::Color32::TRANSPARENT
+        );
+    }
+
+    #[test]
+    fn test_user_textures_stay_bounded() {
+        let mut user_textures = EguiUserTextures::default();
+        let mut live = Vec::new();
+        let mut live_ids = HashMap::new();
+        for i in 0..100_000u128 {
+            let image = Handle::<Image>::weak_from_u128(i);
+            let id = user_textures.add_image(image.clone());
+            assert!(live_ids.insert(id, i).is_none(), "{id:?} collides");
+            live.push((image, i));
+            // Remove in bursts, keeping a few textures alive.
+            if live.len() == 64 {
+                for (image, _) in live.drain(8..) {
+                    let id = user_textures.remove_image(&image).unwrap();
+                    live_ids.remove(&id);
+                }
+                user_textures.shrink_to_fit();
+                assert_eq!(user_textures.len(), 8);
+                assert!(user_textures.capacity() <= 64);
+            }
+        }
+        for (image, i) in &live {
+            assert_eq!(live_ids[&user_textures.image_id(image).unwrap()], *i);
+        }
+    }
+
+    #[test]
+    fn test_despawned_context_frees_managed_textures() {
+        let (mut app, window) = input_test_app();
+        app.update();
+        let stats = *app.world.resource::<EguiTextureStats>();
+        assert!(stats.managed_textures > 0, "{stats:?}");
+        assert!(stats.managed_texture_bytes > 0, "{stats:?}");
+
+        let mut reader = app
+            .world
+            .resource::<Events<EguiManagedTextureFreed>>()
+            .get_reader_current();
+        app.world.despawn(window);
+        app.update();
+        let events = app.world.resource::<Events<EguiManagedTextureFreed>>();
+        assert_eq!(reader.read(events).count(), stats.managed_textures);
+        assert!(app.world.resource::<EguiManagedTextures>().is_empty());
+        assert_eq!(
+            *app.world.resource::<EguiTextureStats>(),
+            EguiTextureStats::default()
+        );
+    }
+
+    #[test]
+    fn test_render_graph_nodes_follow_windows() {
+        use bevy::{
+            ecs::schedule::Schedule,
+            render::{
+                graph::CameraDriverLabel,
+                render_graph::{EmptyNode, RenderGraph, RenderLabel},
+                MainWorld,
+            },
+        };
+        use render_systems::{EguiPass, EguiRenderGraphNodes};
+
+        let mut render_world = World::new();
+        render_world.init_resource::<MainWorld>();
+        render_world.init_resource::<EguiRenderGraphNodes>();
+        let mut render_graph = RenderGraph::default();
+        render_graph.add_node(CameraDriverLabel, EmptyNode);
+        render_world.insert_resource(render_graph);
+        let mut extract = Schedule::default();
+        extract.add_systems(
+            (
+                render_systems::teardown_despawned_windows_render_system,
+                render_systems::setup_new_windows_render_system,
+            )
+                .chain(),
+        );
+
+        let mut windows = Vec::new();
+        for i in 0..1000 {
+            let mut main_world = render_world.resource_mut::<MainWorld>();
+            // Despawning windows frees their indices, which the new windows reuse.
+            if i % 3 == 0 && windows.len() > 2 {
+                for window in windows.drain(..2) {
+                    main_world.despawn(window);
+                }
+            }
+            windows.push(main_world.spawn(Window::default()).id());
+            main_world.increment_change_tick();
+            extract.run(&mut render_world);
+        }
+
+        let render_graph = render_world.resource::<RenderGraph>();
+        assert_eq!(render_graph.iter_nodes().count(), windows.len() + 1);
+        for &window in &windows {
+            let egui_pass = render_graph.get_node_state(EguiPass::new(window)).unwrap();
+            assert_eq!(egui_pass.label, EguiPass::new(window).intern());
+        }
+        assert_eq!(
+            render_world.resource::<EguiRenderGraphNodes>().len(),
+            windows.len()
+        );
+    }
+
+    #[test]
+    fn test_render_limits() {
+        let (mut app, window) = input_test_app();
+        app.world.insert_resource(EguiRenderLimits {
+            max_texture_side: 1024,
+        });
+        app.update();
+
+        let ctx = app.world.get::<EguiContext>(window).unwrap().ctx.clone();
+        assert_eq!(ctx.input(|input| input.max_texture_side), 1024);
+    }
+
+    #[cfg(all(
+        feature = "manage_clipboard",
+        not(any(target_arch = "wasm32", target_os = "android"))
+    ))]
+    #[test]
+    fn test_clipboard_fallback() {
+        #[derive(Default)]
+        struct MockClipboard(String);
+
+        impl ClipboardProvider for MockClipboard {
+            fn get_text(&mut self) -> Result<String, String> {
+                Ok(self.0.clone())
+            }
+
+            fn set_text(&mut self, contents: &str) -> Result<(), String> {
+                self.0 = contents.to_owned();
+                Ok(())
+            }
+        }
+
+        let mut clipboard = EguiClipboard {
+            new_provider: || Err("unavailable".to_owned()),
+            ..Default::default()
+        };
+        assert!(!clipboard.is_system_clipboard_available());
+        assert_eq!(clipboard.get_contents(), None);
+        clipboard.set_contents("copied within the app");
+        assert_eq!(
+            clipboard.get_contents().as_deref(),
+            Some("copied within the app")
+        );
+
+        let mut clipboard = EguiClipboard {
+            new_provider: || Ok(Box::<MockClipboard>::default()),
+            ..Default::default()
+        };
+        assert!(clipboard.is_system_clipboard_available());
+        clipboard.set_contents("system contents");
+        assert_eq!(clipboard.fallback_contents, None);
+        assert_eq!(clipboard.get_contents().as_deref(), Some("system contents"));
+    }
+
+    #[test]
+    fn test_render_target_resized_events() {
+        let (mut app, _) = input_test_app();
+        let mut reader = app
+            .world
+            .resource::<Events<EguiRenderTargetResized>>()
+            .get_reader_current();
+        let window = app
+            .world
+            .spawn(Window {
+                resolution: WindowResolution::new(0.0, 0.0),
+                ..Default::default()
+            })
+            .id();
+        app.update();
+        app.update();
+        let events = app.world.resource::<Events<EguiRenderTargetResized>>();
+        assert_eq!(reader.read(events).count(), 0);
+
+        app.world
+            .get_mut::<Window>(window)
+            .unwrap()
+            .resolution
+            .set(200.0, 100.0);
+        app.update();
+        app.update();
+        let events = app.world.resource::<Events<EguiRenderTargetResized>>();
+        let events: Vec<_> = reader.read(events).copied().collect();
+        assert_eq!(events.len(), 1, "{events:?}");
+        assert_eq!(events[0].entity, window);
+        assert_eq!(events[0].old, WindowSize::default());
+        assert_eq!(
+            (events[0].new.width(), events[0].new.height()),
+            (200.0, 100.0)
+        );
+    }
+
+    #[test]
+    fn test_plugin_settings() {
+        let mut app = headless_app_with_plugin(EguiPlugin::default().with_settings(EguiSettings {
+            scale_factor: 2.0,
+            ..Default::default()
+        }));
+        app.update();
+
+        let window = app.world.spawn(Window::default()).id();
+        app.update();
+        let computed_scale = app.world.get::<EguiComputedScale>(window).unwrap();
+        assert_eq!(computed_scale.pixels_per_point, 2.0);
+    }
+
+    #[test]
+    fn test_plugin_default_context_settings() {
+        let mut app = headless_app_with_plugin(
+            EguiPlugin::default().with_default_context_settings(EguiContextSettings {
+                pass_rate: Some(EguiPassRate::EveryNFrames(2)),
+                color_mode: Some(EguiColorMode::Accurate),
+                ..Default::default()
+            }),
+        );
+        let primary_window = app
+            .world
+            .query_filtered::<Entity, With<PrimaryWindow>>()
+            .single(&app.world);
+        // The settings also apply to the windows created long after the startup.
+        for _ in 0..100 {
+            app.update();
+        }
+        let window = app.world.spawn(Window::default()).id();
+        let window_with_color_mode = app
+            .world
+            .spawn((Window::default(), EguiColorMode::Standard))
+            .id();
+        app.update();
+        for window in [primary_window, window] {
+            assert_eq!(
+                app.world.get::<EguiPassRate>(window),
+                Some(&EguiPassRate::EveryNFrames(2))
+            );
+            assert_eq!(
+                app.world.get::<EguiColorMode>(window),
+                Some(&EguiColorMode::Accurate)
+            );
+            assert!(app.world.get::<EguiWindowAlphaMode>(window).is_none());
+        }
+        // Components inserted by the app take precedence.
+        assert_eq!(
+            app.world.get::<EguiColorMode>(window_with_color_mode),
+            Some(&EguiColorMode::Standard)
+        );
+
+        // Changing the resource affects the windows created afterwards.
+        app.world.resource_mut::<EguiContextSettings>().color_mode = None;
+        let new_window = app.world.spawn(Window::default()).id();
+        app.update();
+        assert!(app.world.get::<EguiColorMode>(new_window).is_none());
+        assert_eq!(
+            app.world.get::<EguiColorMode>(window),
+            Some(&EguiColorMode::Accurate)
+        );
+    }
+
+    #[test]
+    fn test_touch_long_press_secondary_click() {
+        let (mut app, window) = input_test_app();
+        app.world
+            .entity_mut(window)
+            .insert(LongPressConfig::default());
+        app.insert_resource(TimeUpdateStrategy::ManualDuration(
+            std::time::Duration::from_millis(100),
+        ));
+        let touch = |phase, x| TouchInput {
+            phase,
+            position: Vec2::new(x, 10.0),
+            window,
+            force: None,
+            id: 0,
+        };
+        let mut buttons = Vec::new();
+        let mut update =
+            |app: &mut App| {
+                app.update();
+                buttons.extend(app.world.resource::<CapturedEvents>().0.iter().filter_map(
+                    |event| match event {
+                        egui::Event::PointerButton {
+                            button, pressed, ..
+                        } => Some((*button, *pressed)),
+                        _ => None,
+                    },
+                ));
+            };
+
+        // Hold the touch still for long enough.
+        app.world.send_event(touch(TouchPhase::Started, 10.0));
+        for _ in 0..10 {
+            update(&mut app);
+        }
+        app.world.send_event(touch(TouchPhase::Ended, 10.0));
+        update(&mut app);
+        // A tap.
+        app.world.send_event(touch(TouchPhase::Started, 10.0));
+        update(&mut app);
+        app.world.send_event(touch(TouchPhase::Ended, 10.0));
+        update(&mut app);
+        // Drag within the long press duration.
+        app.world.send_event(touch(TouchPhase::Started, 10.0));
+        update(&mut app);
+        app.world.send_event(touch(TouchPhase::Moved, 30.0));
+        update(&mut app);
+        for _ in 0..10 {
+            update(&mut app);
+        }
+        app.world.send_event(touch(TouchPhase::Ended, 30.0));
+        update(&mut app);
+
+        use egui::PointerButton::{Primary, Secondary};
+        assert_eq!(
+            buttons,
+            [
+                (Secondary, true),
+                (Secondary, false),
+                (Primary, true),
+                (Primary, false),
+                (Primary, true),
+                (Primary, false),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_touch_pointer_promotion() {
+        let (mut app, window) = input_test_app();
+        let touch = |id, phase, x| TouchInput {
+            phase,
+            position: Vec2::new(x, 10.0),
+            window,
+            force: None,
+            id,
+        };
+        let pointer_events = |app: &App| {
+            app.world
+                .resource::<CapturedEvents>()
+                .0
+                .iter()
+                .filter(|event| !matches!(event, egui::Event::Touch { .. }))
+                .cloned()
+                .collect::<Vec<_>>()
+        };
+        let primary_button = |x, pressed| egui::Event::PointerButton {
+            pos: egui::pos2(x, 10.0),
+            button: egui::PointerButton::Primary,
+            pressed,
+            modifiers: egui::Modifiers::NONE,
+        };
+
+        app.world.send_event(touch(0, TouchPhase::Started, 10.0));
+        app.world.send_event(touch(1, TouchPhase::Started, 50.0));
+        app.update();
+        assert_eq!(
+            pointer_events(&app),
+            [
+                egui::Event::PointerMoved(egui::pos2(10.0, 10.0)),
+                primary_button(10.0, true)
+            ]
+        );
+
+        // The first touch lifts mid-gesture, the second one takes over the pointer.
+        app.world.send_event(touch(0, TouchPhase::Ended, 20.0));
+        app.update();
+        assert_eq!(
+            pointer_events(&app),
+            [
+                primary_button(20.0, false),
+                egui::Event::PointerMoved(egui::pos2(50.0, 10.0))
+            ]
+        );
+        app.world.send_event(touch(1, TouchPhase::Moved, 60.0));
+        app.update();
+        assert_eq!(
+            pointer_events(&app),
+            [egui::Event::PointerMoved(egui::pos2(60.0, 10.0))]
+        );
+        // No phantom press or release of the promoted pointer.
+        app.world.send_event(touch(1, TouchPhase::Ended, 60.0));
+        app.update();
+        assert_eq!(pointer_events(&app), [egui::Event::PointerGone]);
+
+        // Touch events are fed for all the touches.
+        let touch_ids = |app: &App| {
+            app.world
+                .resource::<CapturedEvents>()
+                .0
+                .iter()
+                .filter_map(|event| match event {
+                    egui::Event::Touch { id, .. } => Some(*id),
+                    _ => None,
+                })
+                .collect::<Vec<_>>()
+        };
+        app.world.send_event(touch(2, TouchPhase::Started, 10.0));
+        app.world.send_event(touch(3, TouchPhase::Started, 50.0));
+        app.update();
+        assert_eq!(
+            touch_ids(&app),
+            [egui::TouchId::from(2u64), egui::TouchId::from(3u64)]
+        );
+
+        // Losing focus stops the emulation, the next touch starts it anew.
+        app.world.send_event(bevy::window::WindowFocused {
+            window,
+            focused: false,
+        });
+        app.update();
+        assert_eq!(pointer_events(&app), [egui::Event::PointerGone]);
+        app.world.send_event(touch(4, TouchPhase::Started, 30.0));
+        app.update();
+        assert_eq!(
+            pointer_events(&app),
+            [
+                egui::Event::PointerMoved(egui::pos2(30.0, 10.0)),
+                primary_button(30.0, true)
+            ]
+        );
+    }
+
+    #[test]
+    fn test_render_targets_change_detection() {
+        /// Contexts that would be extracted to the render world during the last frame.
+        #[derive(Resource, Default)]
+        struct ChangedRenderTargets(Vec<Entity>);
+
+        #[allow(clippy::type_complexity)]
+        fn changed_render_targets_system(
+            mut changed_render_targets: ResMut<ChangedRenderTargets>,
+            changed: Query<Entity, Or<(Changed<WindowSize>, Changed<EguiComputedScale>)>>,
+        ) {
+            changed_render_targets.0 = changed.iter().collect();
+        }
+
+        let (mut app, window) = input_test_app();
+        app.init_resource::<ChangedRenderTargets>()
+            .add_systems(Last, changed_render_targets_system);
+        let changed = |app: &App| app.world.resource::<ChangedRenderTargets>().0.clone();
+
+        // New contexts always get extracted.
+        let new_window = app.world.spawn(Window::default()).id();
+        app.update();
+        let mut changed_windows = changed(&app);
+        changed_windows.sort();
+        assert_eq!(changed_windows, [window, new_window]);
+
+        for _ in 0..3 {
+            app.update();
+            assert_eq!(changed(&app), []);
+        }
+
+        app.world
+            .get_mut::<Window>(window)
+            .unwrap()
+            .resolution
+            .set(200.0, 100.0);
+        app.update();
+        assert_eq!(changed(&app), [window]);
+        app.update();
+        assert_eq!(changed(&app), []);
+    }
+
+    #[cfg(feature = "render")]
+    #[test]
+    fn test_render_targets_extraction() {
+        use bevy::{
+            ecs::system::{IntoSystem, System},
+            render::MainWorld,
+        };
+        use render_systems::{extract_egui_render_targets_system, ExtractedEguiRenderTargets};
+
+        let (mut app, window) = input_test_app();
+        let mut render_world = World::new();
+        render_world.init_resource::<ExtractedEguiRenderTargets>();
+        render_world.init_resource::<MainWorld>();
+        let mut extract = IntoSystem::into_system(extract_egui_render_targets_system);
+        // Swaps the main world in, as the render app does during `ExtractSchedule`.
+        let swap_main_world = |app: &mut App, render_world: &mut World| {
+            std::mem::swap(
+                &mut app.world,
+                &mut render_world.resource_mut::<MainWorld>(),
+            );
+        };
+        swap_main_world(&mut app, &mut render_world);
+        extract.initialize(&mut render_world);
+        swap_main_world(&mut app, &mut render_world);
+        let mut extract_frame = |app: &mut App, render_world: &mut World| {
+            app.update();
+            swap_main_world(app, render_world);
+            extract.run((), render_world);
+            swap_main_world(app, render_world);
+            render_world
+                .resource::<ExtractedEguiRenderTargets>()
+                .0
+                .clone()
+        };
+        let main_world_target = |app: &App| {
+            (
+                *app.world.get::<WindowSize>(window).unwrap(),
+                *app.world.get::<EguiComputedScale>(window).unwrap(),
+            )
+        };
+
+        let render_targets = extract_frame(&mut app, &mut render_world);
+        assert_eq!(render_targets.len(), 1);
+        assert_eq!(render_targets[&window], main_world_target(&app));
+
+        // Unchanged contexts aren't extracted again.
+        let stale = (WindowSize::default(), EguiComputedScale::default());
+        render_world
+            .resource_mut::<ExtractedEguiRenderTargets>()
+            .insert(window, stale);
+        let render_targets = extract_frame(&mut app, &mut render_world);
+        assert_eq!(render_targets[&window], stale);
+
+        app.world
+            .get_mut::<Window>(window)
+            .unwrap()
+            .resolution
+            .set(200.0, 100.0);
+        let render_targets = extract_frame(&mut app, &mut render_world);
+        assert_eq!(render_targets[&window], main_world_target(&app));
+        assert_eq!(render_targets[&window].0.physical_width, 200.0);
+
+        app.world.despawn(window);
+        let render_targets = extract_frame(&mut app, &mut render_world);
+        assert!(render_targets.is_empty());
+    }
+
+    #[test]
+    fn test_text_cursor_info() {
+        fn text_edit_system(mut contexts: EguiContexts, mut text: Local<String>) {
+            egui::CentralPanel::default().show(contexts.ctx_mut(), |ui| {
+                let response = ui.text_edit_singleline(&mut *text);
+                if !response.has_focus() {
+                    response.request_focus();
+                }
+            });
+        }
+
+        let (mut app, window) = input_test_app();
+        app.add_systems(Update, text_edit_system);
+        app.update();
+        app.update();
+        let text_cursor_info = *app.world.get::<EguiTextCursorInfo>(window).unwrap();
+        assert!(text_cursor_info.active);
+        let caret_rect = text_cursor_info.caret_rect.unwrap();
+        assert!(text_cursor_info
+            .text_edit_rect
+            .unwrap()
+            .contains(caret_rect.center()));
+
+        for char in ["a", "b", "c"] {
+            app.world.send_event(ReceivedCharacter {
+                window,
+                char: char.into(),
+            });
+        }
+        app.update();
+        let moved_caret_rect = app
+            .world
+            .get::<EguiTextCursorInfo>(window)
+            .unwrap()
+            .caret_rect
+            .unwrap();
+        assert!(moved_caret_rect.min.x > caret_rect.min.x);
+        assert_eq!(moved_caret_rect.min.y, caret_rect.min.y);
+
+        let computed_scale = *app.world.get::<EguiComputedScale>(window).unwrap();
+        let physical_rect = helpers::egui_to_physical_rect(moved_caret_rect, &computed_scale);
+        assert_eq!(
+            physical_rect.min.x,
+            moved_caret_rect.min.x * computed_scale.pixels_per_point
+        );
+    }
+
+    #[test]
+    fn test_request_redraws() {
+        #[derive(Resource, Default)]
+        struct RepaintAfter(Option<std::time::Duration>);
+
+        fn request_repaint_system(
+            mut repaint_after: ResMut<RepaintAfter>,
+            mut contexts: EguiContexts,
+        ) {
+            if let Some(delay) = repaint_after.0.take() {
+                contexts.ctx_mut().request_repaint_after(delay);
+            }
+        }
+
+        let mut app = headless_app();
+        app.insert_resource(TimeUpdateStrategy::ManualDuration(
+            std::time::Duration::from_millis(100),
+        ))
+        .init_resource::<RepaintAfter>()
+        .add_systems(Update, request_repaint_system);
+        let mut reader = app
+            .world
+            .resource::<Events<RequestRedraw>>()
+            .get_reader_current();
+        let mut redraw_requested = |app: &mut App| {
+            app.update();
+            reader
+                .read(app.world.resource::<Events<RequestRedraw>>())
+                .count()
+                > 0
+        };
+        // Egui repaints immediately while it initializes.
+        for _ in 0..5 {
+            redraw_requested(&mut app);
+        }
+        assert!(!redraw_requested(&mut app));
+
+        // Egui asks for an extra frame after an immediate repaint.
+        app.world.resource_mut::<RepaintAfter>().0 = Some(std::time::Duration::ZERO);
+        let redraws: Vec<bool> = (0..3).map(|_| redraw_requested(&mut app)).collect();
+        assert_eq!(redraws, [true, true, false]);
+
+        // With 100ms frames, a redraw is requested in the frame that passes the 250ms deadline.
+        app.world.resource_mut::<RepaintAfter>().0 = Some(std::time::Duration::from_millis(250));
+        let redraws: Vec<bool> = (0..5).map(|_| redraw_requested(&mut app)).collect();
+        assert_eq!(redraws, [false, false, false, true, false]);
+
+        app.world.resource_mut::<EguiSettings>().request_redraws = false;
+        app.world.resource_mut::<RepaintAfter>().0 = Some(std::time::Duration::ZERO);
+        assert!(!redraw_requested(&mut app));
+    }
+
+    #[cfg(feature = "render")]
+    #[test]
+    fn test_user_texture_generations() {
+        let mut app = headless_app();
+        app.update();
+        let image = app
+            .world
+            .resource_mut::<Assets<Image>>()
+            .add(Image::default());
+        let unregistered_image = app
+            .world
+            .resource_mut::<Assets<Image>>()
+            .add(Image::default());
+        app.world
+            .resource_mut::<EguiUserTextures>()
+            .add_image(image.clone());
+        app.update();
+        assert_eq!(
+            app.world.resource::<EguiUserTextures>().generation(&image),
+            0
+        );
+
+        // Modifying an image (which also happens on hot-reload) bumps the generation in the same frame.
+        for handle in [&image, &unregistered_image] {
+            app.world
+                .resource_mut::<Assets<Image>>()
+                .get_mut(handle)
+                .unwrap()
+                .data[0] = 0;
+        }
+        app.update();
+        let user_textures = app.world.resource::<EguiUserTextures>();
+        assert_eq!(user_textures.generation(&image), 1);
+        assert_eq!(user_textures.generation(&unregistered_image), 0);
+
+        let mut user_textures = app.world.resource_mut::<EguiUserTextures>();
+        user_textures.mark_changed(&image);
+        assert_eq!(user_textures.generation(&image), 2);
+        user_textures.remove_image(&image);
+        assert_eq!(user_textures.generation(&image), 0);
+    }
+
+    #[test]
+    fn test_wants_input_before_update() {
+        use bevy::input::{
+            keyboard::{Key, KeyCode, KeyboardInput},
+            ButtonInput,
+        };
+
+        #[derive(Resource, Default)]
+        struct Ui {
+            text: String,
+            leaked_hotkeys: usize,
+        }
+
+        fn ui_system(mut ui: ResMut<Ui>, mut contexts: EguiContexts) {
+            egui::CentralPanel::default().show(contexts.ctx_mut(), |egui_ui| {
+                egui_ui.text_edit_singleline(&mut ui.text);
+            });
+        }
+
+        fn hotkeys_system(
+            mut ui: ResMut<Ui>,
+            keys: Res<ButtonInput<KeyCode>>,
+            egui_wants_input: Res<EguiWantsInput>,
+        ) {
+            if keys.just_pressed(KeyCode::KeyA) && !egui_wants_input.wants_keyboard_input {
+                ui.leaked_hotkeys += 1;
+            }
+        }
+
+        let (mut app, window) = input_test_app();
+        app.init_resource::<Ui>()
+            .add_systems(Update, (ui_system, hotkeys_system));
+        app.update();
+        assert!(!app.world.resource::<EguiWantsInput>().wants_keyboard_input);
+
+        // Focus the text field.
+        app.world.send_event(CursorMoved {
+            window,
+            position: Vec2::new(20.0, 15.0),
+            delta: None,
+        });
+        for state in [ButtonState::Pressed, ButtonState::Released] {
+            app.world.send_event(MouseButtonInput {
+                button: MouseButton::Left,
+                state,
+                window,
+            });
+            app.update();
+        }
+
+        // The first key typed into the text field doesn't reach the game.
+        app.world.send_event(KeyboardInput {
+            key_code: KeyCode::KeyA,
+            logical_key: Key::Character("a".into()),
+            state: ButtonState::Pressed,
+            window,
+        });
+        app.world.send_event(ReceivedCharacter {
+            window,
+            char: "a".into(),
+        });
+        app.update();
+        let ui = app.world.resource::<Ui>();
+        assert_eq!(ui.text, "a");
+        assert_eq!(ui.leaked_hotkeys, 0);
+        assert!(app.world.resource::<EguiWantsInput>().wants_keyboard_input);
+    }
+
+    #[test]
+    fn test_without_render_plugin() {
+        let mut app = App::new();
+        app.add_plugins((
+            bevy::MinimalPlugins,
+            bevy::asset::AssetPlugin::default(),
+            bevy::input::InputPlugin,
+            bevy::window::WindowPlugin {
+                primary_window: None,
+                ..Default::default()
+            },
+        ))
+        .add_plugins(EguiPlugin::default())
+        .add_systems(Update, |mut contexts: EguiContexts| {
+            egui::Window::new("Hello").show(contexts.ctx_mut(), |ui| {
+                ui.label("world");
+            });
+        });
+        app.world.spawn((Window::default(), PrimaryWindow));
+        for _ in 0..10 {
+            app.update();
+        }
+        let window = app
+            .world
+            .query_filtered::<Entity, With<PrimaryWindow>>()
+            .single(&app.world);
+        assert!(!app
+            .world
+            .get::<EguiRenderOutput>(window)
+            .unwrap()
+            .paint_jobs
+            .is_empty());
+    }
+
+    #[test]
+    fn test_modifiers_synced_on_focus() {
+        use bevy::input::{keyboard::KeyCode, ButtonInput};
+
+        let (mut app, window) = input_test_app();
+        // Control was pressed while the window was unfocused, so no keyboard events were sent.
+        app.world
+            .resource_mut::<ButtonInput<KeyCode>>()
+            .press(KeyCode::ControlLeft);
+        app.world.send_event(bevy::window::WindowFocused {
+            window,
+            focused: true,
+        });
+        app.update();
+
+        app.world.send_event(MouseButtonInput {
+            button: MouseButton::Left,
+            state: ButtonState::Pressed,
+            window,
+        });
+        app.update();
+        let events = &app.world.resource::<CapturedEvents>().0;
+        let Some(egui::Event::PointerButton { modifiers, .. }) = events.last() else {
+            panic!("Unexpected events: {events:?}");
+        };
+        assert!(modifiers.ctrl);
+        assert_eq!(modifiers.command, !cfg!(target_os = "macos"));
+    }
+
+    #[test]
+    fn test_debug_overlay() {
+        let (mut app, window) = input_test_app();
+        app.world.resource_mut::<EguiSettings>().show_debug_overlay = true;
+        app.update();
+        app.update();
+        let overlay_rect = app
+            .world
+            .get_mut::<EguiContext>(window)
+            .unwrap()
+            .get_mut()
+            .memory(|memory| memory.area_rect(egui::Id::new("bevy_egui_debug_overlay")))
+            .unwrap();
+        assert!(overlay_rect.width() > 0.0);
+
+        // Hovering the overlay doesn't make Egui want the pointer.
+        app.world.send_event(CursorMoved {
+            window,
+            position: Vec2::new(overlay_rect.center().x, overlay_rect.center().y),
+            delta: None,
+        });
+        app.update();
+        let egui_wants_input = *app.world.resource::<EguiWantsInput>();
+        assert!(!egui_wants_input.is_pointer_over_area);
+        assert!(!egui_wants_input.wants_pointer_input);
+    }
+
+    #[test]
+    fn test_extra_paint_jobs() {
+        let (mut app, window) = input_test_app();
+        app.add_systems(Update, |mut contexts: EguiContexts| {
+            egui::Window::new("Hello").show(contexts.ctx_mut(), |ui| ui.label("world"));
+        });
+        app.update();
+
+        let clip_rect = egui::Rect::from_min_max(egui::pos2(10.0, 10.0), egui::pos2(50.0, 30.0));
+        let quad = |texture_id| {
+            let mut mesh = egui::epaint::Mesh::with_texture(texture_id);
+            mesh.add_colored_rect(clip_rect, egui::Color32::RED);
+            egui::ClippedPrimitive {
+                clip_rect,
+                primitive: egui::epaint::Primitive::Mesh(mesh),
+            }
+        };
+        let is_quad = |primitive: &egui::ClippedPrimitive, texture_id| {
+            primitive.clip_rect == clip_rect
+                && matches!(
+                    &primitive.primitive,
+                    egui::epaint::Primitive::Mesh(mesh)
+                        if mesh.texture_id == texture_id && mesh.vertices.len() == 4
+                )
+        };
+        app.world.entity_mut(window).insert(EguiExtraPaintJobs {
+            primitives: vec![quad(egui::TextureId::default())],
+            placement: EguiExtraPaintJobsPlacement::Overlay,
+        });
+        app.update();
+        let paint_jobs = &app
+            .world
+            .get::<EguiRenderOutput>(window)
+            .unwrap()
+            .paint_jobs;
+        assert!(paint_jobs.len() > 1);
+        assert!(is_quad(
+            paint_jobs.last().unwrap(),
+            egui::TextureId::default()
+        ));
+        // The primitives are consumed by the pass.
+        assert!(app
+            .world
+            .get::<EguiExtraPaintJobs>(window)
+            .unwrap()
+            .primitives
+            .is_empty());
+        app.update();
+        let paint_jobs = &app
+            .world
+            .get::<EguiRenderOutput>(window)
+            .unwrap()
+            .paint_jobs;
+        assert!(!paint_jobs
+            .iter()
+            .any(|primitive| is_quad(primitive, egui::TextureId::default())));
+
+        // Underlays are painted first, unknown user textures are still painted.
+        let mut extra_paint_jobs = app.world.get_mut::<EguiExtraPaintJobs>(window).unwrap();
+        extra_paint_jobs.placement = EguiExtraPaintJobsPlacement::Underlay;
+        extra_paint_jobs
+            .primitives
+            .push(quad(egui::TextureId::User(100)));
+        app.update();
+        let paint_jobs = &app
+            .world
+            .get::<EguiRenderOutput>(window)
+            .unwrap()
+            .paint_jobs;
+        assert!(is_quad(&paint_jobs[0], egui::TextureId::User(100)));
+    }
+
+    #[test]
+    fn test_shape_filters() {
+        let (mut app, window) = input_test_app();
+        app.add_systems(Update, |mut contexts: EguiContexts| {
+            egui::Window::new("Hello").show(contexts.ctx_mut(), |ui| ui.label("world"));
+        });
+        let paint_jobs = |app: &mut App| {
+            app.update();
+            app.world
+                .get::<EguiRenderOutput>(window)
+                .unwrap()
+                .paint_jobs
+                .clone()
+        };
+        paint_jobs(&mut app);
+        let unfiltered = paint_jobs(&mut app).len();
+        assert!(unfiltered > 1);
+
+        // Filters run in order: the second one sees the shapes left by the first one.
+        let mut filters = EguiShapeFilters::default();
+        filters
+            .add(|shapes, _| shapes.truncate(1))
+            .add(|shapes, _| {
+                assert_eq!(shapes.len(), 1);
+                shapes.push(egui::epaint::ClippedShape {
+                    clip_rect: egui::Rect::EVERYTHING,
+                    shape: egui::Shape::rect_filled(
+                        egui::Rect::from_min_max(egui::pos2(10.0, 10.0), egui::pos2(20.0, 20.0)),
+                        0.0,
+                        egui::Color32::GOLD,
+                    ),
+                });
+            });
+        app.world.entity_mut(window).insert(filters);
+        let filtered = paint_jobs(&mut app);
+        assert!(filtered.len() < unfiltered, "{} paint jobs", filtered.len());
+        // Anti-aliasing feathers the edges, so the vertices are slightly off the rect.
+        let gold_rect = egui::Rect::from_points(
+            &filtered
+                .iter()
+                .filter_map(|primitive| match &primitive.primitive {
+                    egui::epaint::Primitive::Mesh(mesh) => Some(&mesh.vertices),
+                    egui::epaint::Primitive::Callback(_) => None,
+                })
+                .flatten()
+                .filter(|vertex| vertex.color == egui::Color32::GOLD)
+                .map(|vertex| vertex.pos)
+                .collect::<Vec<_>>(),
+        );
+        assert!(
+            (gold_rect.center() - egui::pos2(15.0, 15.0)).length() < 1.0,
+            "{gold_rect:?}"
+        );
+
+        // Without tessellation, only the extra paint jobs are painted.
+        app.world
+            .entity_mut(window)
+            .insert((EguiSkipTessellation, EguiExtraPaintJobs::default()));
+        assert!(paint_jobs(&mut app).is_empty());
+        app.world
+            .get_mut::<EguiExtraPaintJobs>(window)
+            .unwrap()
+            .primitives
+            .push(egui::ClippedPrimitive {
+                clip_rect: egui::Rect::EVERYTHING,
+                primitive: egui::epaint::Primitive::Mesh(Default::default()),
+            });
+        assert_eq!(paint_jobs(&mut app).len(), 1);
+    }
+
+    #[test]
+    fn test_time_source() {
+        let (mut app, primary_window) = input_test_app();
+        app.insert_resource(TimeUpdateStrategy::ManualDuration(
+            std::time::Duration::from_millis(100),
+        ));
+        let virtual_window = app
+            .world
+            .spawn((Window::default(), EguiTimeSource::Virtual))
+            .id();
+        let custom_window = app
+            .world
+            .spawn((
+                Window::default(),
+                EguiTimeSource::Custom,
+                EguiCustomTime(42.5),
+            ))
+            .id();
+        app.update();
+        app.update();
+        let time = |app: &App, window| {
+            app.world
+                .get::<EguiContext>(window)
+                .unwrap()
+                .ctx
+                .input(|input| input.time)
+        };
+
+        app.world.resource_mut::<Time<Virtual>>().pause();
+        app.update();
+        let (real, virtual_) = (time(&app, primary_window), time(&app, virtual_window));
+        app.update();
+        app.update();
+        assert!(time(&app, primary_window) > real);
+        assert_eq!(time(&app, virtual_window), virtual_);
+        assert_eq!(time(&app, custom_window), 42.5);
+
+        app.world
+            .get_mut::<EguiCustomTime>(custom_window)
+            .unwrap()
+            .0 = 7.25;
+        app.update();
+        assert_eq!(time(&app, custom_window), 7.25);
+    }
+
+    #[test]
+    fn test_mouse_wheel() {
+        use bevy::input::{
+            keyboard::{Key, KeyCode, KeyboardInput},
+            mouse::{MouseScrollUnit, MouseWheel},
+        };
+
+        let (mut app, window) = input_test_app();
+        app.world
+            .get_mut::<Window>(window)
+            .unwrap()
+            .resolution
+            .set_scale_factor(2.0);
+        app.update();
+        let scroll = |app: &mut App, unit, x, y| {
+            app.world.send_event(MouseWheel { unit, x, y, window });
+            app.update();
+            app.world
+                .resource::<CapturedEvents>()
+                .0
+                .iter()
+                .find_map(|event| match event {
+                    egui::Event::Scroll(delta) => Some(*delta),
+                    _ => None,
+                })
+                .unwrap()
+        };
+
+        // A trackpad sends horizontal deltas in physical pixels.
+        assert_eq!(
+            scroll(&mut app, MouseScrollUnit::Pixel, -8.0, 0.0),
+            egui::vec2(-4.0, 0.0)
+        );
+        assert_eq!(
+            scroll(&mut app, MouseScrollUnit::Line, 0.0, -1.0),
+            egui::vec2(0.0, -50.0)
+        );
+
+        app.world.send_event(KeyboardInput {
+            key_code: KeyCode::ShiftLeft,
+            logical_key: Key::Shift,
+            state: ButtonState::Pressed,
+            window,
+        });
+        // The vertical wheel scrolls horizontally while Shift is held.
+        assert_eq!(
+            scroll(&mut app, MouseScrollUnit::Line, 0.0, -1.0),
+            egui::vec2(-50.0, 0.0)
+        );
+        // Horizontal deltas (sent by macOS while Shift is held) stay horizontal.
+        assert_eq!(
+            scroll(&mut app, MouseScrollUnit::Line, -1.0, 0.0),
+            egui::vec2(-50.0, 0.0)
+        );
+
+        app.world
+            .resource_mut::<EguiSettings>()
+            .shift_scrolls_horizontally = false;
+        assert_eq!(
+            scroll(&mut app, MouseScrollUnit::Line, 0.0, -1.0),
+            egui::vec2(0.0, -50.0)
+        );
+    }
+
+    #[test]
+    fn test_window_ime() {
+        let (mut app, window) = input_test_app();
+        app.add_systems(
+            Update,
+            |mut contexts: EguiContexts, mut text: Local<String>| {
+                egui::CentralPanel::default().show(contexts.ctx_mut(), |ui| {
+                    ui.text_edit_singleline(&mut *text);
+                });
+            },
+        );
+        app.update();
+        let click = |app: &mut App, x, y| {
+            app.world.send_event(CursorMoved {
+                window,
+                position: Vec2::new(x, y),
+                delta: None,
+            });
+            for state in [ButtonState::Pressed, ButtonState::Released] {
+                app.world.send_event(MouseButtonInput {
+                    button: MouseButton::Left,
+                    state,
+                    window,
+                });
+                app.update();
+            }
+        };
+        let ime_enabled = |app: &App| app.world.get::<Window>(window).unwrap().ime_enabled;
+        assert!(!ime_enabled(&app));
+
+        click(&mut app, 20.0, 15.0);
+        assert!(ime_enabled(&app));
+        click(&mut app, 200.0, 200.0);
+        assert!(!ime_enabled(&app));
+
+        // Unmanaged windows keep the flag set by the application.
+        app.world
+            .entity_mut(window)
+            .insert(EguiWindowImeMode::Unmanaged);
+        app.world.get_mut::<Window>(window).unwrap().ime_enabled = true;
+        app.update();
+        assert!(ime_enabled(&app));
+    }
+
+    #[test]
+    fn test_cursor_icon_changes() {
+        #[derive(Resource, Default)]
+        struct WindowChanges(usize);
+
+        let (mut app, window) = input_test_app();
+        app.init_resource::<WindowChanges>()
+            .add_systems(Update, |mut contexts: EguiContexts| {
+                egui::CentralPanel::default().show(contexts.ctx_mut(), |ui| {
+                    let _ = ui.link("Link");
+                });
+            })
+            .add_systems(
+                Last,
+                |windows: Query<(), Changed<Window>>, mut changes: ResMut<WindowChanges>| {
+                    changes.0 += windows.iter().count();
+                },
+            );
+        let changes = |app: &mut App| {
+            app.update();
+            std::mem::take(&mut app.world.resource_mut::<WindowChanges>().0)
+        };
+        changes(&mut app);
+        changes(&mut app);
+        for _ in 0..5 {
+            assert_eq!(changes(&mut app), 0);
+        }
+
+        // Hovering the link switches the cursor once.
+        app.world.send_event(CursorMoved {
+            window,
+            position: Vec2::new(20.0, 15.0),
+            delta: None,
+        });
+        let mut icon_changes = 0;
+        for _ in 0..5 {
+            icon_changes += changes(&mut app);
+        }
+        assert_eq!(icon_changes, 1);
+        assert_eq!(
+            app.world.get::<Window>(window).unwrap().cursor.icon,
+            bevy::window::CursorIcon::Pointer
+        );
+
+        // Mapped cursors take precedence over the default mapping, and can be changed at runtime.
+        let mut cursor_map = app.world.resource_mut::<EguiCursorMap>();
+        assert_eq!(
+            cursor_map.insert(
+                egui::CursorIcon::PointingHand,
+                bevy::window::CursorIcon::Crosshair
+            ),
+            None
+        );
+        assert_eq!(
+            cursor_map.get(egui::CursorIcon::PointingHand),
+            Some(bevy::window::CursorIcon::Crosshair)
+        );
+        assert_eq!(cursor_map.get(egui::CursorIcon::Text), None);
+        app.update();
+        assert_eq!(
+            app.world.get::<Window>(window).unwrap().cursor.icon,
+            bevy::window::CursorIcon::Crosshair
+        );
+        app.world
+            .resource_mut::<EguiCursorMap>()
+            .remove(egui::CursorIcon::PointingHand);
+        app.update();
+        assert_eq!(
+            app.world.get::<Window>(window).unwrap().cursor.icon,
+            bevy::window::CursorIcon::Pointer
+        );
+    }
+
+    #[test]
+    fn test_occlusion_map() {
+        #[derive(Resource)]
+        struct WindowPos(egui::Pos2);
+
+        let (mut app, window) = input_test_app();
+        app.insert_resource(WindowPos(egui::pos2(100.0, 100.0)))
+            .add_systems(
+                Update,
+                |mut contexts: EguiContexts, window_pos: Res<WindowPos>| {
+                    egui::Window::new("Occluder")
+                        .current_pos(window_pos.0)
+                        .fixed_size([200.0, 100.0])
+                        .show(contexts.ctx_mut(), |ui| {
+                            ui.label("Covering");
+                        });
+                },
+            );
+        app.world
+            .entity_mut(window)
+            .insert(EguiOcclusionMap::default());
+        app.update();
+        app.update();
+
+        let map = app.world.get::<EguiOcclusionMap>(window).unwrap();
+        let layer = map.covering_layer(egui::pos2(150.0, 150.0)).unwrap();
+        assert_eq!(layer.order, egui::Order::Middle);
+        assert!(map.is_point_covered(egui::pos2(150.0, 150.0)));
+        assert!(!map.is_point_covered(egui::pos2(50.0, 50.0)));
+        assert!(!map.is_point_covered(egui::pos2(450.0, 150.0)));
+
+        app.world.resource_mut::<WindowPos>().0 = egui::pos2(400.0, 100.0);
+        app.update();
+
+        let map = app.world.get::<EguiOcclusionMap>(window).unwrap();
+        assert!(!map.is_point_covered(egui::pos2(150.0, 150.0)));
+        assert_eq!(map.covering_layer(egui::pos2(450.0, 150.0)), Some(layer));
+    }
+
+    #[test]
+    fn test_recover_from_poisoned_pass() {
+        let (mut app, window) = input_test_app();
+        app.update();
+        // A panic caught after the pass has begun leaves it open.
+        app.world.run_schedule(PreUpdate);
+        app.update();
+
+        // Egui only applies zoom changes to the outermost pass.
+        app.world.resource_mut::<EguiSettings>().scale_factor = 2.0;
+        app.update();
+        app.update();
+        let ctx = &app.world.get::<EguiContext>(window).unwrap().ctx;
+        assert_eq!(ctx.zoom_factor(), 2.0);
+    }
+
+    #[test]
+    fn test_poisoned_ui_schedule() {
+        use bevy::ecs::schedule::ScheduleLabel;
+
+        #[derive(ScheduleLabel, Clone, Debug, PartialEq, Eq, Hash)]
+        struct TestUi;
+
+        #[derive(Resource, Default)]
+        struct ShouldPanic(bool);
+
+        let (mut app, window) = input_test_app();
+        app.init_resource::<ShouldPanic>()
+            .init_schedule(TestUi)
+            .add_systems(
+                TestUi,
+                |mut contexts: EguiContexts, should_panic: Res<ShouldPanic>| {
+                    egui::Window::new("Table").show(contexts.ctx_mut(), |ui| {
+                        ui.label("Row");
+                        assert!(!should_panic.0, "index out of bounds");
+                    });
+                },
+            );
+        app.world
+            .entity_mut(window)
+            .insert(EguiUiSchedule(TestUi.intern()));
+        app.update();
+        assert!(app.world.get::<EguiContextPoisoned>(window).is_none());
+
+        app.world.resource_mut::<ShouldPanic>().0 = true;
+        app.update();
+        assert!(app.world.get::<EguiContextPoisoned>(window).is_some());
+        let events: Vec<_> = app
+            .world
+            .resource_mut::<Events<EguiContextPoisonedEvent>>()
+            .drain()
+            .collect();
+        assert_eq!(events.len(), 1);
+        assert_eq!(events[0].context, window);
+        assert!(events[0].message.contains("index out of bounds"));
+
+        // The app keeps running without passes of the poisoned context.
+        app.update();
+        assert!(!app.world.get::<EguiContext>(window).unwrap().is_pass_due());
+
+        app.world.resource_mut::<ShouldPanic>().0 = false;
+        app.world.entity_mut(window).remove::<EguiContextPoisoned>();
+        app.update();
+        app.update();
+        assert!(app.world.get::<EguiContext>(window).unwrap().is_pass_due());
+        assert!(app.world.get::<EguiContextPoisoned>(window).is_none());
+    }
+
+    #[test]
+    fn test_tessellation_settings() {
+        let (mut app, primary_window) = input_test_app();
+        let unfeathered_window = app
+            .world
+            .spawn((
+                Window::default(),
+                EguiTessellationSettings {
+                    feathering: Some(false),
+                    ..Default::default()
+                },
+            ))
+            .id();
+        let low_ppp_window = app
+            .world
+            .spawn((
+                Window::default(),
+                EguiTessellationSettings {
+                    feathering_auto_disable_below_ppp: Some(1.5),
+                    ..Default::default()
+                },
+            ))
+            .id();
+        let high_ppp_window = app
+            .world
+            .spawn((
+                Window::default(),
+                EguiDpiMode::ForceScale(2.0),
+                EguiTessellationSettings {
+                    feathering_auto_disable_below_ppp: Some(1.5),
+                    ..Default::default()
+                },
+            ))
+            .id();
+        app.update();
+        app.update();
+
+        let feathering = |app: &App, window| {
+            app.world
+                .get::<EguiContext>(window)
+                .unwrap()
+                .ctx
+                .tessellation_options(|options| options.feathering)
+        };
+        assert!(feathering(&app, primary_window));
+        assert!(!feathering(&app, unfeathered_window));
+        assert!(!feathering(&app, low_ppp_window));
+        assert!(feathering(&app, high_ppp_window));
+    }
+
+    #[test]
+    fn test_input_suppressed() {
+        use crate::input::EguiInputSuppressed;
+
+        let (mut app, window) = input_test_app();
+        app.add_systems(Update, |mut contexts: EguiContexts| {
+            egui::Window::new("Panel")
+                .current_pos([100.0, 100.0])
+                .show(contexts.ctx_mut(), |ui| {
+                    ui.label("Drop here");
+                });
+        });
+        app.update();
+        let move_cursor = |app: &mut App, position| {
+            app.world.send_event(CursorMoved {
+                window,
+                position,
+                delta: None,
+            });
+        };
+        let is_pointer_over_area =
+            |app: &App| app.world.resource::<EguiWantsInput>().is_pointer_over_area;
+        move_cursor(&mut app, Vec2::new(120.0, 120.0));
+        app.update();
+        app.update();
+        assert!(is_pointer_over_area(&app));
+
+        // Suppressing the pointer mid-hover clears the hover.
+        app.world.entity_mut(window).insert(EguiInputSuppressed {
+            pointer: true,
+            keyboard: false,
+        });
+        app.update();
+        assert_eq!(
+            app.world.resource::<CapturedEvents>().0,
+            [egui::Event::PointerGone]
+        );
+        // Egui keeps the last interaction position for one more frame.
+        app.update();
+        assert!(!is_pointer_over_area(&app));
+
+        move_cursor(&mut app, Vec2::new(130.0, 125.0));
+        app.world.send_event(MouseButtonInput {
+            button: MouseButton::Left,
+            state: ButtonState::Pressed,
+            window,
+        });
+        app.world.send_event(ReceivedCharacter {
+            window,
+            char: "a".into(),
+        });
+        app.update();
+        assert_eq!(
+            app.world.resource::<CapturedEvents>().0,
+            [egui::Event::Text("a".to_owned())]
+        );
+        assert!(!is_pointer_over_area(&app));
+
+        // The pointer comes back where the cursor is.
+        app.world.entity_mut(window).remove::<EguiInputSuppressed>();
+        app.update();
+        assert_eq!(
+            app.world.resource::<CapturedEvents>().0,
+            [egui::Event::PointerMoved(egui::pos2(130.0, 125.0))]
+        );
+        assert!(is_pointer_over_area(&app));
+    }
+
+    #[test]
+    fn test_observed_canvas_size() {
+        let (mut app, window) = input_test_app();
+        app.world
+            .entity_mut(window)
+            .insert(EguiObservedCanvasSize(WindowSize {
+                physical_width: 800.0,
+                physical_height: 600.0,
+                scale_factor: 2.0,
+            }));
+        app.world.send_event(CursorMoved {
+            window,
+            position: Vec2::new(100.0, 60.0),
+            delta: None,
+        });
+        app.update();
+
+        assert_eq!(
+            *app.world.get::<EguiComputedScale>(window).unwrap(),
+            EguiComputedScale {
+                pixels_per_point: 2.0,
+                logical_size: Vec2::new(400.0, 300.0),
+            }
+        );
+        let ctx = &app.world.get::<EguiContext>(window).unwrap().ctx;
+        assert_eq!(
+            ctx.screen_rect(),
+            egui::Rect::from_min_size(egui::Pos2::ZERO, egui::vec2(400.0, 300.0))
+        );
+        // Cursor positions are in the logical pixels of the window (with a scale factor of 1).
+        assert_eq!(
+            ctx.input(|input| input.pointer.latest_pos()),
+            Some(egui::pos2(50.0, 30.0))
+        );
+    }
+
+    #[test]
+    fn test_texture_bind_group_keys() {
+        use bevy::{
+            render::render_resource::{SamplerId, TextureViewId},
+            utils::Uuid,
+        };
+        use render_systems::{EguiTextureBindGroupKey, EguiTextureBindGroupKeys, EguiTextureId};
+
+        let new_key = |image| EguiTextureBindGroupKey {
+            image: AssetId::Uuid {
+                uuid: Uuid::from_u128(image),
+            },
+            texture_view: TextureViewId::new(),
+            sampler: SamplerId::new(),
+        };
+        let mut textures: Vec<_> = (0..200)
+            .map(|id| (EguiTextureId::User(id), new_key(id as u128)))
+            .collect();
+        let mut keys = EguiTextureBindGroupKeys::default();
+        assert_eq!(keys.update(textures.clone()).len(), 200);
+
+        // A static scene creates no bind groups.
+        for _ in 0..10 {
+            assert!(keys.update(textures.clone()).is_empty());
+        }
+        assert_eq!(keys.created(), 200);
+
+        // A new texture gets exactly one.
+        textures.push((EguiTextureId::User(200), new_key(200)));
+        assert_eq!(keys.update(textures.clone()), [EguiTextureId::User(200)]);
+        assert!(keys.update(textures.clone()).is_empty());
+
+        // Prepared again, a texture gets a new bind group.
+        textures[3].1.texture_view = TextureViewId::new();
+        assert_eq!(keys.update(textures.clone()), [EguiTextureId::User(3)]);
+
+        // Removed textures are forgotten.
+        let removed = textures.pop().unwrap();
+        assert!(keys.update(textures.clone()).is_empty());
+        assert!(!keys.contains(&removed.0));
+        assert_eq!(keys.created(), 202);
+    }
+
+    #[test]
+    fn test_teardown() {
+        fn ui_system(mut contexts: EguiContexts) {
+            let Some(ctx) = contexts.try_ctx_mut() else {
+                return;
+            };
+            egui::Window::new("Hello").show(ctx, |ui| {
+                ui.label("world");
+            });
+        }
+
+        // Rebuilding the app after a teardown works the same.
+        for _ in 0..2 {
+            let mut app = headless_app();
+            app.add_systems(Update, ui_system);
+            app.update();
+            app.update();
+            let image = app
+                .world
+                .resource_mut::<Assets<Image>>()
+                .add(Image::default());
+            app.world
+                .resource_mut::<EguiUserTextures>()
+                .add_image(image.clone());
+            let managed_textures: Vec<_> = app
+                .world
+                .resource::<EguiManagedTextures>()
+                .values()
+                .map(|managed_texture| managed_texture.handle.id())
+                .collect();
+            assert!(!managed_textures.is_empty());
+
+            EguiPlugin::teardown(&mut app);
+            // Systems may run one more frame.
+            app.update();
+            assert_eq!(
+                app.world
+                    .query_filtered::<(), With<EguiContext>>()
+                    .iter(&app.world)
+                    .count(),
+                0
+            );
+            assert!(app.world.resource::<EguiManagedTextures>().is_empty());
+            let image_assets = app.world.resource::<Assets<Image>>();
+            assert!(managed_textures
+                .iter()
+                .all(|id| !image_assets.contains(*id)));
+            assert!(app
+                .world
+                .resource::<EguiUserTextures>()
+                .image_id(&image)
+                .is_none());
+
+            // Windows spawned after the teardown don't get a context.
+            let window = app.world.spawn(Window::default()).id();
+            app.update();
+            app.update();
+            assert!(app.world.get::<EguiContext>(window).is_none());
+            if let Ok(render_app) = app.get_sub_app(RenderApp) {
+                let render_graph_nodes = render_app
+                    .world
+                    .resource::<render_systems::EguiRenderGraphNodes>();
+                assert!(render_graph_nodes.is_empty());
+            }
+        }
+    }
+
+    #[test]
+    fn test_accurate_color_mode() {
+        use bevy::render::render_resource::TextureFormat;
+
+        let color = egui::Color32::from_rgba_premultiplied(100, 66, 0, 128);
+        let color_image = egui::ColorImage::new([2, 2], color);
+        let image = egui_node::color_image_as_bevy_image(
+            &color_image,
+            ImageSampler::Default,
+            EguiColorMode::Accurate,
+        );
+        // Premultiplied colors are uploaded as is.
+        assert_eq!(image.texture_descriptor.format, TextureFormat::Rgba8Unorm);
+        assert_eq!(image.data[..4], [100, 66, 0, 128]);
+
+        let mut app = headless_app();
+        app.add_systems(Update, |mut contexts: EguiContexts| {
+            egui::Window::new("Hello").show(contexts.ctx_mut(), |ui| {
+                ui.label("world");
+            });
+        });
+        let other_window = app.world.spawn(Window::default()).id();
+        app.update();
+        app.update();
+        let texture_formats = |app: &App, context: Entity| {
+            let image_assets = app.world.resource::<Assets<Image>>();
+            app.world
+                .resource::<EguiManagedTextures>()
+                .iter()
+                .filter(|((window_id, _), _)| *window_id == context)
+                .map(|(_, managed_texture)| {
+                    image_assets
+                        .get(&managed_texture.handle)
+                        .unwrap()
+                        .texture_descriptor
+                        .format
+                })
+                .collect::<Vec<_>>()
+        };
+        let window = app
+            .world
+            .query_filtered::<Entity, With<PrimaryWindow>>()
+            .single(&app.world);
+        for context in [window, other_window] {
+            let formats = texture_formats(&app, context);
+            assert!(!formats.is_empty());
+            assert!(formats
+                .iter()
+                .all(|&format| format == TextureFormat::Rgba8UnormSrgb));
+        }
+
+        // Switching modes uploads the managed textures of the context again.
+        app.world.entity_mut(window).insert(EguiColorMode::Accurate);
+        app.update();
+        assert!(texture_formats(&app, window)
+            .iter()
+            .all(|&format| format == TextureFormat::Rgba8Unorm));
+        assert!(texture_formats(&app, other_window)
+            .iter()
+            .all(|&format| format == TextureFormat::Rgba8UnormSrgb));
+    }
+
+    #[test]
+    fn test_output_changed() {
+        #[derive(Resource, Default)]
+        struct Checked(bool);
+
+        let (mut app, window) = input_test_app();
+        app.init_resource::<Checked>().add_systems(
+            Update,
+            |mut contexts: EguiContexts, mut checked: ResMut<Checked>| {
+                egui::CentralPanel::default().show(contexts.ctx_mut(), |ui| {
+                    ui.checkbox(&mut checked.0, "Checked");
+                });
+            },
+        );
+        app.world
+            .entity_mut(window)
+            .insert(EguiOutputChanged::default());
+        let changed = |app: &App| {
+            (
+                app.world.get::<EguiOutputChanged>(window).unwrap().changed,
+                app.world.resource::<EguiAnyOutputChanged>().0,
+            )
+        };
+        app.update();
+        assert_eq!(changed(&app), (true, true));
+        for _ in 0..5 {
+            app.update();
+        }
+        assert_eq!(changed(&app), (false, false));
+
+        app.world.resource_mut::<Checked>().0 = true;
+        app.update();
+        assert_eq!(changed(&app), (true, true));
+        app.update();
+        assert_eq!(changed(&app), (false, false));
+    }
+
+    #[test]
+    fn test_font_atlas_follows_scale() {
+        let (mut app, window) = input_test_app();
+        app.world
+            .entity_mut(window)
+            .insert(EguiTessellationSettings {
+                round_text_to_pixels: Some(false),
+                ..Default::default()
+            });
+        app.add_systems(Update, |mut contexts: EguiContexts| {
+            egui::CentralPanel::default().show(contexts.ctx_mut(), |ui| {
+                ui.label("Sharp text");
+            });
+        });
+        let mut reader = app
+            .world
+            .resource::<Events<EguiManagedTextureUpdated>>()
+            .get_reader_current();
+        let mut texture_updates = |app: &mut App| {
+            app.update();
+            let events = app.world.resource::<Events<EguiManagedTextureUpdated>>();
+            reader.read(events).count()
+        };
+        // The fonts were created during the first frame.
+        texture_updates(&mut app);
+        for _ in 0..3 {
+            assert_eq!(texture_updates(&mut app), 0);
+        }
+        let ctx = app.world.get::<EguiContext>(window).unwrap().ctx.clone();
+        assert!(!ctx.tessellation_options(|options| options.round_text_to_pixels));
+
+        // The fonts are regenerated once for a new scale factor.
+        app.world.resource_mut::<EguiSettings>().scale_factor = 1.25;
+        let updates: usize = (0..3).map(|_| texture_updates(&mut app)).sum();
+        assert!(updates > 0);
+        assert_eq!(ctx.pixels_per_point(), 1.25);
+        for _ in 0..3 {
+            assert_eq!(texture_updates(&mut app), 0);
+        }
+    }
+
+    #[test]
+    fn test_reflection() {
+        use bevy::{
+            ecs::reflect::AppTypeRegistry,
+            reflect::{
+                serde::{ReflectSerializer, UntypedReflectDeserializer},
+                FromReflect,
+            },
+        };
+        use serde::de::DeserializeSeed;
+
+        let app = headless_app();
+        let type_registry = app.world.resource::<AppTypeRegistry>().read();
+        for type_path in [
+            "bevy_egui::EguiSettings",
+            "bevy_egui::EguiColorMode",
+            "bevy_egui::EguiWindowAlphaMode",
+            "bevy_egui::LongPressConfig",
+            "bevy_egui::EguiWantsInput",
+            "bevy_egui::EguiRenderLimits",
+            "bevy_egui::WindowSize",
+            "bevy_egui::EguiObservedCanvasSize",
+            "bevy_egui::EguiComputedScale",
+            "bevy_egui::EguiPrimaryContext",
+            "bevy_egui::EguiContextId",
+            "bevy_egui::EguiTextCursorInfo",
+            "bevy_egui::EguiTessellationSettings",
+            "bevy_egui::input::EguiInputSuppressed",
+        ] {
+            assert!(
+                type_registry.get_with_type_path(type_path).is_some(),
+                "{type_path} isn't registered"
+            );
+        }
+        for type_id in [
+            std::any::TypeId::of::<WindowSize>(),
+            std::any::TypeId::of::<EguiColorMode>(),
+            std::any::TypeId::of::<EguiWindowAlphaMode>(),
+            std::any::TypeId::of::<LongPressConfig>(),
+        ] {
+            assert!(type_registry
+                .get_type_data::<ReflectComponent>(type_id)
+                .is_some());
+        }
+        assert!(type_registry
+            .get_type_data::<ReflectResource>(std::any::TypeId::of::<EguiSettings>())
+            .is_some());
+
+        let settings = EguiSettings {
+            scale_factor: 1.5,
+            disabled_input_sources: vec!["gamepad".to_owned()],
+            max_indices_per_draw: Some(3000),
+            ..Default::default()
+        };
+        let serialized =
+            ron::to_string(&ReflectSerializer::new(&settings, &type_registry)).unwrap();
+        let mut deserializer = ron::Deserializer::from_str(&serialized).unwrap();
+        let reflected = UntypedReflectDeserializer::new(&type_registry)
+            .deserialize(&mut deserializer)
+            .unwrap();
+        assert_eq!(
+            EguiSettings::from_reflect(reflected.as_ref()).unwrap(),
+            settings
+        );
+    }
+
+    #[test]
+    fn test_prewarm() {
+        let (mut app, window) = input_test_app();
+        let mut reader = app
+            .world
+            .resource::<Events<EguiManagedTextureUpdated>>()
+            .get_reader_current();
+        let mut texture_updates = |app: &mut App| {
+            app.update();
+            let events = app.world.resource::<Events<EguiManagedTextureUpdated>>();
+            reader.read(events).count()
+        };
+        texture_updates(&mut app);
+        assert_eq!(texture_updates(&mut app), 0);
+
+        let text = "Паўза";
+        app.world.entity_mut(window).insert(EguiPrewarm {
+            texts: vec![text.to_owned()],
+            font_ids: vec![egui::FontId::proportional(40.0)],
+        });
+        assert!(texture_updates(&mut app) > 0);
+        assert!(app.world.get::<EguiPrewarm>(window).is_none());
+        // Nothing is painted by the prewarming pass, only the font atlas is updated.
+        let render_output = app.world.get::<EguiRenderOutput>(window).unwrap();
+        assert!(render_output.paint_jobs.is_empty());
+        assert!(app
+            .world
+            .resource::<EguiManagedTextures>()
+            .contains_key(&(window, 0)));
+        assert_eq!(texture_updates(&mut app), 0);
+
+        // Showing the text later doesn't grow the atlas.
+        app.add_systems(Update, move |mut contexts: EguiContexts| {
+            egui::CentralPanel::default().show(contexts.ctx_mut(), |ui| {
+                ui.label(egui::RichText::new(text).size(40.0));
+                ui.label(text);
+            });
+        });
+        for _ in 0..3 {
+            assert_eq!(texture_updates(&mut app), 0);
+        }
+    }
+
+    #[test]
+    fn test_custom_schedules() {
+        use bevy::{app::MainScheduleOrder, ecs::schedule::ScheduleLabel};
+
+        #[derive(ScheduleLabel, Clone, Debug, PartialEq, Eq, Hash)]
+        struct EguiInputSchedule;
+        #[derive(ScheduleLabel, Clone, Debug, PartialEq, Eq, Hash)]
+        struct UiSchedule;
+
+        let broken = EguiSchedules {
+            process_input: EguiInputSchedule.intern(),
+            ..Default::default()
+        };
+        assert!(broken.validate().is_err());
+        let build = || {
+            App::new().add_plugins(EguiPlugin::default().with_schedules(broken));
+        };
+        assert!(std::panic::catch_unwind(std::panic::AssertUnwindSafe(build)).is_err());
+
+        let schedules = EguiSchedules {
+            init_contexts: EguiInputSchedule.intern(),
+            process_input: EguiInputSchedule.intern(),
+            begin_frame: UiSchedule.intern(),
+            process_output: UiSchedule.intern(),
+        };
+        assert_eq!(schedules.validate(), Ok(()));
+        let mut app = headless_app_with_plugin(EguiPlugin::default().with_schedules(schedules));
+        let mut order = app.world.resource_mut::<MainScheduleOrder>();
+        order.insert_after(PreUpdate, EguiInputSchedule);
+        order.insert_after(Update, UiSchedule);
+
+        #[derive(Resource, Default)]
+        struct Clicked(bool);
+        app.init_resource::<Clicked>().add_systems(
+            UiSchedule,
+            (|mut contexts: EguiContexts, mut clicked: ResMut<Clicked>| {
+                egui::Area::new("area".into())
+                    .fixed_pos(egui::Pos2::ZERO)
+                    .show(contexts.ctx_mut(), |ui| {
+                        if ui.button("Click me").clicked() {
+                            clicked.0 = true;
+                        }
+                    });
+            })
+            .after(EguiSet::BeginFrame)
+            .before(EguiSet::ProcessOutput),
+        );
+        // New areas are invisible during their first pass.
+        app.update();
+        app.update();
+        let window = app
+            .world
+            .query_filtered::<Entity, With<PrimaryWindow>>()
+            .single(&app.world);
+        assert!(app.world.get::<EguiContext>(window).is_some());
+        let render_output = app.world.get::<EguiRenderOutput>(window).unwrap();
+        assert!(!render_output.paint_jobs.is_empty());
+        assert!(app
+            .world
+            .resource::<EguiManagedTextures>()
+            .contains_key(&(window, 0)));
+
+        for state in [ButtonState::Pressed, ButtonState::Released] {
+            app.world.send_event(CursorMoved {
+                window,
+                position: Vec2::new(10.0, 10.0),
+                delta: None,
+            });
+            app.world.send_event(MouseButtonInput {
+                button: MouseButton::Left,
+                state,
+                window,
+            });
+            app.update();
+        }
+        assert!(app.world.resource::<Clicked>().0);
+    }
+
+    #[test]
+    fn test_last_user_activity() {
+        use crate::input::{
+            egui_user_active_within, EguiInputEvent, EguiInputEventSource, EguiInputSuppressed,
+            EguiLastUserActivity,
+        };
+
+        let (mut app, window) = input_test_app();
+        app.insert_resource(TimeUpdateStrategy::ManualDuration(
+            std::time::Duration::from_secs(1),
+        ));
+        #[derive(Resource, Default)]
+        struct Active(bool);
+        app.init_resource::<Active>().add_systems(
+            Update,
+            (
+                |mut active: ResMut<Active>| active.0 = false,
+                (|mut active: ResMut<Active>| active.0 = true).run_if(egui_user_active_within(2.5)),
+            )
+                .chain(),
+        );
+        for _ in 0..3 {
+            app.update();
+        }
+        assert_eq!(
+            *app.world.resource::<EguiLastUserActivity>(),
+            EguiLastUserActivity::default()
+        );
+
+        // Synthetic events don't count.
+        app.world.send_event(EguiInputEvent {
+            context: window,
+            event: egui::Event::PointerGone,
+            source: EguiInputEventSource::Synthetic,
+        });
+        app.world.entity_mut(window).insert(EguiInputSuppressed {
+            pointer: true,
+            keyboard: false,
+        });
+        app.update();
+        assert!(app
+            .world
+            .resource::<CapturedEvents>()
+            .0
+            .contains(&egui::Event::PointerGone));
+        assert_eq!(
+            *app.world.resource::<EguiLastUserActivity>(),
+            EguiLastUserActivity::default()
+        );
+        assert!(!app.world.resource::<Active>().0);
+
+        app.world.entity_mut(window).remove::<EguiInputSuppressed>();
+        send_moves_and_click(&mut app, window);
+        let last_activity = *app.world.resource::<EguiLastUserActivity>();
+        assert_eq!(last_activity.context, Some(window));
+        assert_eq!(
+            last_activity.time,
+            app.world
+                .resource::<Time<bevy::time::Real>>()
+                .elapsed_seconds_f64()
+        );
+        assert!(app.world.resource::<Active>().0);
+
+        app.update();
+        app.update();
+        assert!(app.world.resource::<Active>().0);
+        app.update();
+        assert!(!app.world.resource::<Active>().0);
+        assert_eq!(*app.world.resource::<EguiLastUserActivity>(), last_activity);
+    }
+
+    #[test]
+    fn test_window_alpha_mode() {
+        use crate::render_systems::writes_alpha;
+        use bevy::window::CompositeAlphaMode;
+
+        assert!(writes_alpha(
+            EguiWindowAlphaMode::Auto,
+            CompositeAlphaMode::PreMultiplied
+        ));
+        assert!(writes_alpha(
+            EguiWindowAlphaMode::Auto,
+            CompositeAlphaMode::Auto
+        ));
+        assert!(!writes_alpha(
+            EguiWindowAlphaMode::Auto,
+            CompositeAlphaMode::Opaque
+        ));
+        assert!(!writes_alpha(
+            EguiWindowAlphaMode::Opaque,
+            CompositeAlphaMode::PreMultiplied
+        ));
+        assert!(writes_alpha(
+            EguiWindowAlphaMode::PreMultiplied,
+            CompositeAlphaMode::Opaque
+        ));
+    }
+
+    #[test]
+    fn test_user_texture_namespaces() {
+        let mut user_textures = EguiUserTextures::default();
+        let user_id = |texture_id| match texture_id {
+            egui::TextureId::User(id) => id,
+            egui::TextureId::Managed(_) => unreachable!(),
+        };
+        let image = |i| Handle::<Image>::weak_from_u128(i);
+
+        // The same image gets a different id in every namespace.
+        let legacy = user_id(user_textures.add_image(image(0)));
+        let a = user_id(user_textures.namespace("a").add_image(image(0)));
+        let b = user_id(user_textures.namespace("b").add_image(image(0)));
+        assert_ne!(legacy, a);
+        assert_ne!(a, b);
+        assert_eq!(user_textures.namespace_of(legacy), None);
+        assert_eq!(user_textures.namespace_of(a), Some("a"));
+        assert_eq!(user_textures.namespace_of(b), Some("b"));
+        assert_eq!(user_textures.len(), 3);
+
+        // Removing an image from a namespace keeps the other registrations.
+        assert_eq!(
+            user_textures.namespace("a").remove_image(&image(0)),
+            Some(egui::TextureId::User(a))
+        );
+        assert!(!user_textures.contains_texture_id(a));
+        assert!(user_textures.contains_texture_id(b));
+        assert!(user_textures.contains_texture_id(legacy));
+        assert_eq!(
+            user_textures.namespace("b").image_id(&image(0)),
+            Some(egui::TextureId::User(b))
+        );
+        assert_eq!(
+            user_textures.image_id(&image(0)),
+            Some(egui::TextureId::User(legacy))
+        );
+
+        // Ids added and removed in a namespace never alias the live ids of another one.
+        let mut live_b = vec![b];
+        for i in 1..100 {
+            let id = user_id(user_textures.namespace("a").add_image(image(i)));
+            assert!(!live_b.contains(&id), "{id} aliases a texture of b");
+            assert_eq!(user_textures.namespace_of(id), Some("a"));
+            user_textures.namespace("a").remove_image(&image(i));
+            live_b.push(user_id(user_textures.namespace("b").add_image(image(i))));
+        }
+        assert!(live_b
+            .iter()
+            .all(|id| user_textures.contains_texture_id(*id)
+                && user_textures.namespace_of(*id) == Some("b")));
+        assert_eq!(user_textures.len(), 1 + live_b.len());
+    }
+
+    #[cfg(feature = "render")]
+    #[test]
+    fn test_empty_render_output_is_not_extracted() {
+        let mut app = headless_app();
+        app.update();
+        let window = app
+            .world
+            .query_filtered::<Entity, With<PrimaryWindow>>()
+            .single(&app.world);
+
+        // Nothing is shown, and the font texture has already been uploaded.
+        app.update();
+        let render_output = app.world.get::<EguiRenderOutput>(window).unwrap();
+        assert!(render_output.is_empty());
+        assert!(EguiRenderOutput::extract_component(render_output).is_none());
+
+        app.add_systems(Update, |mut contexts: EguiContexts| {
+            egui::Window::new("Hello").show(contexts.ctx_mut(), |ui| ui.label("world"));
+        });
+        // Windows are invisible during the first frame, while their size is being computed.
+        app.update();
+        app.update();
+        let render_output = app.world.get::<EguiRenderOutput>(window).unwrap();
+        assert!(EguiRenderOutput::extract_component(render_output).is_some());
     }
 }
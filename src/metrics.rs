@@ -0,0 +1,293 @@
+//! Aggregated interaction metrics (where players press the pointer, how much they type and
+//! scroll), for UX research builds.
+//!
+//! The metrics are read from the [`EguiInput`] of the contexts once the input is processed, so
+//! the input systems don't change when the plugin is added.
+
+use crate::{EguiInput, EguiSchedules, EguiSet};
+use bevy::{
+    app::{App, Plugin},
+    ecs::{
+        entity::Entity,
+        schedule::IntoSystemConfigs,
+        system::{Query, ResMut, Resource},
+    },
+    utils::HashMap,
+};
+use serde::{Deserialize, Serialize};
+
+/// The maximum number of columns and rows of the grids of [`EguiInteractionMetrics`].
+pub const MAX_GRID_SIDE: u32 = 64;
+
+/// Records [`EguiInteractionMetrics`]. Requires [`EguiPlugin`](crate::EguiPlugin).
+///
+/// ```no_run
+/// use bevy::prelude::*;
+/// use bevy_egui::{
+///     metrics::{EguiInteractionMetrics, EguiInteractionMetricsPlugin},
+///     EguiPlugin,
+/// };
+///
+/// fn upload_metrics_system(
+///     keys: Res<ButtonInput<KeyCode>>,
+///     mut metrics: ResMut<EguiInteractionMetrics>,
+/// ) {
+///     if keys.just_pressed(KeyCode::F9) {
+///         let snapshot = metrics.take_metrics();
+///         // Serialize the snapshot with any serde format and upload it.
+///         # let _ = snapshot;
+///     }
+/// }
+///
+/// App::new()
+///     .add_plugins(DefaultPlugins)
+///     .add_plugins(EguiPlugin::default())
+///     .add_plugins(EguiInteractionMetricsPlugin)
+///     .add_systems(Update, upload_metrics_system)
+///     .run();
+/// ```
+pub struct EguiInteractionMetricsPlugin;
+
+impl Plugin for EguiInteractionMetricsPlugin {
+    fn build(&self, app: &mut App) {
+        let schedule = EguiSchedules::from_app(app).begin_frame;
+        app.init_resource::<EguiInteractionMetrics>().add_systems(
+            schedule,
+            record_interaction_metrics_system
+                .after(EguiSet::ProcessInput)
+                .before(EguiSet::BeginFrame),
+        );
+    }
+}
+
+/// Metrics of all contexts since the last [`EguiInteractionMetrics::take_metrics`] call.
+///
+/// Pointer presses are counted in the cells of a grid laid over the screen rect of each context
+/// (8 × 8 by default), so the memory used doesn't depend on the number of presses.
+#[derive(Resource, Clone, Debug)]
+pub struct EguiInteractionMetrics {
+    grid_size: [u32; 2],
+    contexts: HashMap<Entity, EguiContextMetrics>,
+}
+
+impl Default for EguiInteractionMetrics {
+    fn default() -> Self {
+        Self {
+            grid_size: [8, 8],
+            contexts: HashMap::default(),
+        }
+    }
+}
+
+impl EguiInteractionMetrics {
+    /// Number of columns and rows of the grids.
+    pub fn grid_size(&self) -> [u32; 2] {
+        self.grid_size
+    }
+
+    /// Sets the number of columns and rows of the grids, clamped between 1 and
+    /// [`MAX_GRID_SIDE`]. The recorded metrics are reset.
+    pub fn set_grid_size(&mut self, columns: u32, rows: u32) {
+        self.grid_size = [
+            columns.clamp(1, MAX_GRID_SIDE),
+            rows.clamp(1, MAX_GRID_SIDE),
+        ];
+        self.contexts.clear();
+    }
+
+    /// Returns the metrics of a context.
+    pub fn context(&self, context: Entity) -> Option<&EguiContextMetrics> {
+        self.contexts.get(&context)
+    }
+
+    /// Returns the metrics recorded since the last call, and resets them.
+    pub fn take_metrics(&mut self) -> EguiMetricsSnapshot {
+        let mut contexts: Vec<_> = self
+            .contexts
+            .drain()
+            .map(|(context, metrics)| (context.to_bits(), metrics))
+            .collect();
+        contexts.sort_unstable_by_key(|(context, _)| *context);
+        EguiMetricsSnapshot {
+            grid_size: self.grid_size,
+            contexts,
+        }
+    }
+}
+
+/// Metrics of a context, see [`EguiInteractionMetrics`].
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct EguiContextMetrics {
+    /// Size of the screen rect of the context in Egui points, when the last event was recorded.
+    pub screen_size: [f32; 2],
+    /// Pointer presses per cell of the grid, row by row from the top left corner.
+    pub pointer_presses: Vec<u32>,
+    /// Key presses (repeats excluded).
+    pub key_presses: u32,
+    /// Scrolled distance in Egui points, horizontally and vertically.
+    pub scroll_distance: [f32; 2],
+}
+
+impl EguiContextMetrics {
+    /// Returns the number of presses in a cell of the grid.
+    pub fn pointer_presses_at(&self, grid_size: [u32; 2], column: u32, row: u32) -> u32 {
+        self.pointer_presses
+            .get((row * grid_size[0] + column) as usize)
+            .copied()
+            .unwrap_or(0)
+    }
+}
+
+/// Metrics returned by [`EguiInteractionMetrics::take_metrics`].
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct EguiMetricsSnapshot {
+    /// Number of columns and rows of the grids.
+    pub grid_size: [u32; 2],
+    /// Metrics per context, the contexts are identified by the bits of their entities (see
+    /// [`Entity::to_bits`]).
+    pub contexts: Vec<(u64, EguiContextMetrics)>,
+}
+
+/// Records the events of the contexts into [`EguiInteractionMetrics`].
+pub fn record_interaction_metrics_system(
+    mut metrics: ResMut<EguiInteractionMetrics>,
+    contexts: Query<(Entity, &EguiInput)>,
+) {
+    let grid_size = metrics.grid_size;
+    for (context, egui_input) in contexts.iter() {
+        if egui_input.events.is_empty() {
+            continue;
+        }
+        let screen_rect = egui_input.screen_rect.unwrap_or(egui::Rect::NOTHING);
+        let context_metrics =
+            metrics
+                .contexts
+                .entry(context)
+                .or_insert_with(|| EguiContextMetrics {
+                    pointer_presses: vec![0; (grid_size[0] * grid_size[1]) as usize],
+                    ..Default::default()
+                });
+        context_metrics.screen_size = screen_rect.size().into();
+
+        for event in &egui_input.events {
+            match event {
+                egui::Event::PointerButton {
+                    pos, pressed: true, ..
+                } => {
+                    let Some(cell) = grid_cell(screen_rect, grid_size, *pos) else {
+                        continue;
+                    };
+                    context_metrics.pointer_presses[cell] += 1;
+                }
+                egui::Event::Key {
+                    pressed: true,
+                    repeat: false,
+                    ..
+                } => context_metrics.key_presses += 1,
+                egui::Event::Scroll(delta) => {
+                    context_metrics.scroll_distance[0] += delta.x.abs();
+                    context_metrics.scroll_distance[1] += delta.y.abs();
+                }
+                _ => {}
+            }
+        }
+    }
+}
+
+/// Returns the index of the cell containing the position, [`None`] if the screen rect is empty.
+fn grid_cell(screen_rect: egui::Rect, grid_size: [u32; 2], pos: egui::Pos2) -> Option<usize> {
+    if !(screen_rect.width() > 0.0 && screen_rect.height() > 0.0) {
+        return None;
+    }
+    let cell = |pos: f32, min: f32, size: f32, count: u32| {
+        (((pos - min) / size * count as f32).floor().max(0.0) as u32).min(count - 1)
+    };
+    let column = cell(pos.x, screen_rect.min.x, screen_rect.width(), grid_size[0]);
+    let row = cell(pos.y, screen_rect.min.y, screen_rect.height(), grid_size[1]);
+    Some((row * grid_size[0] + column) as usize)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{test_utils::input_test_app, WindowSize};
+    use bevy::{
+        input::{
+            mouse::{MouseButton, MouseButtonInput},
+            ButtonState,
+        },
+        math::Vec2,
+        window::CursorMoved,
+    };
+
+    #[test]
+    fn test_interaction_metrics() {
+        use bevy::input::mouse::{MouseScrollUnit, MouseWheel};
+
+        let (mut app, window) = input_test_app();
+        // Nothing is recorded without the plugin.
+        assert!(!app.world.contains_resource::<EguiInteractionMetrics>());
+
+        app.add_plugins(EguiInteractionMetricsPlugin);
+        app.world
+            .resource_mut::<EguiInteractionMetrics>()
+            .set_grid_size(4, 1000);
+        assert_eq!(
+            app.world.resource::<EguiInteractionMetrics>().grid_size(),
+            [4, MAX_GRID_SIDE]
+        );
+        app.world
+            .resource_mut::<EguiInteractionMetrics>()
+            .set_grid_size(4, 2);
+        let window_size = *app.world.get::<WindowSize>(window).unwrap();
+        let (width, height) = (window_size.width(), window_size.height());
+        let click = |app: &mut App, position| {
+            app.world.send_event(CursorMoved {
+                window,
+                position,
+                delta: None,
+            });
+            for state in [ButtonState::Pressed, ButtonState::Released] {
+                app.world.send_event(MouseButtonInput {
+                    button: MouseButton::Left,
+                    state,
+                    window,
+                });
+                app.update();
+            }
+        };
+        click(&mut app, Vec2::new(1.0, 1.0));
+        click(&mut app, Vec2::new(width * 0.6, height * 0.9));
+        click(&mut app, Vec2::new(width * 0.6, height * 0.9));
+        app.world.send_event(MouseWheel {
+            unit: MouseScrollUnit::Pixel,
+            x: 0.0,
+            y: -30.0,
+            window,
+        });
+        app.update();
+
+        let metrics = app.world.resource::<EguiInteractionMetrics>();
+        let context_metrics = metrics.context(window).unwrap();
+        assert_eq!(context_metrics.screen_size, [width, height]);
+        assert_eq!(context_metrics.pointer_presses_at([4, 2], 0, 0), 1);
+        assert_eq!(context_metrics.pointer_presses_at([4, 2], 2, 1), 2);
+        assert_eq!(context_metrics.pointer_presses.iter().sum::<u32>(), 3);
+        assert_eq!(context_metrics.scroll_distance, [0.0, 30.0]);
+
+        let snapshot = app
+            .world
+            .resource_mut::<EguiInteractionMetrics>()
+            .take_metrics();
+        assert_eq!(snapshot.grid_size, [4, 2]);
+        assert_eq!(snapshot.contexts.len(), 1);
+        assert_eq!(snapshot.contexts[0].0, window.to_bits());
+        // Taking the metrics resets them.
+        assert!(app
+            .world
+            .resource_mut::<EguiInteractionMetrics>()
+            .take_metrics()
+            .contexts
+            .is_empty());
+    }
+}
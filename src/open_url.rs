@@ -0,0 +1,174 @@
+//! Deciding what happens to the URLs that Egui opens.
+
+use bevy::ecs::{entity::Entity, event::Event, system::Resource};
+
+/// Decides what happens to the URLs Egui opens (clicked hyperlinks, [`egui::Context::open_url`]),
+/// consulted during [`EguiSet::ProcessOutput`](crate::EguiSet::ProcessOutput) before anything is opened.
+///
+/// Builds that mustn't open URLs (consoles, sandboxed storefronts) can forward them to an overlay
+/// browser of their own with [`EguiOpenUrlRequested`], or block them and tell the player with
+/// [`EguiOpenUrlBlocked`].
+///
+/// ```rust
+/// use bevy::prelude::*;
+/// use bevy_egui::open_url::{EguiOpenUrlPolicy, EguiUrlDecision};
+///
+/// fn setup_system(mut commands: Commands) {
+///     commands.insert_resource(EguiOpenUrlPolicy::filtered(|url| {
+///         if url.starts_with("https://example.com/") {
+///             EguiUrlDecision::Allow
+///         } else {
+///             EguiUrlDecision::Block
+///         }
+///     }));
+/// }
+/// ```
+#[derive(Resource, Default)]
+pub enum EguiOpenUrlPolicy {
+    /// URLs are opened with the default browser (requires the `open_url` feature, they're
+    /// ignored otherwise).
+    #[default]
+    OpenDirectly,
+    /// URLs are never opened, an [`EguiOpenUrlRequested`] event is sent instead.
+    EmitEventOnly,
+    /// The function decides for every URL.
+    Filtered(Box<dyn Fn(&str) -> EguiUrlDecision + Send + Sync>),
+}
+
+impl EguiOpenUrlPolicy {
+    /// Creates an [`EguiOpenUrlPolicy::Filtered`] policy.
+    pub fn filtered(filter: impl Fn(&str) -> EguiUrlDecision + Send + Sync + 'static) -> Self {
+        Self::Filtered(Box::new(filter))
+    }
+
+    /// Returns what happens to a URL.
+    #[must_use]
+    pub fn decide(&self, url: &str) -> EguiUrlDecision {
+        match self {
+            Self::OpenDirectly => EguiUrlDecision::Allow,
+            Self::EmitEventOnly => EguiUrlDecision::Emit,
+            Self::Filtered(filter) => filter(url),
+        }
+    }
+}
+
+/// What happens to a URL, see [`EguiOpenUrlPolicy`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum EguiUrlDecision {
+    /// The URL is opened with the default browser (requires the `open_url` feature).
+    Allow,
+    /// The URL isn't opened, an [`EguiOpenUrlBlocked`] event is sent.
+    Block,
+    /// The URL isn't opened, an [`EguiOpenUrlRequested`] event is sent.
+    Emit,
+}
+
+/// Is sent when a context asks to open a URL that [`EguiOpenUrlPolicy`] forwards to the app.
+#[derive(Event, Clone, Debug, PartialEq, Eq)]
+pub struct EguiOpenUrlRequested {
+    /// Entity of the context (window) that asked.
+    pub context: Entity,
+    /// The URL.
+    pub url: String,
+    /// Whether the URL was meant to be opened in a new tab.
+    pub new_tab: bool,
+}
+
+/// Is sent when [`EguiOpenUrlPolicy`] blocks a URL, to let the player know that links are
+/// disabled.
+#[derive(Event, Clone, Debug, PartialEq, Eq)]
+pub struct EguiOpenUrlBlocked {
+    /// Entity of the context (window) that asked.
+    pub context: Entity,
+    /// The URL.
+    pub url: String,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{input::EguiInputEvent, test_utils::input_test_app, EguiContexts};
+    use bevy::{
+        app::{App, Update},
+        ecs::event::Events,
+    };
+
+    #[test]
+    fn test_open_url_policy() {
+        let (mut app, window) = input_test_app();
+        app.add_systems(Update, |mut contexts: EguiContexts| {
+            egui::CentralPanel::default().show(contexts.ctx_mut(), |ui| {
+                ui.hyperlink_to("link", "https://example.com/");
+            });
+        });
+        app.update();
+        let link_pos = egui::pos2(16.0, 14.0);
+        // Returns the URLs requested and blocked after a click on the link.
+        let click = |app: &mut App| {
+            let mut requested_reader = app
+                .world
+                .resource::<Events<EguiOpenUrlRequested>>()
+                .get_reader_current();
+            let mut blocked_reader = app
+                .world
+                .resource::<Events<EguiOpenUrlBlocked>>()
+                .get_reader_current();
+            for pressed in [true, false] {
+                app.world.send_event(EguiInputEvent::new(
+                    window,
+                    egui::Event::PointerMoved(link_pos),
+                ));
+                app.world.send_event(EguiInputEvent::new(
+                    window,
+                    egui::Event::PointerButton {
+                        pos: link_pos,
+                        button: egui::PointerButton::Primary,
+                        pressed,
+                        modifiers: Default::default(),
+                    },
+                ));
+                app.update();
+            }
+            app.update();
+            let requested: Vec<_> = requested_reader
+                .read(app.world.resource::<Events<EguiOpenUrlRequested>>())
+                .cloned()
+                .collect();
+            let blocked: Vec<_> = blocked_reader
+                .read(app.world.resource::<Events<EguiOpenUrlBlocked>>())
+                .map(|event| event.url.clone())
+                .collect();
+            (requested, blocked)
+        };
+
+        // Opening URLs directly isn't exercised, as it would launch a browser.
+        let policy = EguiOpenUrlPolicy::default();
+        assert_eq!(
+            policy.decide("https://example.com/"),
+            EguiUrlDecision::Allow
+        );
+
+        app.insert_resource(EguiOpenUrlPolicy::EmitEventOnly);
+        let expected = EguiOpenUrlRequested {
+            context: window,
+            url: "https://example.com/".to_owned(),
+            new_tab: false,
+        };
+        assert_eq!(click(&mut app), (vec![expected.clone()], vec![]));
+        assert_eq!(click(&mut app), (vec![expected.clone()], vec![]));
+
+        app.insert_resource(EguiOpenUrlPolicy::filtered(|url| {
+            if url.starts_with("https://") {
+                EguiUrlDecision::Block
+            } else {
+                EguiUrlDecision::Allow
+            }
+        }));
+        assert_eq!(
+            click(&mut app),
+            (vec![], vec!["https://example.com/".to_owned()])
+        );
+        app.insert_resource(EguiOpenUrlPolicy::filtered(|_| EguiUrlDecision::Emit));
+        assert_eq!(click(&mut app), (vec![expected], vec![]));
+    }
+}
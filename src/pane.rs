@@ -0,0 +1,231 @@
+//! Showing cameras that render to images in resizable Egui panes.
+
+use bevy::{
+    asset::{Assets, Handle},
+    ecs::{
+        component::Component,
+        entity::Entity,
+        system::{Query, Res, ResMut},
+    },
+    math::{Ray3d, UVec2, Vec2},
+    render::{
+        camera::Camera,
+        render_resource::{Extent3d, TextureDimension, TextureFormat, TextureUsages},
+        texture::Image,
+    },
+    time::{Real, Time},
+    transform::components::GlobalTransform,
+};
+
+/// A pane that shows the output of a camera rendering to an image.
+///
+/// The UI code calls [`EguiPaneViewport::show`], which records the size of the pane, and
+/// [`resize_pane_viewports_system`] resizes the image to match it. Cameras targeting the image
+/// update their viewports and projections automatically.
+///
+/// ```no_run
+/// use bevy::{prelude::*, render::camera::RenderTarget};
+/// use bevy_egui::{pane::EguiPaneViewport, EguiContexts};
+///
+/// fn setup(mut commands: Commands, mut images: ResMut<Assets<Image>>, mut contexts: EguiContexts) {
+///     let handle = images.add(EguiPaneViewport::new_image(UVec2::new(512, 512)));
+///     let texture_id = contexts.add_image(handle.clone_weak());
+///     let camera = commands
+///         .spawn(Camera3dBundle {
+///             camera: Camera {
+///                 target: RenderTarget::Image(handle.clone()),
+///                 ..default()
+///             },
+///             ..default()
+///         })
+///         .id();
+///     commands.spawn(EguiPaneViewport::new(camera, texture_id, handle));
+/// }
+///
+/// fn ui_system(mut contexts: EguiContexts, mut panes: Query<&mut EguiPaneViewport>) {
+///     let mut pane = panes.single_mut();
+///     egui::CentralPanel::default().show(contexts.ctx_mut(), |ui| {
+///         pane.show(ui);
+///     });
+/// }
+/// ```
+#[derive(Component, Clone, Debug)]
+pub struct EguiPaneViewport {
+    /// The camera that renders to the image.
+    pub camera: Entity,
+    /// Egui texture id of the image.
+    pub texture_id: egui::TextureId,
+    /// The image the camera renders to.
+    pub handle: Handle<Image>,
+    /// The size of the pane in physical pixels, which the image gets resized to.
+    pub requested_size: UVec2,
+    /// Position of the pointer over the pane, normalized to `[0, 1]` (with `(0, 0)` being
+    /// the top left corner), or [`None`] if the pane isn't hovered.
+    pub pointer_uv: Option<Vec2>,
+    /// How long (in seconds) the requested size needs to stay the same before the image gets
+    /// resized (`0.1` by default), so that the image isn't reallocated every frame while a pane
+    /// is being dragged. In the meantime, the image is stretched to the pane.
+    pub resize_debounce: f32,
+    pending_resize: Option<(UVec2, f32)>,
+}
+
+impl EguiPaneViewport {
+    /// Creates a pane for a camera and the image it renders to.
+    pub fn new(camera: Entity, texture_id: egui::TextureId, handle: Handle<Image>) -> Self {
+        Self {
+            camera,
+            texture_id,
+            handle,
+            requested_size: UVec2::ZERO,
+            pointer_uv: None,
+            resize_debounce: 0.1,
+            pending_resize: None,
+        }
+    }
+
+    /// Creates an image that can be used as a camera render target and shown in Egui.
+    pub fn new_image(size: UVec2) -> Image {
+        let mut image = Image::new_fill(
+            Extent3d {
+                width: size.x.max(1),
+                height: size.y.max(1),
+                depth_or_array_layers: 1,
+            },
+            TextureDimension::D2,
+            &[0, 0, 0, 0],
+            TextureFormat::Bgra8UnormSrgb,
+            Default::default(),
+        );
+        image.texture_descriptor.usage = TextureUsages::TEXTURE_BINDING
+            | TextureUsages::COPY_DST
+            | TextureUsages::RENDER_ATTACHMENT;
+        image
+    }
+
+    /// Shows the image in all the available space of the `ui`, and records the pane size and
+    /// the pointer position.
+    pub fn show(&mut self, ui: &mut egui::Ui) -> egui::Response {
+        let size = ui.available_size().max(egui::Vec2::splat(1.0));
+        let pixels_per_point = ui.ctx().pixels_per_point();
+        self.requested_size = UVec2::new(
+            (size.x * pixels_per_point).round() as u32,
+            (size.y * pixels_per_point).round() as u32,
+        );
+
+        let response = ui.add(
+            egui::Image::new(egui::load::SizedTexture::new(self.texture_id, size))
+                .sense(egui::Sense::click_and_drag()),
+        );
+        self.pointer_uv = response.hover_pos().map(|pos| {
+            let uv = (pos - response.rect.min) / response.rect.size();
+            Vec2::new(uv.x, uv.y)
+        });
+        response
+    }
+
+    /// Returns a ray from the camera through the pointer, which can be used for picking objects
+    /// in the pane.
+    ///
+    /// Returns [`None`] if the pane isn't hovered or the camera viewport isn't computed yet.
+    pub fn pointer_ray(
+        &self,
+        camera: &Camera,
+        camera_transform: &GlobalTransform,
+    ) -> Option<Ray3d> {
+        let viewport_position = self.pointer_uv? * camera.logical_viewport_size()?;
+        camera.viewport_to_world(camera_transform, viewport_position)
+    }
+}
+
+/// Resizes images of [`EguiPaneViewport`]s to the sizes of their panes.
+pub fn resize_pane_viewports_system(
+    time: Res<Time<Real>>,
+    mut panes: Query<&mut EguiPaneViewport>,
+    mut images: ResMut<Assets<Image>>,
+) {
+    let now = time.elapsed_seconds();
+    for mut pane in panes.iter_mut() {
+        let Some(image) = images.get(&pane.handle) else {
+            continue;
+        };
+        let requested_size = pane.requested_size;
+        if requested_size == image.size() || requested_size.min_element() == 0 {
+            if pane.pending_resize.is_some() {
+                pane.pending_resize = None;
+            }
+            continue;
+        }
+
+        match pane.pending_resize {
+            Some((pending_size, since)) if pending_size == requested_size => {
+                if now - since < pane.resize_debounce {
+                    continue;
+                }
+            }
+            _ => {
+                pane.pending_resize = Some((requested_size, now));
+                if pane.resize_debounce > 0.0 {
+                    continue;
+                }
+            }
+        }
+
+        pane.pending_resize = None;
+        if let Some(image) = images.get_mut(&pane.handle) {
+            image.resize(Extent3d {
+                width: requested_size.x,
+                height: requested_size.y,
+                depth_or_array_layers: 1,
+            });
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils::headless_app;
+    use bevy::{app::App, time::TimeUpdateStrategy};
+
+    #[test]
+    fn test_pane_viewport_resize_debounce() {
+        let mut app = headless_app();
+        app.insert_resource(TimeUpdateStrategy::ManualDuration(
+            std::time::Duration::from_millis(50),
+        ));
+        let handle = app
+            .world
+            .resource_mut::<Assets<Image>>()
+            .add(EguiPaneViewport::new_image(bevy::math::UVec2::new(64, 64)));
+        let mut pane = EguiPaneViewport::new(Entity::PLACEHOLDER, egui::TextureId::User(0), handle);
+        pane.resize_debounce = 0.12;
+        let pane = app.world.spawn(pane).id();
+        let image_size = |app: &App| {
+            let handle = &app.world.get::<EguiPaneViewport>(pane).unwrap().handle;
+            app.world
+                .resource::<Assets<Image>>()
+                .get(handle)
+                .unwrap()
+                .size()
+        };
+        let request_size = |app: &mut App, width, height| {
+            app.world
+                .get_mut::<EguiPaneViewport>(pane)
+                .unwrap()
+                .requested_size = bevy::math::UVec2::new(width, height);
+            app.update();
+        };
+
+        // Dragging a pane restarts the debounce on every size change.
+        for width in [100, 110, 120, 130] {
+            request_size(&mut app, width, 80);
+            assert_eq!(image_size(&app), bevy::math::UVec2::new(64, 64));
+        }
+        for _ in 0..2 {
+            request_size(&mut app, 130, 80);
+            assert_eq!(image_size(&app), bevy::math::UVec2::new(64, 64));
+        }
+        request_size(&mut app, 130, 80);
+        assert_eq!(image_size(&app), bevy::math::UVec2::new(130, 80));
+    }
+}
@@ -0,0 +1,160 @@
+//! Running the Egui passes of contexts less often than every frame.
+
+use crate::{
+    input::focus::{primary_context, PrimaryContextQuery},
+    EguiContext,
+};
+use bevy::{
+    ecs::{
+        component::Component, entity::Entity, event::Event, reflect::ReflectComponent,
+        system::Query,
+    },
+    reflect::{std_traits::ReflectDefault, Reflect},
+};
+
+/// How often the Egui pass (running the UI systems and tessellation) of a context happens.
+///
+/// Contexts without the component run a pass every frame. On the frames without a pass,
+/// the last output gets rendered again, and input events are accumulated to be fed to the next
+/// pass. UI systems must skip these frames, as the Egui frame isn't begun:
+///
+/// ```rust
+/// use bevy::prelude::*;
+/// use bevy_egui::{
+///     pass_rate::{primary_context_pass_due, EguiPassRate},
+///     EguiContexts,
+/// };
+///
+/// fn setup(mut commands: Commands, windows: Query<Entity, With<Window>>) {
+///     commands.entity(windows.single()).insert(EguiPassRate::Hz(30.0));
+/// }
+///
+/// fn ui_system(mut contexts: EguiContexts) {
+///     egui::Window::new("Hello").show(contexts.ctx_mut(), |ui| ui.label("world"));
+/// }
+///
+/// # fn build(app: &mut App) {
+/// app.add_systems(Startup, setup)
+///     .add_systems(Update, ui_system.run_if(primary_context_pass_due));
+/// # }
+/// ```
+///
+/// Note that hover effects, tooltips and animations update only at the pass rate, and widgets
+/// respond to clicks with a delay of up to a pass period. Send [`EguiForcePass`] to run a pass
+/// right away (when opening a menu, for example).
+#[derive(Component, Clone, Copy, Debug, Default, PartialEq, Reflect)]
+#[reflect(Component, Default)]
+pub enum EguiPassRate {
+    /// A pass runs every frame.
+    #[default]
+    EveryFrame,
+    /// Passes run at most at the given frequency (in Hz). Non-positive values mean every frame.
+    Hz(f32),
+    /// A pass runs once every N frames. `0` and `1` mean every frame.
+    EveryNFrames(u32),
+}
+
+/// Makes the context of the entity run a pass during the next [`EguiSet::BeginFrame`](crate::EguiSet::BeginFrame), regardless
+/// of its [`EguiPassRate`].
+#[derive(Event, Clone, Copy, Debug, PartialEq, Eq)]
+pub struct EguiForcePass(pub Entity);
+
+/// A run condition that is `true` if the primary context runs a pass during the current frame,
+/// see [`EguiPassRate`].
+pub fn primary_context_pass_due(
+    primary_contexts: PrimaryContextQuery,
+    contexts: Query<&EguiContext>,
+) -> bool {
+    primary_context(&primary_contexts)
+        .and_then(|entity| contexts.get(entity).ok())
+        .is_some_and(EguiContext::is_pass_due)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        test_utils::{input_test_app, CapturedEvents},
+        EguiContexts, EguiRenderOutput,
+    };
+    use bevy::{
+        app::{App, Update},
+        ecs::schedule::IntoSystemConfigs,
+        input::{mouse::MouseButtonInput, ButtonState},
+        math::Vec2,
+        prelude::MouseButton,
+        time::TimeUpdateStrategy,
+        window::CursorMoved,
+    };
+
+    #[test]
+    fn test_pass_rate() {
+        fn passes(app: &mut App, window: Entity, frames: usize) -> Vec<bool> {
+            (0..frames)
+                .map(|_| {
+                    let frame_nr = |app: &mut App| {
+                        app.world
+                            .get_mut::<EguiContext>(window)
+                            .unwrap()
+                            .get_mut()
+                            .frame_nr()
+                    };
+                    let before = frame_nr(app);
+                    app.update();
+                    frame_nr(app) != before
+                })
+                .collect()
+        }
+
+        let (mut app, window) = input_test_app();
+        app.add_systems(
+            Update,
+            (|mut contexts: EguiContexts| {
+                egui::Window::new("Hello").show(contexts.ctx_mut(), |ui| ui.label("world"));
+            })
+            .run_if(primary_context_pass_due),
+        );
+        assert_eq!(passes(&mut app, window, 2), [true, true]);
+
+        app.world
+            .entity_mut(window)
+            .insert(EguiPassRate::EveryNFrames(3));
+        app.world.send_event(CursorMoved {
+            window,
+            position: Vec2::new(10.0, 20.0),
+            delta: None,
+        });
+        assert_eq!(passes(&mut app, window, 1), [false]);
+        // The last output is kept to be rendered again.
+        assert!(!app
+            .world
+            .get::<EguiRenderOutput>(window)
+            .unwrap()
+            .paint_jobs
+            .is_empty());
+        app.world.send_event(MouseButtonInput {
+            button: MouseButton::Left,
+            state: ButtonState::Pressed,
+            window,
+        });
+        assert_eq!(passes(&mut app, window, 1), [false]);
+        // Events are accumulated until the next pass.
+        assert_eq!(app.world.resource::<CapturedEvents>().0.len(), 2);
+        assert_eq!(passes(&mut app, window, 1), [true]);
+        assert_eq!(app.world.resource::<CapturedEvents>().0.len(), 2);
+        assert_eq!(passes(&mut app, window, 3), [false, false, true]);
+        assert!(app.world.resource::<CapturedEvents>().0.is_empty());
+
+        app.insert_resource(TimeUpdateStrategy::ManualDuration(
+            std::time::Duration::from_millis(10),
+        ));
+        app.world.entity_mut(window).insert(EguiPassRate::Hz(30.0));
+        assert_eq!(
+            passes(&mut app, window, 8),
+            [false, false, false, true, false, false, false, true]
+        );
+
+        app.world.send_event(EguiForcePass(window));
+        assert_eq!(passes(&mut app, window, 2), [true, false]);
+    }
+}
@@ -1,9 +1,11 @@
 use crate::{
     egui_node::{EguiNode, EguiPipeline, EguiPipelineKey},
-    EguiManagedTextures, EguiSettings, EguiUserTextures, WindowSize,
+    EguiColorMode, EguiComputedScale, EguiContext, EguiManagedTextures, EguiSettings, EguiTornDown,
+    EguiUserTextures, EguiWindowAlphaMode, WindowSize,
 };
 use bevy::{
     ecs::system::SystemParam,
+    log,
     prelude::*,
     render::{
         extract_resource::ExtractResource,
@@ -11,13 +13,16 @@ use bevy::{
         render_graph::{RenderGraph, RenderLabel},
         render_resource::{
             BindGroup, BindGroupEntry, BindingResource, BufferId, CachedRenderPipelineId,
-            DynamicUniformBuffer, PipelineCache, ShaderType, SpecializedRenderPipelines,
+            DynamicUniformBuffer, PipelineCache, Sampler, SamplerId, SpecializedRenderPipelines,
+            TextureViewId,
         },
         renderer::{RenderDevice, RenderQueue},
+        texture::ImageSamplerDescriptor,
         view::ExtractedWindows,
         Extract,
     },
     utils::HashMap,
+    window::CompositeAlphaMode,
 };
 
 /// Extracted Egui settings.
@@ -36,7 +41,7 @@ impl ExtractResource for ExtractedEguiManagedTextures {
 }
 
 /// Corresponds to Egui's [`egui::TextureId`].
-#[derive(Debug, PartialEq, Eq, Hash)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
 pub enum EguiTextureId {
     /// Textures allocated via Egui.
     Managed(Entity, u64),
@@ -62,6 +67,23 @@ pub struct EguiPass {
     pub window_generation: u32,
 }
 
+impl EguiPass {
+    /// Returns the label of the pass of a window. The index and the generation together identify
+    /// the entity, so a reused entity index gets a different label.
+    pub fn new(window: Entity) -> Self {
+        Self {
+            window_index: window.index(),
+            window_generation: window.generation(),
+        }
+    }
+}
+
+/// Labels of the Egui passes added to the [`RenderGraph`], by window entity.
+///
+/// The passes of despawned windows are removed using the recorded labels.
+#[derive(Resource, Deref, DerefMut, Default)]
+pub struct EguiRenderGraphNodes(pub HashMap<Entity, EguiPass>);
+
 impl ExtractedEguiTextures<'_> {
     /// Returns an iterator over all textures (both Egui and Bevy managed).
     pub fn handles(&self) -> impl Iterator<Item = (EguiTextureId, AssetId<Image>)> + '_ {
@@ -77,28 +99,105 @@ impl ExtractedEguiTextures<'_> {
                     .iter()
                     .map(|(handle, id)| (EguiTextureId::User(*id), handle.id())),
             )
+            .chain(
+                self.user_textures
+                    .sampled_textures
+                    .iter()
+                    .flat_map(|(handle, samplers)| {
+                        samplers
+                            .iter()
+                            .map(|(id, _)| (EguiTextureId::User(*id), handle.id()))
+                    }),
+            )
+            .chain(
+                self.user_textures
+                    .namespaces
+                    .iter()
+                    .flat_map(|namespace| &namespace.textures)
+                    .map(|(handle, id)| (EguiTextureId::User(*id), handle.id())),
+            )
+    }
+
+    /// Returns an iterator over user textures that are sampled with a custom sampler.
+    pub fn sampler_overrides(&self) -> impl Iterator<Item = (u64, &ImageSamplerDescriptor)> + '_ {
+        self.user_textures
+            .sampled_textures
+            .values()
+            .flatten()
+            .map(|(id, sampler)| (*id, sampler))
+    }
+}
+
+/// Sizes and scales of Egui contexts (windows), which are used for rendering.
+///
+/// Unlike components, the values are extracted only when they change.
+#[derive(Resource, Deref, DerefMut, Default)]
+pub struct ExtractedEguiRenderTargets(pub HashMap<Entity, (WindowSize, EguiComputedScale)>);
+
+/// Extracts sizes and scales of Egui contexts that have changed since the last extraction.
+#[allow(clippy::type_complexity)]
+pub fn extract_egui_render_targets_system(
+    mut render_targets: ResMut<ExtractedEguiRenderTargets>,
+    changed: Extract<
+        Query<
+            (Entity, &WindowSize, &EguiComputedScale),
+            Or<(Changed<WindowSize>, Changed<EguiComputedScale>)>,
+        >,
+    >,
+    contexts: Extract<Query<(), With<EguiContext>>>,
+) {
+    render_targets.retain(|&entity, _| contexts.contains(entity));
+    for (entity, window_size, computed_scale) in changed.iter() {
+        render_targets.insert(entity, (*window_size, *computed_scale));
     }
 }
 
 /// Sets up the pipeline for newly created windows.
 pub fn setup_new_windows_render_system(
     windows: Extract<Query<Entity, Added<Window>>>,
+    torn_down: Extract<Option<Res<EguiTornDown>>>,
     mut render_graph: ResMut<RenderGraph>,
+    mut render_graph_nodes: ResMut<EguiRenderGraphNodes>,
 ) {
+    if torn_down.is_some() {
+        return;
+    }
     for window in windows.iter() {
-        let egui_pass = EguiPass {
-            window_index: window.index(),
-            window_generation: window.generation(),
-        };
+        let egui_pass = EguiPass::new(window);
+
+        if render_graph.get_node_state(egui_pass.clone()).is_ok() {
+            log::warn!("Replacing an existing Egui pass ({egui_pass:?})");
+            if let Err(err) = render_graph.remove_node(egui_pass.clone()) {
+                log::warn!("Failed to remove an Egui pass ({egui_pass:?}): {err}");
+            }
+        }
 
         let new_node = EguiNode::new(window);
 
         render_graph.add_node(egui_pass.clone(), new_node);
 
-        render_graph.add_node_edge(bevy::render::graph::CameraDriverLabel, egui_pass);
+        render_graph.add_node_edge(bevy::render::graph::CameraDriverLabel, egui_pass.clone());
+        render_graph_nodes.insert(window, egui_pass);
     }
 }
 
+/// Removes the passes of despawned windows.
+pub fn teardown_despawned_windows_render_system(
+    windows: Extract<Query<(), With<Window>>>,
+    mut render_graph: ResMut<RenderGraph>,
+    mut render_graph_nodes: ResMut<EguiRenderGraphNodes>,
+) {
+    render_graph_nodes.retain(|&window, egui_pass| {
+        if windows.contains(window) {
+            return true;
+        }
+        if let Err(err) = render_graph.remove_node(egui_pass.clone()) {
+            log::warn!("Failed to remove an Egui pass ({egui_pass:?}): {err}");
+        }
+        false
+    });
+}
+
 /// Describes the transform buffer.
 #[derive(Resource, Default)]
 pub struct EguiTransforms {
@@ -110,23 +209,32 @@ pub struct EguiTransforms {
     pub bind_group: Option<(BufferId, BindGroup)>,
 }
 
-/// Scale and translation for rendering Egui shapes. Is needed to transform Egui coordinates from
-/// the screen space with the center at (0, 0) to the normalised viewport space.
-#[derive(ShaderType, Default)]
-pub struct EguiTransform {
-    /// Is affected by window size and [`EguiSettings::scale_factor`].
-    pub scale: Vec2,
-    /// Normally equals `Vec2::new(-1.0, 1.0)`.
-    pub translation: Vec2,
+pub use transform::EguiTransform;
+
+// The `ShaderType` derive generates field checks that newer compilers report as dead code, they're
+// items of their own, so the lint is allowed for the module.
+#[allow(dead_code)]
+mod transform {
+    use bevy::{math::Vec2, render::render_resource::ShaderType};
+
+    /// Scale and translation for rendering Egui shapes. Is needed to transform Egui coordinates
+    /// from the screen space with the center at (0, 0) to the normalised viewport space.
+    #[derive(ShaderType, Default)]
+    pub struct EguiTransform {
+        /// Is affected by window size and [`EguiSettings::scale_factor`](crate::EguiSettings::scale_factor).
+        pub scale: Vec2,
+        /// Normally equals `Vec2::new(-1.0, 1.0)`.
+        pub translation: Vec2,
+    }
 }
 
 impl EguiTransform {
-    /// Calculates the transform from window size and scale factor.
-    pub fn from_window_size(window_size: WindowSize, scale_factor: f32) -> Self {
+    /// Calculates the transform from the logical size of a context.
+    pub fn from_computed_scale(computed_scale: EguiComputedScale) -> Self {
         EguiTransform {
             scale: Vec2::new(
-                2.0 / (window_size.width() / scale_factor),
-                -2.0 / (window_size.height() / scale_factor),
+                2.0 / computed_scale.logical_size.x,
+                -2.0 / computed_scale.logical_size.y,
             ),
             translation: Vec2::new(-1.0, 1.0),
         }
@@ -136,8 +244,7 @@ impl EguiTransform {
 /// Prepares Egui transforms.
 pub fn prepare_egui_transforms_system(
     mut egui_transforms: ResMut<EguiTransforms>,
-    window_sizes: Query<(Entity, &WindowSize)>,
-    egui_settings: Res<EguiSettings>,
+    render_targets: Res<ExtractedEguiRenderTargets>,
 
     render_device: Res<RenderDevice>,
     render_queue: Res<RenderQueue>,
@@ -147,13 +254,10 @@ pub fn prepare_egui_transforms_system(
     egui_transforms.buffer.clear();
     egui_transforms.offsets.clear();
 
-    for (window, size) in window_sizes.iter() {
+    for (&window, (_, computed_scale)) in render_targets.iter() {
         let offset = egui_transforms
             .buffer
-            .push(&EguiTransform::from_window_size(
-                *size,
-                egui_settings.scale_factor,
-            ));
+            .push(&EguiTransform::from_computed_scale(*computed_scale));
         egui_transforms.offsets.insert(window, offset);
     }
 
@@ -180,45 +284,179 @@ pub fn prepare_egui_transforms_system(
 }
 
 /// Maps Egui textures to bind groups.
+///
+/// Bind groups persist across frames, they're recreated only when the GPU image or the sampler of
+/// their texture changes (see [`EguiTextureBindGroupKeys`]).
 #[derive(Resource, Deref, DerefMut, Default)]
 pub struct EguiTextureBindGroups(pub HashMap<EguiTextureId, BindGroup>);
 
-/// Queues bind groups.
+/// Identifies the resources a bind group of [`EguiTextureBindGroups`] was created from.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct EguiTextureBindGroupKey {
+    /// The image asset.
+    pub image: AssetId<Image>,
+    /// The view of the GPU image, which changes when the image gets prepared again.
+    pub texture_view: TextureViewId,
+    /// The sampler, either the one of the image or a custom one.
+    pub sampler: SamplerId,
+}
+
+/// Keys of the bind groups of [`EguiTextureBindGroups`].
+#[derive(Resource, Default)]
+pub struct EguiTextureBindGroupKeys {
+    keys: HashMap<EguiTextureId, EguiTextureBindGroupKey>,
+    created: u64,
+}
+
+impl EguiTextureBindGroupKeys {
+    /// Forgets the textures that aren't in `textures`, and stores the keys of the textures that
+    /// are new or whose resources have changed. Returns the latter, their bind groups need to be
+    /// (re)created.
+    pub fn update(
+        &mut self,
+        textures: impl IntoIterator<Item = (EguiTextureId, EguiTextureBindGroupKey)>,
+    ) -> Vec<EguiTextureId> {
+        let textures: HashMap<_, _> = textures.into_iter().collect();
+        self.keys
+            .retain(|texture, _| textures.contains_key(texture));
+        let mut outdated = Vec::new();
+        for (texture, key) in textures {
+            if self.keys.insert(texture, key) != Some(key) {
+                outdated.push(texture);
+            }
+        }
+        self.created += outdated.len() as u64;
+        outdated
+    }
+
+    /// Returns `true` if a bind group is stored for the texture.
+    pub fn contains(&self, texture: &EguiTextureId) -> bool {
+        self.keys.contains_key(texture)
+    }
+
+    /// Number of bind groups created since startup.
+    pub fn created(&self) -> u64 {
+        self.created
+    }
+}
+
+/// Caches samplers created for user textures with custom samplers.
+#[derive(Resource, Default)]
+pub struct EguiUserTextureSamplers(pub Vec<(ImageSamplerDescriptor, Sampler)>);
+
+impl EguiUserTextureSamplers {
+    fn get_or_create(
+        &mut self,
+        descriptor: &ImageSamplerDescriptor,
+        render_device: &RenderDevice,
+    ) -> Sampler {
+        if let Some((_, sampler)) = self
+            .0
+            .iter()
+            .find(|(cached, _)| cached.as_wgpu() == descriptor.as_wgpu())
+        {
+            return sampler.clone();
+        }
+        let sampler = render_device.create_sampler(&descriptor.as_wgpu());
+        self.0.push((descriptor.clone(), sampler.clone()));
+        sampler
+    }
+}
+
+/// Queues bind groups of the textures that are new or whose resources have changed.
 pub fn queue_bind_groups_system(
-    mut commands: Commands,
+    mut bind_groups: ResMut<EguiTextureBindGroups>,
+    mut bind_group_keys: ResMut<EguiTextureBindGroupKeys>,
     egui_textures: ExtractedEguiTextures,
     render_device: Res<RenderDevice>,
     gpu_images: Res<RenderAssets<Image>>,
     egui_pipeline: Res<EguiPipeline>,
+    mut user_texture_samplers: ResMut<EguiUserTextureSamplers>,
 ) {
-    let bind_groups = egui_textures
+    let sampler_overrides: HashMap<u64, Sampler> = egui_textures
+        .sampler_overrides()
+        .map(|(id, descriptor)| {
+            (
+                id,
+                user_texture_samplers.get_or_create(descriptor, &render_device),
+            )
+        })
+        .collect();
+    // Drop the samplers that aren't used by any of the registered textures anymore.
+    user_texture_samplers.0.retain(|(cached, _)| {
+        egui_textures
+            .sampler_overrides()
+            .any(|(_, descriptor)| descriptor.as_wgpu() == cached.as_wgpu())
+    });
+
+    let mut gpu_textures = HashMap::default();
+    let keys = egui_textures
         .handles()
         .filter_map(|(texture, handle_id)| {
-            let gpu_image = gpu_images.get(&Handle::Weak(handle_id))?;
-            let bind_group = render_device.create_bind_group(
-                None,
-                &egui_pipeline.texture_bind_group_layout,
-                &[
-                    BindGroupEntry {
-                        binding: 0,
-                        resource: BindingResource::TextureView(&gpu_image.texture_view),
-                    },
-                    BindGroupEntry {
-                        binding: 1,
-                        resource: BindingResource::Sampler(&gpu_image.sampler),
-                    },
-                ],
-            );
-            Some((texture, bind_group))
+            let gpu_image = gpu_images.get(Handle::Weak(handle_id))?;
+            let sampler = match texture {
+                EguiTextureId::User(id) => sampler_overrides.get(&id),
+                EguiTextureId::Managed(..) => None,
+            }
+            .unwrap_or(&gpu_image.sampler);
+            let key = EguiTextureBindGroupKey {
+                image: handle_id,
+                texture_view: gpu_image.texture_view.id(),
+                sampler: sampler.id(),
+            };
+            gpu_textures.insert(texture, (gpu_image, sampler));
+            Some((texture, key))
         })
-        .collect();
+        .collect::<Vec<_>>();
 
-    commands.insert_resource(EguiTextureBindGroups(bind_groups))
+    // Textures disappear when they're removed, or while their images aren't loaded (again).
+    let outdated = bind_group_keys.update(keys);
+    bind_groups.retain(|texture, _| bind_group_keys.contains(texture));
+    for texture in outdated {
+        let (gpu_image, sampler) = gpu_textures[&texture];
+        let bind_group = render_device.create_bind_group(
+            None,
+            &egui_pipeline.texture_bind_group_layout,
+            &[
+                BindGroupEntry {
+                    binding: 0,
+                    resource: BindingResource::TextureView(&gpu_image.texture_view),
+                },
+                BindGroupEntry {
+                    binding: 1,
+                    resource: BindingResource::Sampler(sampler),
+                },
+            ],
+        );
+        bind_groups.insert(texture, bind_group);
+    }
 }
 
 /// Cached Pipeline IDs for the specialized `EguiPipeline`s
 #[derive(Resource)]
-pub struct EguiPipelines(pub HashMap<Entity, CachedRenderPipelineId>);
+pub struct EguiPipelines(pub HashMap<Entity, EguiWindowPipelines>);
+
+/// The pipelines a window is rendered with.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct EguiWindowPipelines {
+    /// Draws the meshes with textures allocated via Bevy.
+    pub user_textures: CachedRenderPipelineId,
+    /// Draws the meshes with textures allocated via Egui, it's the same pipeline as
+    /// [`EguiWindowPipelines::user_textures`] in [`EguiColorMode::Standard`].
+    pub managed_textures: CachedRenderPipelineId,
+}
+
+/// Returns whether Egui writes the alpha channel of a window.
+pub fn writes_alpha(
+    alpha_mode: EguiWindowAlphaMode,
+    window_alpha_mode: CompositeAlphaMode,
+) -> bool {
+    match alpha_mode {
+        EguiWindowAlphaMode::Auto => window_alpha_mode != CompositeAlphaMode::Opaque,
+        EguiWindowAlphaMode::Opaque => false,
+        EguiWindowAlphaMode::PreMultiplied => true,
+    }
+}
 
 /// Queue [`EguiPipeline`]s specialized on each window's swap chain texture format.
 pub fn queue_pipelines_system(
@@ -227,16 +465,46 @@ pub fn queue_pipelines_system(
     mut pipelines: ResMut<SpecializedRenderPipelines<EguiPipeline>>,
     egui_pipeline: Res<EguiPipeline>,
     windows: Res<ExtractedWindows>,
+    modes: Query<(Option<&EguiColorMode>, Option<&EguiWindowAlphaMode>)>,
 ) {
     let pipelines = windows
         .iter()
         .filter_map(|(window_id, window)| {
+            let (color_mode, alpha_mode) = modes.get(*window_id).map_or_else(
+                |_| Default::default(),
+                |(color_mode, alpha_mode)| {
+                    (
+                        color_mode.copied().unwrap_or_default(),
+                        alpha_mode.copied().unwrap_or_default(),
+                    )
+                },
+            );
             let key = EguiPipelineKey {
                 texture_format: window.swap_chain_texture_format?.add_srgb_suffix(),
+                color_mode,
+                gamma_premultiplied_textures: false,
+                write_alpha: writes_alpha(alpha_mode, window.alpha_mode),
+            };
+            let user_textures = pipelines.specialize(&pipeline_cache, &egui_pipeline, key);
+            let managed_textures = match color_mode {
+                EguiColorMode::Standard => user_textures,
+                EguiColorMode::Accurate => pipelines.specialize(
+                    &pipeline_cache,
+                    &egui_pipeline,
+                    EguiPipelineKey {
+                        gamma_premultiplied_textures: true,
+                        ..key
+                    },
+                ),
             };
-            let pipeline_id = pipelines.specialize(&pipeline_cache, &egui_pipeline, key);
 
-            Some((*window_id, pipeline_id))
+            Some((
+                *window_id,
+                EguiWindowPipelines {
+                    user_textures,
+                    managed_textures,
+                },
+            ))
         })
         .collect();
 
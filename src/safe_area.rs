@@ -0,0 +1,84 @@
+//! Insets of the window area that Egui contexts keep clear, for notches and system bars.
+
+use bevy::{
+    ecs::{component::Component, reflect::ReflectComponent},
+    reflect::{std_traits::ReflectDefault, Reflect},
+};
+
+/// Insets (in Egui points) of the window area that is obscured by notches, rounded corners
+/// or system bars.
+///
+/// Egui lays out panels and windows within the [`egui::Context::screen_rect`], which gets shrunk by
+/// the insets during [`EguiSet::InitContexts`](crate::EguiSet::InitContexts). Egui coordinates still match the window ones, so
+/// pointer positions don't need to be offset, and the area under the insets is left for the
+/// app's background.
+///
+/// Bevy doesn't expose the safe area of mobile devices yet, so the insets need to be set by the app
+/// (for example, from platform APIs). Setting them manually also allows simulating a notch on
+/// desktop (see [./examples/safe_area.rs](https://github.com/mvlabat/bevy_egui/blob/main/examples/safe_area.rs)).
+#[derive(Component, Debug, Default, Clone, Copy, PartialEq, Reflect)]
+#[reflect(Component, Default)]
+pub struct EguiSafeArea {
+    /// Left inset.
+    pub left: f32,
+    /// Right inset.
+    pub right: f32,
+    /// Top inset.
+    pub top: f32,
+    /// Bottom inset.
+    pub bottom: f32,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{test_utils::headless_app, EguiContext};
+    use bevy::{
+        app::App,
+        ecs::{entity::Entity, query::With},
+        window::{PrimaryWindow, Window, WindowResolution},
+    };
+
+    #[test]
+    fn test_safe_area() {
+        let mut app = headless_app();
+        app.update();
+        let window = app
+            .world
+            .query_filtered::<Entity, With<PrimaryWindow>>()
+            .single(&app.world);
+        app.world.get_mut::<Window>(window).unwrap().resolution =
+            WindowResolution::new(400.0, 800.0).with_scale_factor_override(1.0);
+        *app.world.get_mut::<EguiSafeArea>(window).unwrap() = EguiSafeArea {
+            top: 40.0,
+            bottom: 20.0,
+            ..Default::default()
+        };
+        app.update();
+        let screen_rect = |app: &mut App| {
+            app.world
+                .get_mut::<EguiContext>(window)
+                .unwrap()
+                .get_mut()
+                .screen_rect()
+        };
+        assert_eq!(
+            screen_rect(&mut app),
+            egui::Rect::from_min_max(egui::pos2(0.0, 40.0), egui::pos2(400.0, 780.0))
+        );
+
+        // Rotating the device updates the rect in the same frame.
+        app.world.get_mut::<Window>(window).unwrap().resolution =
+            WindowResolution::new(800.0, 400.0).with_scale_factor_override(1.0);
+        *app.world.get_mut::<EguiSafeArea>(window).unwrap() = EguiSafeArea {
+            left: 40.0,
+            bottom: 20.0,
+            ..Default::default()
+        };
+        app.update();
+        assert_eq!(
+            screen_rect(&mut app),
+            egui::Rect::from_min_max(egui::pos2(40.0, 0.0), egui::pos2(800.0, 380.0))
+        );
+    }
+}
@@ -0,0 +1,198 @@
+//! Snapshot testing of the Egui output, which doesn't require a GPU.
+//!
+//! Egui output is deterministic given the same input, screen size and fonts, so instead of
+//! comparing rendered images, a test can compare a normalized summary of
+//! [`EguiRenderOutput::paint_jobs`]. Every primitive is described by its clip rect, texture id,
+//! vertex and index counts, and the bounding rect of its vertices (text is tessellated into meshes
+//! that sample the font texture, so text changes show up as changes of these values).
+//!
+//! ```no_run
+//! # use bevy::prelude::*;
+//! # use bevy_egui::{assert_egui_snapshot, EguiRenderOutput};
+//! fn check_ui(world: &mut World, window: Entity) {
+//!     let render_output = world.get::<EguiRenderOutput>(window).unwrap();
+//!     assert_egui_snapshot!(render_output, "main_menu");
+//! }
+//! ```
+//!
+//! Snapshots are stored in the `snapshots` directory of the crate being tested. A missing
+//! snapshot is written on the first run, set the `BEVY_EGUI_UPDATE_SNAPSHOTS` environment variable
+//! to overwrite the existing ones. Coordinates are compared with the [`SNAPSHOT_TOLERANCE`], so
+//! minor floating point differences between platforms don't fail the tests.
+//!
+//! For the output to be reproducible, run the app with a fixed window resolution and scale factor,
+//! and with the default fonts (the `default_fonts` feature).
+
+use crate::EguiRenderOutput;
+use std::{fmt::Write, path::PathBuf};
+
+/// Maximum difference (in points) between the coordinates of an expected and an actual snapshot.
+pub const SNAPSHOT_TOLERANCE: f32 = 0.5;
+
+/// Describes the paint jobs of a render output, one primitive per line.
+pub fn render_output_snapshot(render_output: &EguiRenderOutput) -> String {
+    let mut snapshot = String::new();
+    for egui::ClippedPrimitive {
+        clip_rect,
+        primitive,
+    } in &render_output.paint_jobs
+    {
+        match primitive {
+            egui::epaint::Primitive::Mesh(mesh) => {
+                let bounds = mesh.calc_bounds();
+                writeln!(
+                    snapshot,
+                    "mesh clip {} texture {:?} vertices {} indices {} bounds {}",
+                    format_rect(*clip_rect),
+                    mesh.texture_id,
+                    mesh.vertices.len(),
+                    mesh.indices.len(),
+                    format_rect(bounds),
+                )
+            }
+            egui::epaint::Primitive::Callback(_) => {
+                writeln!(snapshot, "callback clip {}", format_rect(*clip_rect))
+            }
+        }
+        .unwrap();
+    }
+    snapshot
+}
+
+fn format_rect(rect: egui::Rect) -> String {
+    format!(
+        "{:.1} {:.1} {:.1} {:.1}",
+        rect.min.x, rect.min.y, rect.max.x, rect.max.y
+    )
+}
+
+/// Returns `true` if the snapshots differ only by numbers that are within the `tolerance`.
+pub fn snapshots_match(expected: &str, actual: &str, tolerance: f32) -> bool {
+    let expected_lines: Vec<_> = expected.lines().collect();
+    let actual_lines: Vec<_> = actual.lines().collect();
+    expected_lines.len() == actual_lines.len()
+        && expected_lines
+            .iter()
+            .zip(&actual_lines)
+            .all(|(expected, actual)| lines_match(expected, actual, tolerance))
+}
+
+fn lines_match(expected: &str, actual: &str, tolerance: f32) -> bool {
+    let expected_tokens: Vec<_> = expected.split_whitespace().collect();
+    let actual_tokens: Vec<_> = actual.split_whitespace().collect();
+    expected_tokens.len() == actual_tokens.len()
+        && expected_tokens
+            .iter()
+            .zip(&actual_tokens)
+            .all(
+                |(expected, actual)| match (expected.parse::<f32>(), actual.parse::<f32>()) {
+                    (Ok(expected), Ok(actual)) => (expected - actual).abs() <= tolerance,
+                    _ => expected == actual,
+                },
+            )
+}
+
+/// Compares the snapshot with the one stored under the `name`, or stores it if it doesn't exist
+/// (or the `BEVY_EGUI_UPDATE_SNAPSHOTS` environment variable is set).
+///
+/// On a mismatch, the actual snapshot is written next to the expected one with the `.snap.new`
+/// extension.
+///
+/// # Panics
+///
+/// Panics if the snapshots don't match or the snapshot file can't be accessed.
+#[track_caller]
+pub fn assert_snapshot(name: &str, snapshot: &str) {
+    let dir =
+        PathBuf::from(std::env::var("CARGO_MANIFEST_DIR").unwrap_or_default()).join("snapshots");
+    let path = dir.join(format!("{name}.snap"));
+
+    if std::env::var_os("BEVY_EGUI_UPDATE_SNAPSHOTS").is_some() || !path.exists() {
+        std::fs::create_dir_all(&dir).expect("failed to create the snapshots directory");
+        std::fs::write(&path, snapshot).expect("failed to write a snapshot");
+        return;
+    }
+
+    let expected = std::fs::read_to_string(&path).expect("failed to read a snapshot");
+    if !snapshots_match(&expected, snapshot, SNAPSHOT_TOLERANCE) {
+        let new_path = path.with_extension("snap.new");
+        std::fs::write(&new_path, snapshot).expect("failed to write a snapshot");
+        panic!(
+            "Egui snapshot `{name}` doesn't match (the actual one is written to {})\n\
+             expected:\n{expected}\nactual:\n{snapshot}",
+            new_path.display()
+        );
+    }
+}
+
+/// Asserts that an [`EguiRenderOutput`] matches the stored snapshot with the given name.
+///
+/// See the [`snapshot`](crate::snapshot) module for details.
+#[macro_export]
+macro_rules! assert_egui_snapshot {
+    ($render_output:expr, $name:expr) => {
+        $crate::snapshot::assert_snapshot(
+            $name,
+            &$crate::snapshot::render_output_snapshot(&$render_output),
+        )
+    };
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{test_utils::headless_app, EguiContexts};
+    use bevy::{
+        app::Update,
+        ecs::{entity::Entity, query::With},
+        window::{PrimaryWindow, Window, WindowResolution},
+    };
+
+    #[cfg(feature = "default_fonts")]
+    #[test]
+    fn test_simple_window_snapshot() {
+        fn simple_window_system(mut contexts: EguiContexts) {
+            egui::Window::new("Hello").show(contexts.ctx_mut(), |ui| {
+                ui.label("world");
+            });
+        }
+
+        let mut app = headless_app();
+        app.add_systems(Update, simple_window_system);
+        app.update();
+        let window = app
+            .world
+            .query_filtered::<Entity, With<PrimaryWindow>>()
+            .single(&app.world);
+        app.world.get_mut::<Window>(window).unwrap().resolution =
+            WindowResolution::new(800.0, 600.0).with_scale_factor_override(1.0);
+        // Windows are sized to their contents during the first frames.
+        for _ in 0..3 {
+            app.update();
+        }
+
+        let render_output = app.world.get::<EguiRenderOutput>(window).unwrap();
+        assert!(!render_output.paint_jobs.is_empty());
+        assert_egui_snapshot!(render_output, "simple_window");
+    }
+
+    #[test]
+    fn test_snapshots_match() {
+        let expected = "mesh clip 0.0 0.0 800.0 600.0 texture Managed(0) vertices 4 indices 6";
+        assert!(snapshots_match(
+            expected,
+            "mesh clip 0.0 0.3 800.0 599.6 texture Managed(0) vertices 4 indices 6",
+            SNAPSHOT_TOLERANCE,
+        ));
+        assert!(!snapshots_match(
+            expected,
+            "mesh clip 0.0 0.0 800.0 600.0 texture Managed(0) vertices 5 indices 6",
+            SNAPSHOT_TOLERANCE,
+        ));
+        assert!(!snapshots_match(
+            expected,
+            &format!("{expected}\n{expected}"),
+            SNAPSHOT_TOLERANCE,
+        ));
+    }
+}
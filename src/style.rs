@@ -0,0 +1,190 @@
+//! Style values that game systems can change (and animate) at any time during a frame, without
+//! touching the systems that build the UI.
+
+use crate::EguiContext;
+use bevy::ecs::{component::Component, system::Query};
+
+/// Overrides parts of the style of a context, insert it on the window entity.
+///
+/// The overrides are applied before every pass (before [`EguiSet::BeginFrame`](crate::EguiSet::BeginFrame),
+/// and after themes get applied), so changes made by systems at any point of a frame take effect
+/// on the next pass, the same way for all contexts.
+/// The style is patched only if it doesn't have the overridden values already. Fields set to
+/// [`None`] leave the style as is (setting a field back to [`None`] doesn't restore the previous
+/// value).
+///
+/// ```rust
+/// use bevy::prelude::*;
+/// use bevy_egui::style::EguiStyleOverrides;
+///
+/// /// Pulses the accent color while the player is hurt.
+/// fn damage_pulse_system(time: Res<Time>, mut overrides: Query<&mut EguiStyleOverrides>) {
+///     let target = EguiStyleOverrides {
+///         selection_color: Some(egui::Color32::RED),
+///         ..default()
+///     };
+///     for mut overrides in overrides.iter_mut() {
+///         overrides.lerp_toward(&target, (time.delta_seconds() * 4.0).min(1.0));
+///     }
+/// }
+/// ```
+#[derive(Component, Clone, Copy, Debug, Default, PartialEq)]
+pub struct EguiStyleOverrides {
+    /// Background color of selected text and widgets (the accent color).
+    pub selection_color: Option<egui::Color32>,
+    /// Background color of windows.
+    pub window_fill: Option<egui::Color32>,
+    /// Color of all the text.
+    pub text_color: Option<egui::Color32>,
+    /// Corner radius of widgets.
+    pub widget_rounding: Option<f32>,
+}
+
+impl EguiStyleOverrides {
+    /// Moves the values a fraction `t` (from `0.0` to `1.0`) of the way toward the values of
+    /// `target`. Colors are interpolated in linear space. Fields that aren't set yet jump to the
+    /// target, fields that aren't set in `target` are left as is.
+    pub fn lerp_toward(&mut self, target: &Self, t: f32) {
+        fn lerp_color(from: egui::Color32, to: egui::Color32, t: f32) -> egui::Color32 {
+            (egui::Rgba::from(from) * (1.0 - t) + egui::Rgba::from(to) * t).into()
+        }
+        fn lerp<T: Copy>(from: &mut Option<T>, to: Option<T>, t: f32, lerp: fn(T, T, f32) -> T) {
+            if let Some(to) = to {
+                *from = Some(from.map_or(to, |from| lerp(from, to, t)));
+            }
+        }
+        let t = t.clamp(0.0, 1.0);
+        lerp(
+            &mut self.selection_color,
+            target.selection_color,
+            t,
+            lerp_color,
+        );
+        lerp(&mut self.window_fill, target.window_fill, t, lerp_color);
+        lerp(&mut self.text_color, target.text_color, t, lerp_color);
+        lerp(
+            &mut self.widget_rounding,
+            target.widget_rounding,
+            t,
+            |from, to, t| from + (to - from) * t,
+        );
+    }
+
+    /// Returns `true` if the style has all the overridden values.
+    #[must_use]
+    pub fn is_applied(&self, style: &egui::Style) -> bool {
+        fn matches<T: PartialEq>(value: Option<T>, actual: T) -> bool {
+            value.is_none() || value == Some(actual)
+        }
+        let visuals = &style.visuals;
+        let widgets = &visuals.widgets;
+        matches(self.selection_color, visuals.selection.bg_fill)
+            && matches(self.window_fill, visuals.window_fill)
+            && (self.text_color.is_none() || self.text_color == visuals.override_text_color)
+            && [
+                &widgets.noninteractive,
+                &widgets.inactive,
+                &widgets.hovered,
+                &widgets.active,
+                &widgets.open,
+            ]
+            .iter()
+            .all(|widget_visuals| {
+                matches(
+                    self.widget_rounding.map(egui::Rounding::from),
+                    widget_visuals.rounding,
+                )
+            })
+    }
+
+    /// Sets the overridden values in the style.
+    pub fn apply(&self, style: &mut egui::Style) {
+        let visuals = &mut style.visuals;
+        if let Some(color) = self.selection_color {
+            visuals.selection.bg_fill = color;
+        }
+        if let Some(color) = self.window_fill {
+            visuals.window_fill = color;
+        }
+        if let Some(color) = self.text_color {
+            visuals.override_text_color = Some(color);
+        }
+        if let Some(rounding) = self.widget_rounding {
+            let widgets = &mut visuals.widgets;
+            for widget_visuals in [
+                &mut widgets.noninteractive,
+                &mut widgets.inactive,
+                &mut widgets.hovered,
+                &mut widgets.active,
+                &mut widgets.open,
+            ] {
+                widget_visuals.rounding = rounding.into();
+            }
+        }
+    }
+}
+
+/// Applies [`EguiStyleOverrides`] to the contexts whose style doesn't have them.
+pub fn apply_style_overrides_system(mut contexts: Query<(&EguiStyleOverrides, &mut EguiContext)>) {
+    for (overrides, mut ctx) in contexts.iter_mut() {
+        if overrides.is_applied(&ctx.ctx.style()) {
+            continue;
+        }
+        ctx.get_mut().style_mut(|style| overrides.apply(style));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{test_utils::input_test_app, EguiContexts, EguiRenderOutput};
+    use bevy::app::{App, Update};
+
+    #[test]
+    fn test_style_overrides() {
+        let (mut app, window) = input_test_app();
+        app.add_systems(Update, |mut contexts: EguiContexts| {
+            egui::Window::new("Hello").show(contexts.ctx_mut(), |ui| ui.label("world"));
+        });
+        let fill = egui::Color32::from_rgb(200, 40, 90);
+        app.world.entity_mut(window).insert(EguiStyleOverrides {
+            window_fill: Some(fill),
+            ..Default::default()
+        });
+        app.update();
+        app.update();
+        let has_fill = |app: &App| {
+            app.world
+                .get::<EguiRenderOutput>(window)
+                .unwrap()
+                .paint_jobs
+                .iter()
+                .any(|primitive| match &primitive.primitive {
+                    egui::epaint::Primitive::Mesh(mesh) => {
+                        mesh.vertices.iter().any(|vertex| vertex.color == fill)
+                    }
+                    egui::epaint::Primitive::Callback(_) => false,
+                })
+        };
+        assert!(has_fill(&app));
+
+        // Interpolation moves toward the target, reaching it at `t == 1.0`.
+        let target = EguiStyleOverrides {
+            window_fill: Some(egui::Color32::BLACK),
+            widget_rounding: Some(8.0),
+            ..Default::default()
+        };
+        let mut overrides = *app.world.get::<EguiStyleOverrides>(window).unwrap();
+        overrides.lerp_toward(&target, 0.5);
+        assert_eq!(overrides.widget_rounding, Some(8.0));
+        let half = overrides.window_fill.unwrap();
+        assert!(half.r() < fill.r() && half.r() > 0, "{half:?}");
+        overrides.lerp_toward(&target, 1.0);
+        assert_eq!(overrides, target);
+        app.world.entity_mut(window).insert(overrides);
+        app.update();
+        assert!(!has_fill(&app));
+        let ctx = app.world.get::<EguiContext>(window).unwrap().ctx.clone();
+        assert!(target.is_applied(&ctx.style()));
+    }
+}
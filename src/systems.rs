@@ -1,513 +1,558 @@
 use crate::{
-    EguiContext, EguiContextQuery, EguiContextQueryItem, EguiInput, EguiSettings, WindowSize,
+    input::{TextInputFocus, TextInputOwner},
+    open_url::{EguiOpenUrlBlocked, EguiOpenUrlPolicy, EguiOpenUrlRequested, EguiUrlDecision},
+    pass_rate::{EguiForcePass, EguiPassRate},
+    EguiAnyOutputChanged, EguiComputedScale, EguiContext, EguiContextInitialized, EguiContextKind,
+    EguiContextPoisoned, EguiContextPoisonedEvent, EguiContextQuery, EguiContextReady,
+    EguiContexts, EguiCursorMap, EguiExtraPaintJobsPlacement, EguiInput, EguiPrewarm,
+    EguiPrimaryContext, EguiRenderLimits, EguiRenderOutput, EguiRenderTargetResized, EguiSettings,
+    EguiTextCursorInfo, EguiUiSchedule, EguiWantsInput, EguiWindowImeMode, WindowSize,
 };
 use bevy::{
     ecs::{
-        event::EventWriter,
-        query::QueryEntityError,
-        system::{Local, Res, SystemParam},
-    },
-    input::{
-        keyboard::{Key, KeyCode, KeyboardInput},
-        mouse::{MouseButton, MouseButtonInput, MouseScrollUnit, MouseWheel},
-        touch::TouchInput,
-        ButtonState,
+        change_detection::DetectChangesMut,
+        event::{EventReader, EventWriter, Events},
+        schedule::Schedules,
+        system::{Local, Res, ResMut, SystemParam},
+        world::World,
     },
     log,
-    prelude::{Entity, EventReader, Query, Resource, Time},
+    math::Vec2,
+    prelude::{Added, Commands, Entity, Has, Query, Time, With, Without},
     time::Real,
-    window::{CursorMoved, ReceivedCharacter, RequestRedraw},
+    window::{PrimaryWindow, RequestRedraw},
 };
-use std::marker::PhantomData;
+use std::time::Duration;
 
-#[allow(missing_docs)]
-#[derive(SystemParam)]
-// IMPORTANT: remember to add the logic to clear event readers to the `clear` method.
-pub struct InputEvents<'w, 's> {
-    pub ev_cursor: EventReader<'w, 's, CursorMoved>,
-    pub ev_mouse_button_input: EventReader<'w, 's, MouseButtonInput>,
-    pub ev_mouse_wheel: EventReader<'w, 's, MouseWheel>,
-    pub ev_received_character: EventReader<'w, 's, ReceivedCharacter>,
-    pub ev_keyboard_input: EventReader<'w, 's, KeyboardInput>,
-    pub ev_touch: EventReader<'w, 's, TouchInput>,
-}
+pub use crate::input::{
+    bevy_to_egui_key, bevy_to_egui_physical_key, process_input_system, ContextSystemParams,
+    InputEvents, InputResources, ModifierKeysState, PendingInputEvents,
+};
 
-impl<'w, 's> InputEvents<'w, 's> {
-    /// Consumes all the events.
-    pub fn clear(&mut self) {
-        self.ev_cursor.read().last();
-        self.ev_mouse_button_input.read().last();
-        self.ev_mouse_wheel.read().last();
-        self.ev_received_character.read().last();
-        self.ev_keyboard_input.read().last();
-        self.ev_touch.read().last();
-    }
-}
+/// Initialises Egui contexts (for multiple windows).
+pub fn update_window_contexts_system(
+    mut context_params: ContextSystemParams,
+    egui_settings: Res<EguiSettings>,
+    render_limits: Option<Res<EguiRenderLimits>>,
+    mut resized_events: EventWriter<EguiRenderTargetResized>,
+    #[cfg(feature = "render")] texture_budgets: Query<
+        &crate::texture_budget::EguiManagedTextureBudget,
+    >,
+) {
+    for mut context in context_params.contexts.iter_mut() {
+        #[allow(unused_mut)]
+        let mut max_texture_side = render_limits
+            .as_ref()
+            .map(|render_limits| render_limits.max_texture_side);
+        #[cfg(feature = "render")]
+        if let Some(budget_side) = texture_budgets
+            .get(context.window_entity)
+            .ok()
+            .and_then(|budget| budget.max_texture_side())
+        {
+            max_texture_side =
+                Some(max_texture_side.map_or(budget_side, |side| side.min(budget_side)));
+        }
+        if max_texture_side.is_some() {
+            context.egui_input.max_texture_side = max_texture_side;
+        }
 
-/// Stores "pressed" state of modifier keys.
-/// Will be removed if Bevy adds support for `ButtonInput<Key>` (logical keys).
-#[derive(Resource, Default, Clone, Copy, Debug)]
-pub struct ModifierKeysState {
-    shift: bool,
-    ctrl: bool,
-    alt: bool,
-    win: bool,
-}
+        let new_window_size = context.canvas_size.map_or_else(
+            || {
+                WindowSize::new(
+                    context.window.physical_width() as f32,
+                    context.window.physical_height() as f32,
+                    context.window.scale_factor(),
+                )
+            },
+            |canvas_size| canvas_size.0,
+        );
+        let dpi_scale_factor = context.dpi_scale_factor();
+        let width = new_window_size.physical_width / dpi_scale_factor / egui_settings.scale_factor;
+        let height =
+            new_window_size.physical_height / dpi_scale_factor / egui_settings.scale_factor;
 
-#[allow(missing_docs)]
-#[derive(SystemParam)]
-pub struct InputResources<'w, 's> {
-    #[cfg(all(
-        feature = "manage_clipboard",
-        not(target_os = "android"),
-        not(all(target_arch = "wasm32", not(web_sys_unstable_apis)))
-    ))]
-    pub egui_clipboard: bevy::ecs::system::ResMut<'w, crate::EguiClipboard>,
-    pub modifier_keys_state: Local<'s, ModifierKeysState>,
-    #[system_param(ignore)]
-    _marker: PhantomData<&'w ()>,
-}
+        if width < 1.0 || height < 1.0 {
+            continue;
+        }
 
-#[allow(missing_docs)]
-#[derive(SystemParam)]
-pub struct ContextSystemParams<'w, 's> {
-    pub contexts: Query<'w, 's, EguiContextQuery>,
-    pub is_macos: Local<'s, bool>,
-    #[system_param(ignore)]
-    _marker: PhantomData<&'s ()>,
-}
+        let safe_area = context.safe_area;
+        let min = egui::pos2(safe_area.left, safe_area.top);
+        let max = egui::pos2(width - safe_area.right, height - safe_area.bottom);
+        context.egui_input.screen_rect = Some(egui::Rect::from_min_max(min, max.max(min)));
 
-impl<'w, 's> ContextSystemParams<'w, 's> {
-    fn window_context(&mut self, window: Entity) -> Option<EguiContextQueryItem> {
-        match self.contexts.get_mut(window) {
-            Ok(context) => Some(context),
-            Err(err @ QueryEntityError::AliasedMutability(_)) => {
-                panic!("Failed to get an Egui context for a window ({window:?}): {err:?}");
-            }
-            Err(
-                err @ QueryEntityError::NoSuchEntity(_)
-                | err @ QueryEntityError::QueryDoesNotMatch(_),
-            ) => {
-                log::error!("Failed to get an Egui context for a window ({window:?}): {err:?}",);
-                None
+        let pixels_per_point = dpi_scale_factor * egui_settings.scale_factor;
+        // Feeding the scale factor as the native one (rather than changing the zoom factor) makes
+        // Egui use the new screen rect right away when the scale factor changes.
+        let viewport_id = context.egui_input.viewport_id;
+        context
+            .egui_input
+            .viewports
+            .entry(viewport_id)
+            .or_default()
+            .native_pixels_per_point = Some(dpi_scale_factor);
+        // Egui regenerates the fonts when the pixels per point change, setting the same zoom
+        // factor again is a no-op, but it locks the context for writing.
+        let ctx = context.ctx.get_mut();
+        if ctx.zoom_factor() != egui_settings.scale_factor {
+            ctx.set_zoom_factor(egui_settings.scale_factor);
+        }
+
+        // The cached pointer position is in the points of the old scale, update it right away
+        // instead of waiting for the next cursor event, so that clicks land where the cursor is.
+        let old_pixels_per_point = context.computed_scale.pixels_per_point;
+        if old_pixels_per_point > 0.0 && old_pixels_per_point != pixels_per_point {
+            if let Some(cursor_position) = context.window.cursor_position() {
+                let mouse_position = context.logical_to_egui_pos(cursor_position, &egui_settings);
+                context.ctx.mouse_position = mouse_position;
+                context
+                    .egui_input
+                    .events
+                    .push(egui::Event::PointerMoved(mouse_position));
+            } else {
+                let ratio = old_pixels_per_point / pixels_per_point;
+                context.ctx.mouse_position =
+                    (context.ctx.mouse_position.to_vec2() * ratio).to_pos2();
             }
         }
+
+        if *context.window_size != new_window_size {
+            resized_events.send(EguiRenderTargetResized {
+                entity: context.window_entity,
+                old: *context.window_size,
+                new: new_window_size,
+            });
+        }
+        // Avoid triggering change detection, so that the values get extracted only when they change.
+        context.window_size.set_if_neq(new_window_size);
+        context.computed_scale.set_if_neq(EguiComputedScale {
+            pixels_per_point,
+            logical_size: Vec2::new(width, height),
+        });
     }
 }
 
-/// Processes Bevy input and feeds it to Egui.
-pub fn process_input_system(
-    mut input_events: InputEvents,
-    mut input_resources: InputResources,
-    mut context_params: ContextSystemParams,
-    egui_settings: Res<EguiSettings>,
-    time: Res<Time<Real>>,
+/// Sends [`EguiContextInitialized`] and inserts [`EguiContextReady`] for contexts that got a valid
+/// screen rect for the first time.
+pub fn notify_initialized_contexts_system(
+    mut commands: Commands,
+    contexts: Query<(Entity, &EguiInput, Has<PrimaryWindow>), Without<EguiContextReady>>,
+    mut initialized_events: EventWriter<EguiContextInitialized>,
 ) {
-    // Test whether it's macOS or OS X.
-    use std::sync::Once;
-    static START: Once = Once::new();
-    START.call_once(|| {
-        // The default for WASM is `false` since the `target_os` is `unknown`.
-        *context_params.is_macos = cfg!(target_os = "macos");
-
-        #[cfg(target_arch = "wasm32")]
-        if let Some(window) = web_sys::window() {
-            let nav = window.navigator();
-            if let Ok(user_agent) = nav.user_agent() {
-                if user_agent.to_ascii_lowercase().contains("mac") {
-                    *context_params.is_macos = true;
-                }
-            }
+    for (entity, egui_input, is_primary_window) in contexts.iter() {
+        if egui_input.screen_rect.is_none() {
+            continue;
         }
-    });
-
-    let mut keyboard_input_events = Vec::new();
-    for event in input_events.ev_keyboard_input.read() {
-        // Copy the events as we might want to pass them to an Egui context later.
-        keyboard_input_events.push(event.clone());
-
-        let KeyboardInput {
-            logical_key, state, ..
-        } = event;
-        match logical_key {
-            Key::Shift => {
-                input_resources.modifier_keys_state.shift = state.is_pressed();
-            }
-            Key::Control => {
-                input_resources.modifier_keys_state.ctrl = state.is_pressed();
-            }
-            Key::Alt => {
-                input_resources.modifier_keys_state.alt = state.is_pressed();
-            }
-            Key::Super | Key::Meta => {
-                input_resources.modifier_keys_state.win = state.is_pressed();
-            }
-            _ => {}
-        };
+        commands.entity(entity).insert(EguiContextReady);
+        initialized_events.send(EguiContextInitialized {
+            entity,
+            kind: EguiContextKind::new(is_primary_window),
+        });
     }
+}
 
-    let ModifierKeysState {
-        shift,
-        ctrl,
-        alt,
-        win,
-    } = *input_resources.modifier_keys_state;
-    let mac_cmd = if *context_params.is_macos { win } else { false };
-    let command = if *context_params.is_macos { win } else { ctrl };
-
-    let modifiers = egui::Modifiers {
-        alt,
-        ctrl,
-        shift,
-        mac_cmd,
-        command,
-    };
+/// Warns if more than one context is marked with [`EguiPrimaryContext`].
+pub fn warn_multiple_primary_contexts_system(
+    added: Query<(), Added<EguiPrimaryContext>>,
+    primary_contexts: Query<Entity, With<EguiPrimaryContext>>,
+) {
+    if added.is_empty() || primary_contexts.iter().len() < 2 {
+        return;
+    }
+    let entities: Vec<Entity> = primary_contexts.iter().collect();
+    log::warn!(
+        "Multiple entities are marked with `EguiPrimaryContext` ({entities:?}), the one with the lowest index is used as the primary context"
+    );
+}
 
-    for event in input_events.ev_cursor.read() {
-        let Some(mut window_context) = context_params.window_context(event.window) else {
+/// Marks frame start for Egui.
+pub fn begin_frame_system(
+    egui_settings: Res<EguiSettings>,
+    mut contexts: Query<(
+        Entity,
+        &mut EguiContext,
+        &mut EguiInput,
+        &mut EguiRenderOutput,
+    )>,
+) {
+    for (entity, mut ctx, mut egui_input, mut render_output) in contexts.iter_mut() {
+        // Input of contexts that skip the pass is accumulated for the next one.
+        if !ctx.pass_due {
             continue;
-        };
-
-        let scale_factor = egui_settings.scale_factor;
-        let (x, y): (f32, f32) = (event.position / scale_factor).into();
-        let mouse_position = egui::pos2(x, y);
-        window_context.ctx.mouse_position = mouse_position;
-        window_context
-            .egui_input
-            .events
-            .push(egui::Event::PointerMoved(mouse_position));
+        }
+        if ctx.pass_open && egui_settings.recover_from_poisoned_pass {
+            log::error!("The previous pass of the context {entity:?} didn't end (a panic was caught?), discarding it");
+            // Textures updates are still needed by the following passes.
+            let textures_delta = ctx.get_mut().end_frame().textures_delta;
+            render_output.textures_delta.append(textures_delta);
+        }
+        ctx.filtered_input_events = 0;
+        ctx.pass_open = true;
+        ctx.get_mut().begin_frame(egui_input.take());
     }
+}
 
-    for event in input_events.ev_mouse_button_input.read() {
-        let Some(mut window_context) = context_params.window_context(event.window) else {
+/// Runs the [`EguiUiSchedule`]s of the contexts that run a pass, marking the contexts whose
+/// schedule panicked with [`EguiContextPoisoned`].
+pub fn run_ui_schedules_system(world: &mut World) {
+    let mut ui_schedules = world.query::<(Entity, &EguiContext, &EguiUiSchedule)>();
+    let ui_schedules: Vec<(Entity, EguiUiSchedule)> = ui_schedules
+        .iter(world)
+        .filter(|(_, ctx, _)| ctx.pass_due)
+        .map(|(entity, _, ui_schedule)| (entity, *ui_schedule))
+        .collect();
+    for (entity, EguiUiSchedule(label)) in ui_schedules {
+        let Some(mut schedule) = world.resource_mut::<Schedules>().remove(label) else {
+            log::warn!("The UI schedule {label:?} of the context {entity:?} doesn't exist");
             continue;
         };
+        let result = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| {
+            schedule.run(world);
+        }));
+        world.resource_mut::<Schedules>().insert(schedule);
 
-        let button = match event.button {
-            MouseButton::Left => Some(egui::PointerButton::Primary),
-            MouseButton::Right => Some(egui::PointerButton::Secondary),
-            MouseButton::Middle => Some(egui::PointerButton::Middle),
-            _ => None,
-        };
-        let pressed = match event.state {
-            ButtonState::Pressed => true,
-            ButtonState::Released => false,
+        let Err(payload) = result else {
+            continue;
         };
-        if let Some(button) = button {
-            window_context
-                .egui_input
-                .events
-                .push(egui::Event::PointerButton {
-                    pos: window_context.ctx.mouse_position,
-                    button,
-                    pressed,
-                    modifiers,
-                });
-        }
+        let message = payload
+            .downcast_ref::<&str>()
+            .map(|message| message.to_string())
+            .or_else(|| payload.downcast_ref::<String>().cloned())
+            .unwrap_or_default();
+        log::error!("The UI schedule {label:?} of the context {entity:?} panicked, disabling the context: {message}");
+        world.entity_mut(entity).insert(EguiContextPoisoned);
+        world
+            .resource_mut::<Events<EguiContextPoisonedEvent>>()
+            .send(EguiContextPoisonedEvent {
+                context: entity,
+                message,
+            });
     }
+}
 
-    for event in input_events.ev_mouse_wheel.read() {
-        let Some(mut window_context) = context_params.window_context(event.window) else {
+/// Decides which contexts run a pass during the current frame, see [`EguiPassRate`].
+pub fn schedule_egui_passes_system(
+    mut contexts: Query<(
+        Entity,
+        &mut EguiContext,
+        Option<&EguiPassRate>,
+        Has<EguiContextPoisoned>,
+    )>,
+    mut force_pass_events: EventReader<EguiForcePass>,
+    time: Res<Time<Real>>,
+) {
+    let forced: Vec<Entity> = force_pass_events.read().map(|event| event.0).collect();
+    let now = time.elapsed_seconds_f64();
+    for (entity, mut ctx, pass_rate, poisoned) in contexts.iter_mut() {
+        if poisoned {
+            ctx.pass_due = false;
             continue;
-        };
-
-        let mut delta = egui::vec2(event.x, event.y);
-        if let MouseScrollUnit::Line = event.unit {
-            // https://github.com/emilk/egui/blob/a689b623a669d54ea85708a8c748eb07e23754b0/egui-winit/src/lib.rs#L449
-            delta *= 50.0;
         }
+        let pass_due = match (ctx.last_pass_time, pass_rate) {
+            (None, _) | (_, None | Some(EguiPassRate::EveryFrame)) => true,
+            (Some(last_pass_time), Some(EguiPassRate::Hz(hz))) => {
+                *hz <= 0.0 || now - last_pass_time >= 1.0 / *hz as f64
+            }
+            (_, Some(EguiPassRate::EveryNFrames(frames))) => ctx.frames_since_pass + 1 >= *frames,
+        } || forced.contains(&entity);
 
-        if ctrl || mac_cmd {
-            // Treat as zoom instead.
-            let factor = (delta.y / 200.0).exp();
-            window_context
-                .egui_input
-                .events
-                .push(egui::Event::Zoom(factor));
-        } else if shift {
-            // Treat as horizontal scrolling.
-            // Note: Mac already fires horizontal scroll events when shift is down.
-            window_context
-                .egui_input
-                .events
-                .push(egui::Event::Scroll(egui::vec2(delta.x + delta.y, 0.0)));
+        ctx.pass_due = pass_due;
+        if pass_due {
+            ctx.last_pass_time = Some(now);
+            ctx.frames_since_pass = 0;
         } else {
-            window_context
-                .egui_input
-                .events
-                .push(egui::Event::Scroll(delta));
+            ctx.frames_since_pass += 1;
         }
     }
+}
 
-    if !command && !win || !*context_params.is_macos && ctrl && alt {
-        for event in input_events.ev_received_character.read() {
-            let Some(mut window_context) = context_params.window_context(event.window) else {
-                continue;
-            };
-
-            if event.char.matches(char::is_control).count() == 0 {
-                window_context
-                    .egui_input
-                    .events
-                    .push(egui::Event::Text(event.char.to_string()));
-            }
-        }
-    }
-
-    for event in keyboard_input_events {
-        let Some(mut window_context) = context_params.window_context(event.window) else {
-            continue;
-        };
-
-        let (Some(key), physical_key) = (
-            bevy_to_egui_key(&event.logical_key),
-            bevy_to_egui_physical_key(&event.key_code),
-        ) else {
-            continue;
-        };
-
-        let egui_event = egui::Event::Key {
-            key,
-            pressed: event.state.is_pressed(),
-            repeat: false,
-            modifiers,
-            physical_key,
-        };
-        window_context.egui_input.events.push(egui_event);
-
-        // We also check that it's an `ButtonState::Pressed` event, as we don't want to
-        // copy, cut or paste on the key release.
-        #[cfg(all(
-            feature = "manage_clipboard",
-            not(target_os = "android"),
-            not(target_arch = "wasm32")
-        ))]
-        if command && event.state.is_pressed() {
-            match key {
-                egui::Key::C => {
-                    window_context.egui_input.events.push(egui::Event::Copy);
-                }
-                egui::Key::X => {
-                    window_context.egui_input.events.push(egui::Event::Cut);
-                }
-                egui::Key::V => {
-                    if let Some(contents) = input_resources.egui_clipboard.get_contents() {
-                        window_context
-                            .egui_input
-                            .events
-                            .push(egui::Event::Text(contents))
-                    }
+/// Updates [`EguiWantsInput`] after the frame has begun.
+pub fn write_egui_wants_input_system(
+    mut contexts: Query<(Entity, &mut EguiContext)>,
+    mut egui_wants_input: ResMut<EguiWantsInput>,
+    mut text_input_focus: ResMut<TextInputFocus>,
+    egui_settings: Res<EguiSettings>,
+) {
+    let yield_focus = egui_settings.respect_external_text_focus && text_input_focus.is_external();
+    let mut new_wants_input = EguiWantsInput::default();
+    let mut keyboard_context = None;
+    for (entity, mut ctx) in contexts.iter_mut() {
+        let ctx = ctx.get_mut();
+        if yield_focus && ctx.wants_keyboard_input() {
+            ctx.memory_mut(|memory| {
+                if let Some(id) = memory.focused() {
+                    memory.surrender_focus(id);
                 }
-                _ => {}
-            }
+            });
+        }
+        new_wants_input.is_pointer_over_area |= ctx.is_pointer_over_area();
+        new_wants_input.wants_pointer_input |= ctx.wants_pointer_input();
+        if ctx.wants_keyboard_input() {
+            new_wants_input.wants_keyboard_input = true;
+            keyboard_context.get_or_insert(entity);
         }
     }
+    egui_wants_input.set_if_neq(new_wants_input);
 
-    #[cfg(all(
-        feature = "manage_clipboard",
-        target_arch = "wasm32",
-        web_sys_unstable_apis
-    ))]
-    while let Some(event) = input_resources.egui_clipboard.try_receive_clipboard_event() {
-        // In web, we assume that we have only 1 window per app.
-        let mut window_context = context_params.contexts.single_mut();
-
-        match event {
-            crate::web_clipboard::WebClipboardEvent::Copy => {
-                window_context.egui_input.events.push(egui::Event::Copy);
-            }
-            crate::web_clipboard::WebClipboardEvent::Cut => {
-                window_context.egui_input.events.push(egui::Event::Cut);
-            }
-            crate::web_clipboard::WebClipboardEvent::Paste(contents) => {
-                input_resources
-                    .egui_clipboard
-                    .set_contents_internal(&contents);
-                window_context
-                    .egui_input
-                    .events
-                    .push(egui::Event::Text(contents))
-            }
-        }
+    // The focus of other text inputs is never clobbered.
+    if !text_input_focus.is_external() {
+        text_input_focus.set_if_neq(TextInputFocus(keyboard_context.map(TextInputOwner::Egui)));
     }
+}
 
-    for event in input_events.ev_touch.read() {
-        let Some(mut window_context) = context_params.window_context(event.window) else {
+/// Lays out the texts of [`EguiPrewarm`] in the contexts that run a pass, and removes the
+/// component.
+pub fn prewarm_fonts_system(
+    mut commands: Commands,
+    mut contexts: Query<(Entity, &mut EguiContext, &EguiPrewarm)>,
+) {
+    for (entity, mut ctx, prewarm) in contexts.iter_mut() {
+        if !ctx.pass_due {
             continue;
-        };
-
-        let touch_id = egui::TouchId::from(event.id);
-        let scale_factor = egui_settings.scale_factor;
-        let touch_position: (f32, f32) = (event.position / scale_factor).into();
-
-        // Emit touch event
-        window_context.egui_input.events.push(egui::Event::Touch {
-            device_id: egui::TouchDeviceId(event.window.to_bits()),
-            id: touch_id,
-            phase: match event.phase {
-                bevy::input::touch::TouchPhase::Started => egui::TouchPhase::Start,
-                bevy::input::touch::TouchPhase::Moved => egui::TouchPhase::Move,
-                bevy::input::touch::TouchPhase::Ended => egui::TouchPhase::End,
-                bevy::input::touch::TouchPhase::Canceled => egui::TouchPhase::Cancel,
-            },
-            pos: egui::pos2(touch_position.0, touch_position.1),
-            force: match event.force {
-                Some(bevy::input::touch::ForceTouch::Normalized(force)) => Some(force as f32),
-                Some(bevy::input::touch::ForceTouch::Calibrated {
-                    force,
-                    max_possible_force,
-                    ..
-                }) => Some((force / max_possible_force) as f32),
-                None => None,
-            },
-        });
-
-        // If we're not yet translating a touch, or we're translating this very
-        // touch, …
-        if window_context.ctx.pointer_touch_id.is_none()
-            || window_context.ctx.pointer_touch_id.unwrap() == event.id
-        {
-            // … emit PointerButton resp. PointerMoved events to emulate mouse.
-            match event.phase {
-                bevy::input::touch::TouchPhase::Started => {
-                    window_context.ctx.pointer_touch_id = Some(event.id);
-                    // First move the pointer to the right location.
-                    window_context
-                        .egui_input
-                        .events
-                        .push(egui::Event::PointerMoved(egui::pos2(
-                            touch_position.0,
-                            touch_position.1,
-                        )));
-                    // Then do mouse button input.
-                    window_context
-                        .egui_input
-                        .events
-                        .push(egui::Event::PointerButton {
-                            pos: egui::pos2(touch_position.0, touch_position.1),
-                            button: egui::PointerButton::Primary,
-                            pressed: true,
-                            modifiers,
-                        });
-                }
-                bevy::input::touch::TouchPhase::Moved => {
-                    window_context
-                        .egui_input
-                        .events
-                        .push(egui::Event::PointerMoved(egui::pos2(
-                            touch_position.0,
-                            touch_position.1,
-                        )));
-                }
-                bevy::input::touch::TouchPhase::Ended => {
-                    window_context.ctx.pointer_touch_id = None;
-                    window_context
-                        .egui_input
-                        .events
-                        .push(egui::Event::PointerButton {
-                            pos: egui::pos2(touch_position.0, touch_position.1),
-                            button: egui::PointerButton::Primary,
-                            pressed: false,
-                            modifiers,
-                        });
-                    window_context
-                        .egui_input
-                        .events
-                        .push(egui::Event::PointerGone);
-                }
-                bevy::input::touch::TouchPhase::Canceled => {
-                    window_context.ctx.pointer_touch_id = None;
-                    window_context
-                        .egui_input
-                        .events
-                        .push(egui::Event::PointerGone);
+        }
+        let ctx = ctx.get_mut();
+        let mut font_ids: Vec<egui::FontId> = ctx.style().text_styles.values().cloned().collect();
+        font_ids.extend(prewarm.font_ids.iter().cloned());
+        ctx.fonts(|fonts| {
+            for font_id in &font_ids {
+                for text in &prewarm.texts {
+                    // The galley isn't painted, only its glyphs get cached by the atlas.
+                    fonts.layout_no_wrap(text.clone(), font_id.clone(), egui::Color32::WHITE);
                 }
             }
-        }
-    }
-
-    for mut context in context_params.contexts.iter_mut() {
-        context.egui_input.modifiers = modifiers;
-        context.egui_input.time = Some(time.elapsed_seconds_f64());
+        });
+        commands.entity(entity).remove::<EguiPrewarm>();
     }
-
-    // In some cases, we may skip certain events. For example, we ignore `ReceivedCharacter` events
-    // when alt or ctrl button is pressed. We still want to clear event buffer.
-    input_events.clear();
 }
 
-/// Initialises Egui contexts (for multiple windows).
-pub fn update_window_contexts_system(
-    mut context_params: ContextSystemParams,
-    egui_settings: Res<EguiSettings>,
-) {
-    for mut context in context_params.contexts.iter_mut() {
-        let new_window_size = WindowSize::new(
-            context.window.physical_width() as f32,
-            context.window.physical_height() as f32,
-            context.window.scale_factor(),
-        );
-        let width = new_window_size.physical_width
-            / new_window_size.scale_factor
-            / egui_settings.scale_factor;
-        let height = new_window_size.physical_height
-            / new_window_size.scale_factor
-            / egui_settings.scale_factor;
+/// Draws the debug overlay on the primary context, see [`EguiSettings::show_debug_overlay`].
+pub fn debug_overlay_system(mut contexts: EguiContexts, egui_wants_input: Res<EguiWantsInput>) {
+    let Some(primary_context_entity) = contexts.primary_context_entity() else {
+        return;
+    };
+    let debug_layer = egui::LayerId::new(egui::Order::Debug, egui::Id::new("bevy_egui_debug"));
 
-        if width < 1.0 || height < 1.0 {
+    let mut rows = Vec::new();
+    for (entity, mut ctx, is_primary_window, _) in contexts.q.iter_mut() {
+        if !ctx.pass_due {
             continue;
         }
-
-        context.egui_input.screen_rect = Some(egui::Rect::from_min_max(
-            egui::pos2(0.0, 0.0),
-            egui::pos2(width, height),
+        let pointer_position = ctx.mouse_position;
+        let ctx = ctx.get_mut();
+        let kind = if is_primary_window {
+            "primary window"
+        } else {
+            "window"
+        };
+        let pixels_per_point = contexts
+            .computed_scales
+            .get(entity)
+            .map_or(0.0, |computed_scale| computed_scale.pixels_per_point);
+        let events_count = ctx.input(|input| input.events.len());
+        rows.push(format!(
+            "{entity:?} ({kind}): screen rect {:?}, {pixels_per_point:.2} pixels per point, pointer {pointer_position:?}, {events_count} events",
+            ctx.screen_rect(),
         ));
 
-        context
-            .ctx
-            .get_mut()
-            .set_pixels_per_point(new_window_size.scale_factor * egui_settings.scale_factor);
-
-        *context.window_size = new_window_size;
+        // Crosshair at the last pointer position fed to the context.
+        let painter = ctx.layer_painter(debug_layer);
+        let stroke = egui::Stroke::new(1.0, egui::Color32::RED);
+        painter.line_segment(
+            [
+                pointer_position - egui::vec2(8.0, 0.0),
+                pointer_position + egui::vec2(8.0, 0.0),
+            ],
+            stroke,
+        );
+        painter.line_segment(
+            [
+                pointer_position - egui::vec2(0.0, 8.0),
+                pointer_position + egui::vec2(0.0, 8.0),
+            ],
+            stroke,
+        );
     }
-}
 
-/// Marks frame start for Egui.
-pub fn begin_frame_system(mut contexts: Query<(&mut EguiContext, &mut EguiInput)>) {
-    for (mut ctx, mut egui_input) in contexts.iter_mut() {
-        ctx.get_mut().begin_frame(egui_input.take());
+    let Ok((_, mut ctx, _, _)) = contexts.q.get_mut(primary_context_entity) else {
+        return;
+    };
+    if !ctx.pass_due {
+        return;
     }
+    // The area isn't interactable, so that it doesn't affect the input it shows.
+    egui::Area::new(egui::Id::new("bevy_egui_debug_overlay"))
+        .order(egui::Order::Debug)
+        .interactable(false)
+        .anchor(egui::Align2::RIGHT_TOP, egui::vec2(-8.0, 8.0))
+        .show(ctx.get_mut(), |ui| {
+            egui::Frame::popup(ui.style()).show(ui, |ui| {
+                ui.label(egui::RichText::new("bevy_egui").strong());
+                for row in &rows {
+                    ui.label(row);
+                }
+                ui.label(format!("{:?}", *egui_wants_input));
+            });
+        });
 }
 
 /// Reads Egui output.
+#[allow(clippy::too_many_arguments)]
 pub fn process_output_system(
-    #[cfg_attr(not(feature = "open_url"), allow(unused_variables))] egui_settings: Res<
-        EguiSettings,
-    >,
+    egui_settings: Res<EguiSettings>,
     mut contexts: Query<EguiContextQuery>,
+    cursor_map: Res<EguiCursorMap>,
     #[cfg(all(feature = "manage_clipboard", not(target_os = "android")))]
     mut egui_clipboard: bevy::ecs::system::ResMut<crate::EguiClipboard>,
+    time: Res<Time<Real>>,
     mut event: EventWriter<RequestRedraw>,
+    // The soonest time (elapsed since the startup) when Egui asked to be repainted.
+    mut redraw_deadline: Local<Option<Duration>>,
+    mut any_output_changed: ResMut<EguiAnyOutputChanged>,
     #[cfg(windows)] mut last_cursor_icon: Local<bevy::utils::HashMap<Entity, egui::CursorIcon>>,
+    #[cfg(feature = "render")] user_textures: Res<crate::EguiUserTextures>,
+    open_url_policy: Res<EguiOpenUrlPolicy>,
+    mut open_url_events: OpenUrlEvents,
+    #[cfg(feature = "validate_user_textures")] mut reported_user_textures: Local<
+        bevy::utils::HashSet<u64>,
+    >,
 ) {
+    let now = time.elapsed();
     let mut should_request_redraw = false;
+    if redraw_deadline.is_some_and(|deadline| deadline <= now) {
+        *redraw_deadline = None;
+        should_request_redraw = true;
+    }
 
-    for mut context in contexts.iter_mut() {
+    // Ending frames and tessellation don't need world access and are independent for every
+    // context, so heavy contexts don't delay the others.
+    // Contexts that skipped the pass keep their last output, which gets rendered again.
+    contexts.par_iter_mut().for_each(|mut context| {
+        if !context.ctx.pass_due {
+            if let Some(output_changed) = &mut context.output_changed {
+                output_changed.changed = false;
+            }
+            return;
+        }
+        context.ctx.pass_open = false;
+        let mut delayed_texture_frees = std::mem::take(&mut context.ctx.delayed_texture_frees);
         let ctx = context.ctx.get_mut();
         let full_output = ctx.end_frame();
         let egui::FullOutput {
             platform_output,
-            shapes,
-            textures_delta,
+            mut shapes,
+            mut textures_delta,
             pixels_per_point,
-            viewport_output: _,
+            viewport_output,
         } = full_output;
-        let paint_jobs = ctx.tessellate(shapes, pixels_per_point);
+        if let Some(shape_filters) = context.shape_filters {
+            shape_filters.apply(&mut shapes, ctx);
+        }
+        if let Some(occlusion_map) = &mut context.occlusion_map {
+            occlusion_map.update(ctx);
+        }
+        if let Some(settings) = context.tessellation_settings {
+            let feathering = settings.feathering(pixels_per_point);
+            if feathering.is_some() || settings.round_text_to_pixels.is_some() {
+                ctx.tessellation_options_mut(|options| {
+                    if let Some(feathering) = feathering {
+                        options.feathering = feathering;
+                    }
+                    if let Some(round_text_to_pixels) = settings.round_text_to_pixels {
+                        options.round_text_to_pixels = round_text_to_pixels;
+                    }
+                });
+            }
+        }
+        let mut paint_jobs = if context.skip_tessellation {
+            Vec::new()
+        } else if let Some(async_tessellation) = &mut context.async_tessellation {
+            async_tessellation.tessellate(
+                ctx,
+                shapes,
+                pixels_per_point,
+                &mut textures_delta,
+                &mut delayed_texture_frees,
+            )
+        } else {
+            ctx.tessellate(shapes, pixels_per_point)
+        };
+        if context.async_tessellation.is_none() {
+            // The `EguiAsyncTessellation` got removed, nothing paints the delayed textures anymore.
+            textures_delta.free.append(&mut delayed_texture_frees);
+        }
+        context.ctx.delayed_texture_frees = delayed_texture_frees;
+
+        if let Some(extra_paint_jobs) = &mut context.extra_paint_jobs {
+            let primitives = std::mem::take(&mut extra_paint_jobs.primitives)
+                .into_iter()
+                .filter(|primitive| {
+                    if let egui::epaint::Primitive::Callback(_) = primitive.primitive {
+                        log::warn!("Paint callbacks aren't supported, skipping an extra paint job");
+                        return false;
+                    }
+                    #[cfg(feature = "render")]
+                    if let egui::epaint::Primitive::Mesh(egui::epaint::Mesh {
+                        texture_id: egui::TextureId::User(id),
+                        ..
+                    }) = primitive.primitive
+                    {
+                        if !user_textures.contains_texture_id(id) {
+                            log::warn!(
+                                "An extra paint job references an unknown user texture ({id})"
+                            );
+                        }
+                    }
+                    true
+                });
+            match extra_paint_jobs.placement {
+                EguiExtraPaintJobsPlacement::Underlay => {
+                    paint_jobs.splice(0..0, primitives);
+                }
+                EguiExtraPaintJobsPlacement::Overlay => paint_jobs.extend(primitives),
+            }
+        }
 
+        if let Some(output_changed) = &mut context.output_changed {
+            output_changed.update(&paint_jobs, &textures_delta);
+        }
         context.render_output.paint_jobs = paint_jobs;
         context.render_output.textures_delta.append(textures_delta);
 
-        context.egui_output.platform_output = platform_output.clone();
+        context.egui_output.platform_output = platform_output;
+        context.ctx.repaint_delay = viewport_output
+            .get(&egui::ViewportId::ROOT)
+            .map(|viewport_output| viewport_output.repaint_delay);
+    });
+
+    any_output_changed.set_if_neq(EguiAnyOutputChanged(contexts.iter().any(|context| {
+        context
+            .output_changed
+            .is_some_and(|output_changed| output_changed.changed)
+    })));
+
+    for mut context in contexts.iter_mut() {
+        if !context.ctx.pass_due {
+            continue;
+        }
+        // Catches ids kept around after their images got removed, once per id.
+        #[cfg(feature = "validate_user_textures")]
+        for primitive in &context.render_output.paint_jobs {
+            let egui::epaint::Primitive::Mesh(egui::epaint::Mesh {
+                texture_id: egui::TextureId::User(id),
+                ..
+            }) = primitive.primitive
+            else {
+                continue;
+            };
+            if !user_textures.contains_texture_id(id) && reported_user_textures.insert(id) {
+                log::error!(
+                    "The context {:?} paints an unregistered user texture (id: {id}, namespace: {:?})",
+                    context.window_entity,
+                    user_textures.namespace_of(id),
+                );
+            }
+        }
+        let platform_output = &context.egui_output.platform_output;
 
         #[cfg(all(
             feature = "manage_clipboard",
@@ -519,14 +564,20 @@ pub fn process_output_system(
         }
 
         let mut set_icon = || {
-            context.window.cursor.icon = egui_to_winit_cursor_icon(platform_output.cursor_icon)
+            let icon = cursor_map
+                .get(platform_output.cursor_icon)
+                .or_else(|| egui_to_winit_cursor_icon(platform_output.cursor_icon))
                 .unwrap_or(bevy::window::CursorIcon::Default);
+            // Writing the same value would still mark the window as changed.
+            if context.window.cursor.icon != icon {
+                context.window.cursor.icon = icon;
+            }
         };
 
         #[cfg(windows)]
         {
             let last_cursor_icon = last_cursor_icon.entry(context.window_entity).or_default();
-            if *last_cursor_icon != platform_output.cursor_icon {
+            if *last_cursor_icon != platform_output.cursor_icon || cursor_map.is_changed() {
                 set_icon();
                 *last_cursor_icon = platform_output.cursor_icon;
             }
@@ -534,34 +585,80 @@ pub fn process_output_system(
         #[cfg(not(windows))]
         set_icon();
 
-        let needs_repaint = !context.render_output.is_empty();
-        should_request_redraw |= ctx.has_requested_repaint() && needs_repaint;
+        let ime = platform_output.ime;
+        context.text_cursor_info.set_if_neq(EguiTextCursorInfo {
+            active: ime.is_some(),
+            caret_rect: ime.map(|ime| ime.cursor_rect),
+            text_edit_rect: ime.map(|ime| ime.rect),
+        });
+        if context.ime_mode.copied().unwrap_or_default() == EguiWindowImeMode::Managed
+            && context.window.ime_enabled != ime.is_some()
+        {
+            context.window.ime_enabled = ime.is_some();
+        }
 
-        #[cfg(feature = "open_url")]
-        if let Some(egui::output::OpenUrl { url, new_tab }) = platform_output.open_url {
-            let target = if new_tab {
-                "_blank"
-            } else {
-                egui_settings
-                    .default_open_url_target
-                    .as_deref()
-                    .unwrap_or("_self")
-            };
-            if let Err(err) = webbrowser::open_browser_with_options(
-                webbrowser::Browser::Default,
-                &url,
-                webbrowser::BrowserOptions::new().with_target_hint(target),
-            ) {
-                log::error!("Failed to open '{}': {:?}", url, err);
+        if let Some(repaint_delay) = context.ctx.repaint_delay {
+            if repaint_delay.is_zero() {
+                should_request_redraw = true;
+            } else if let Some(deadline) = now.checked_add(repaint_delay) {
+                // Egui uses `Duration::MAX` if a repaint isn't needed.
+                if !redraw_deadline.is_some_and(|soonest| soonest <= deadline) {
+                    *redraw_deadline = Some(deadline);
+                }
+            }
+        }
+
+        if let Some(egui::output::OpenUrl { url, new_tab }) = &platform_output.open_url {
+            match open_url_policy.decide(url) {
+                EguiUrlDecision::Allow => {
+                    #[cfg(feature = "open_url")]
+                    {
+                        let target = if *new_tab {
+                            "_blank"
+                        } else {
+                            egui_settings
+                                .default_open_url_target
+                                .as_deref()
+                                .unwrap_or("_self")
+                        };
+                        if let Err(err) = webbrowser::open_browser_with_options(
+                            webbrowser::Browser::Default,
+                            url,
+                            webbrowser::BrowserOptions::new().with_target_hint(target),
+                        ) {
+                            log::error!("Failed to open '{}': {:?}", url, err);
+                        }
+                    }
+                }
+                EguiUrlDecision::Block => {
+                    open_url_events.blocked.send(EguiOpenUrlBlocked {
+                        context: context.window_entity,
+                        url: url.clone(),
+                    });
+                }
+                EguiUrlDecision::Emit => {
+                    open_url_events.requested.send(EguiOpenUrlRequested {
+                        context: context.window_entity,
+                        url: url.clone(),
+                        new_tab: *new_tab,
+                    });
+                }
             }
         }
     }
 
-    if should_request_redraw {
+    if should_request_redraw && egui_settings.request_redraws {
         event.send(RequestRedraw);
     }
 }
 
+/// Writers of the events sent by [`EguiOpenUrlPolicy`].
+#[derive(SystemParam)]
+pub struct OpenUrlEvents<'w> {
+    requested: EventWriter<'w, EguiOpenUrlRequested>,
+    blocked: EventWriter<'w, EguiOpenUrlBlocked>,
+}
+
 fn egui_to_winit_cursor_icon(cursor_icon: egui::CursorIcon) -> Option<bevy::window::CursorIcon> {
     match cursor_icon {
         egui::CursorIcon::Default => Some(bevy::window::CursorIcon::Default),
@@ -601,152 +698,3 @@ fn egui_to_winit_cursor_icon(cursor_icon: egui::CursorIcon) -> Option<bevy::wind
         egui::CursorIcon::None => None,
     }
 }
-
-/// Matches the implementation of <https://github.com/emilk/egui/blob/68b3ef7f6badfe893d3bbb1f791b481069d807d9/crates/egui-winit/src/lib.rs#L1005>.
-pub fn bevy_to_egui_key(key: &Key) -> Option<egui::Key> {
-    let key = match key {
-        Key::Character(str) => return egui::Key::from_name(str.as_str()),
-        Key::Unidentified(_) | Key::Dead(_) => return None,
-
-        Key::Enter => egui::Key::Enter,
-        Key::Tab => egui::Key::Tab,
-        Key::Space => egui::Key::Space,
-        Key::ArrowDown => egui::Key::ArrowDown,
-        Key::ArrowLeft => egui::Key::ArrowLeft,
-        Key::ArrowRight => egui::Key::ArrowRight,
-        Key::ArrowUp => egui::Key::ArrowUp,
-        Key::End => egui::Key::End,
-        Key::Home => egui::Key::Home,
-        Key::PageDown => egui::Key::PageDown,
-        Key::PageUp => egui::Key::PageUp,
-        Key::Backspace => egui::Key::Backspace,
-        Key::Delete => egui::Key::Delete,
-        Key::Insert => egui::Key::Insert,
-        Key::Escape => egui::Key::Escape,
-        Key::F1 => egui::Key::F1,
-        Key::F2 => egui::Key::F2,
-        Key::F3 => egui::Key::F3,
-        Key::F4 => egui::Key::F4,
-        Key::F5 => egui::Key::F5,
-        Key::F6 => egui::Key::F6,
-        Key::F7 => egui::Key::F7,
-        Key::F8 => egui::Key::F8,
-        Key::F9 => egui::Key::F9,
-        Key::F10 => egui::Key::F10,
-        Key::F11 => egui::Key::F11,
-        Key::F12 => egui::Key::F12,
-        Key::F13 => egui::Key::F13,
-        Key::F14 => egui::Key::F14,
-        Key::F15 => egui::Key::F15,
-        Key::F16 => egui::Key::F16,
-        Key::F17 => egui::Key::F17,
-        Key::F18 => egui::Key::F18,
-        Key::F19 => egui::Key::F19,
-        Key::F20 => egui::Key::F20,
-
-        _ => return None,
-    };
-    Some(key)
-}
-
-/// Matches the implementation of <https://github.com/emilk/egui/blob/68b3ef7f6badfe893d3bbb1f791b481069d807d9/crates/egui-winit/src/lib.rs#L1080>.
-pub fn bevy_to_egui_physical_key(key: &KeyCode) -> Option<egui::Key> {
-    let key = match key {
-        KeyCode::ArrowDown => egui::Key::ArrowDown,
-        KeyCode::ArrowLeft => egui::Key::ArrowLeft,
-        KeyCode::ArrowRight => egui::Key::ArrowRight,
-        KeyCode::ArrowUp => egui::Key::ArrowUp,
-
-        KeyCode::Escape => egui::Key::Escape,
-        KeyCode::Tab => egui::Key::Tab,
-        KeyCode::Backspace => egui::Key::Backspace,
-        KeyCode::Enter | KeyCode::NumpadEnter => egui::Key::Enter,
-
-        KeyCode::Insert => egui::Key::Insert,
-        KeyCode::Delete => egui::Key::Delete,
-        KeyCode::Home => egui::Key::Home,
-        KeyCode::End => egui::Key::End,
-        KeyCode::PageUp => egui::Key::PageUp,
-        KeyCode::PageDown => egui::Key::PageDown,
-
-        // Punctuation
-        KeyCode::Space => egui::Key::Space,
-        KeyCode::Comma => egui::Key::Comma,
-        KeyCode::Period => egui::Key::Period,
-        // KeyCode::Colon => egui::Key::Colon, // NOTE: there is no physical colon key on an american keyboard
-        KeyCode::Semicolon => egui::Key::Semicolon,
-        KeyCode::Backslash => egui::Key::Backslash,
-        KeyCode::Slash | KeyCode::NumpadDivide => egui::Key::Slash,
-        KeyCode::BracketLeft => egui::Key::OpenBracket,
-        KeyCode::BracketRight => egui::Key::CloseBracket,
-        KeyCode::Backquote => egui::Key::Backtick,
-
-        KeyCode::Cut => egui::Key::Cut,
-        KeyCode::Copy => egui::Key::Copy,
-        KeyCode::Paste => egui::Key::Paste,
-        KeyCode::Minus | KeyCode::NumpadSubtract => egui::Key::Minus,
-        KeyCode::NumpadAdd => egui::Key::Plus,
-        KeyCode::Equal => egui::Key::Equals,
-
-        KeyCode::Digit0 | KeyCode::Numpad0 => egui::Key::Num0,
-        KeyCode::Digit1 | KeyCode::Numpad1 => egui::Key::Num1,
-        KeyCode::Digit2 | KeyCode::Numpad2 => egui::Key::Num2,
-        KeyCode::Digit3 | KeyCode::Numpad3 => egui::Key::Num3,
-        KeyCode::Digit4 | KeyCode::Numpad4 => egui::Key::Num4,
-        KeyCode::Digit5 | KeyCode::Numpad5 => egui::Key::Num5,
-        KeyCode::Digit6 | KeyCode::Numpad6 => egui::Key::Num6,
-        KeyCode::Digit7 | KeyCode::Numpad7 => egui::Key::Num7,
-        KeyCode::Digit8 | KeyCode::Numpad8 => egui::Key::Num8,
-        KeyCode::Digit9 | KeyCode::Numpad9 => egui::Key::Num9,
-
-        KeyCode::KeyA => egui::Key::A,
-        KeyCode::KeyB => egui::Key::B,
-        KeyCode::KeyC => egui::Key::C,
-        KeyCode::KeyD => egui::Key::D,
-        KeyCode::KeyE => egui::Key::E,
-        KeyCode::KeyF => egui::Key::F,
-        KeyCode::KeyG => egui::Key::G,
-        KeyCode::KeyH => egui::Key::H,
-        KeyCode::KeyI => egui::Key::I,
-        KeyCode::KeyJ => egui::Key::J,
-        KeyCode::KeyK => egui::Key::K,
-        KeyCode::KeyL => egui::Key::L,
-        KeyCode::KeyM => egui::Key::M,
-        KeyCode::KeyN => egui::Key::N,
-        KeyCode::KeyO => egui::Key::O,
-        KeyCode::KeyP => egui::Key::P,
-        KeyCode::KeyQ => egui::Key::Q,
-        KeyCode::KeyR => egui::Key::R,
-        KeyCode::KeyS => egui::Key::S,
-        KeyCode::KeyT => egui::Key::T,
-        KeyCode::KeyU => egui::Key::U,
-        KeyCode::KeyV => egui::Key::V,
-        KeyCode::KeyW => egui::Key::W,
-        KeyCode::KeyX => egui::Key::X,
-        KeyCode::KeyY => egui::Key::Y,
-        KeyCode::KeyZ => egui::Key::Z,
-
-        KeyCode::F1 => egui::Key::F1,
-        KeyCode::F2 => egui::Key::F2,
-        KeyCode::F3 => egui::Key::F3,
-        KeyCode::F4 => egui::Key::F4,
-        KeyCode::F5 => egui::Key::F5,
-        KeyCode::F6 => egui::Key::F6,
-        KeyCode::F7 => egui::Key::F7,
-        KeyCode::F8 => egui::Key::F8,
-        KeyCode::F9 => egui::Key::F9,
-        KeyCode::F10 => egui::Key::F10,
-        KeyCode::F11 => egui::Key::F11,
-        KeyCode::F12 => egui::Key::F12,
-        KeyCode::F13 => egui::Key::F13,
-        KeyCode::F14 => egui::Key::F14,
-        KeyCode::F15 => egui::Key::F15,
-        KeyCode::F16 => egui::Key::F16,
-        KeyCode::F17 => egui::Key::F17,
-        KeyCode::F18 => egui::Key::F18,
-        KeyCode::F19 => egui::Key::F19,
-        KeyCode::F20 => egui::Key::F20,
-        _ => return None,
-    };
-    Some(key)
-}
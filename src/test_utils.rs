@@ -0,0 +1,81 @@
+use crate::{EguiInput, EguiPlugin, EguiSet};
+use bevy::{
+    app::{App, PluginGroup, PreUpdate},
+    ecs::prelude::*,
+    input::{mouse::MouseButtonInput, ButtonState},
+    math::Vec2,
+    prelude::MouseButton,
+    render::{settings::WgpuSettings, RenderPlugin},
+    window::{CursorMoved, PrimaryWindow},
+    winit::WinitPlugin,
+    DefaultPlugins,
+};
+
+pub(crate) fn headless_app() -> App {
+    headless_app_with_plugin(EguiPlugin::default())
+}
+
+pub(crate) fn headless_app_with_plugin(egui_plugin: EguiPlugin) -> App {
+    let mut app = App::new();
+    app.add_plugins(
+        DefaultPlugins
+            .set(RenderPlugin {
+                render_creation: bevy::render::settings::RenderCreation::Automatic(WgpuSettings {
+                    backends: None,
+                    ..Default::default()
+                }),
+                ..Default::default()
+            })
+            .build()
+            .disable::<WinitPlugin>(),
+    )
+    .add_plugins(egui_plugin);
+    app
+}
+
+/// Egui events that were fed to contexts during the last [`EguiSet::ProcessInput`].
+#[derive(Resource, Default)]
+pub(crate) struct CapturedEvents(pub(crate) Vec<egui::Event>);
+
+pub(crate) fn capture_events_system(
+    mut captured: ResMut<CapturedEvents>,
+    egui_inputs: Query<&EguiInput>,
+) {
+    captured.0.clear();
+    for egui_input in egui_inputs.iter() {
+        captured.0.extend(egui_input.events.iter().cloned());
+    }
+}
+
+/// Returns an app with an initialized primary window context, which captures fed Egui events.
+pub(crate) fn input_test_app() -> (App, Entity) {
+    let mut app = headless_app();
+    app.init_resource::<CapturedEvents>().add_systems(
+        PreUpdate,
+        capture_events_system
+            .after(EguiSet::ProcessInput)
+            .before(EguiSet::BeginFrame),
+    );
+    app.update();
+    let window = app
+        .world
+        .query_filtered::<Entity, With<PrimaryWindow>>()
+        .single(&app.world);
+    (app, window)
+}
+
+pub(crate) fn send_moves_and_click(app: &mut App, window: Entity) {
+    for i in 0..10 {
+        app.world.send_event(CursorMoved {
+            window,
+            position: Vec2::new(i as f32 * 10.0, 5.0),
+            delta: None,
+        });
+    }
+    app.world.send_event(MouseButtonInput {
+        button: MouseButton::Left,
+        state: ButtonState::Pressed,
+        window,
+    });
+    app.update();
+}
@@ -0,0 +1,376 @@
+//! Bounding the memory taken by the textures managed by Egui.
+
+use crate::{
+    egui_node, EguiColorMode, EguiManagedTexture, EguiManagedTextureFreed, EguiManagedTextures,
+};
+use bevy::{
+    asset::{Assets, Handle},
+    ecs::{
+        component::Component,
+        entity::Entity,
+        event::{Event, EventWriter},
+    },
+    render::texture::{Image, ImageSampler},
+    utils::{HashMap, HashSet},
+};
+
+/// Bounds the memory taken by the managed textures of a context, insert it on the window entity.
+///
+/// The bytes of the managed textures of the context (4 per pixel, like in
+/// [`EguiTextureStats`](crate::EguiTextureStats)) are counted once its texture deltas are applied. While they exceed the
+/// budget, the textures other than the font atlas are evicted, least recently set or painted
+/// first: they're removed from [`EguiManagedTextures`] along with their images, and
+/// [`EguiManagedTextureEvicted`] is sent (as well as [`EguiManagedTextureFreed`]).
+///
+/// Egui doesn't know about it and still holds the [`egui::TextureHandle`]s, so the pixels of the
+/// evicted textures are kept by the budget (the images, which get uploaded to the GPU, are what's
+/// bounded): once a paint job references an evicted texture again, it's uploaded again and
+/// [`EguiManagedTextureUpdated`](crate::EguiManagedTextureUpdated) is sent as for a full update. Partial updates of evicted
+/// textures are applied to the kept pixels, and upload them too. The textures painted during the
+/// current pass are never evicted, so the budget is exceeded while they don't fit in it.
+///
+/// The font atlas can't be evicted, as every pass paints text with it. If it exceeds the budget on
+/// its own, the maximum texture side of the context is lowered until the budget is removed, so
+/// that the atlas fits (it's never lowered below [`EguiManagedTextureBudget::MIN_TEXTURE_SIDE`]):
+/// Egui rebuilds the atlas during the next pass and rasterizes the glyphs again, more often as the
+/// atlas fills up sooner. The lowered side also applies to the textures loaded with
+/// [`egui::Context::load_texture`].
+#[derive(Component, Clone, Debug)]
+pub struct EguiManagedTextureBudget {
+    /// The maximum number of bytes taken by the managed textures of the context.
+    pub bytes: usize,
+    max_texture_side: Option<usize>,
+    clock: u64,
+    last_set: HashMap<u64, u64>,
+    evicted: HashMap<u64, EvictedTexture>,
+}
+
+/// Pixels of a texture evicted by [`EguiManagedTextureBudget`], to upload it again.
+#[derive(Clone, Debug)]
+pub(crate) struct EvictedTexture {
+    pub(crate) color_image: egui::ColorImage,
+    pub(crate) sampler: ImageSampler,
+}
+
+impl EvictedTexture {
+    /// Uploads the texture again, returns the handle of its image.
+    pub(crate) fn upload(
+        self,
+        window_id: Entity,
+        texture_id: u64,
+        color_mode: EguiColorMode,
+        egui_managed_textures: &mut EguiManagedTextures,
+        image_assets: &mut Assets<Image>,
+    ) -> Handle<Image> {
+        let image =
+            egui_node::color_image_as_bevy_image(&self.color_image, self.sampler, color_mode);
+        let handle = image_assets.add(image);
+        egui_managed_textures.insert(
+            (window_id, texture_id),
+            EguiManagedTexture {
+                handle: handle.clone(),
+                color_image: self.color_image,
+            },
+        );
+        handle
+    }
+}
+
+impl EguiManagedTextureBudget {
+    /// The lowest maximum texture side the budget sets, Egui doesn't support narrower font
+    /// atlases.
+    pub const MIN_TEXTURE_SIDE: usize = 1024;
+
+    /// Creates a budget of the given number of bytes.
+    pub fn new(bytes: usize) -> Self {
+        Self {
+            bytes,
+            max_texture_side: None,
+            clock: 0,
+            last_set: HashMap::default(),
+            evicted: HashMap::default(),
+        }
+    }
+
+    /// The maximum texture side set for the context to fit the font atlas in the budget,
+    /// [`None`] if the atlas never exceeded it.
+    pub fn max_texture_side(&self) -> Option<usize> {
+        self.max_texture_side
+    }
+
+    pub(crate) fn touch(&mut self, texture_id: u64) {
+        self.clock += 1;
+        self.last_set.insert(texture_id, self.clock);
+        self.evicted.remove(&texture_id);
+    }
+
+    pub(crate) fn forget(&mut self, texture_id: u64) {
+        self.last_set.remove(&texture_id);
+        self.evicted.remove(&texture_id);
+    }
+
+    /// Takes the pixels of an evicted texture, to upload it again.
+    pub(crate) fn take_evicted(&mut self, texture_id: u64) -> Option<EvictedTexture> {
+        self.evicted.remove(&texture_id)
+    }
+
+    /// Evicts textures of the context, except for the painted ones, until their bytes fit in the
+    /// budget.
+    #[allow(clippy::too_many_arguments)]
+    pub(crate) fn enforce(
+        &mut self,
+        context: Entity,
+        painted: &HashSet<u64>,
+        egui_managed_textures: &mut EguiManagedTextures,
+        image_assets: &mut Assets<Image>,
+        evicted_events: &mut EventWriter<EguiManagedTextureEvicted>,
+        freed_events: &mut EventWriter<EguiManagedTextureFreed>,
+    ) {
+        let font_texture_id = match egui::TextureId::default() {
+            egui::TextureId::Managed(id) => id,
+            egui::TextureId::User(_) => unreachable!(),
+        };
+        let mut textures: Vec<_> = egui_managed_textures
+            .iter()
+            .filter(|((window_id, _), _)| *window_id == context)
+            .map(|(&(_, texture_id), texture)| {
+                let last_set = self.last_set.get(&texture_id).copied().unwrap_or(0);
+                (texture_id, last_set, texture.color_image.pixels.len() * 4)
+            })
+            .collect();
+        let mut total: usize = textures.iter().map(|(_, _, bytes)| bytes).sum();
+        if total <= self.bytes {
+            return;
+        }
+
+        textures.sort_unstable_by_key(|(_, last_set, _)| *last_set);
+        let mut font_texture_bytes = 0;
+        let mut painted_bytes = 0;
+        for (texture_id, _, bytes) in textures {
+            if texture_id == font_texture_id {
+                font_texture_bytes = bytes;
+                continue;
+            }
+            if total <= self.bytes {
+                break;
+            }
+            if painted.contains(&texture_id) {
+                painted_bytes += bytes;
+                continue;
+            }
+            let Some(managed_texture) = egui_managed_textures.remove(&(context, texture_id)) else {
+                continue;
+            };
+            let sampler = image_assets
+                .remove(managed_texture.handle)
+                .map(|image| image.sampler)
+                .unwrap_or_default();
+            self.last_set.remove(&texture_id);
+            self.evicted.insert(
+                texture_id,
+                EvictedTexture {
+                    color_image: managed_texture.color_image,
+                    sampler,
+                },
+            );
+            total -= bytes;
+            evicted_events.send(EguiManagedTextureEvicted {
+                context,
+                texture_id,
+                bytes,
+            });
+            freed_events.send(EguiManagedTextureFreed {
+                context,
+                texture_id,
+            });
+        }
+
+        // The painted textures are left out, the font atlas is only rebuilt when it doesn't fit
+        // alongside the others.
+        if total - painted_bytes > self.bytes && font_texture_bytes > 0 {
+            let max_texture_side =
+                (((self.bytes / 4) as f64).sqrt() as usize).max(Self::MIN_TEXTURE_SIDE);
+            if self.max_texture_side.unwrap_or(usize::MAX) > max_texture_side {
+                self.max_texture_side = Some(max_texture_side);
+                evicted_events.send(EguiManagedTextureEvicted {
+                    context,
+                    texture_id: font_texture_id,
+                    bytes: font_texture_bytes,
+                });
+            }
+        }
+    }
+}
+
+/// Is sent when a texture managed by Egui gets evicted to fit the
+/// [`EguiManagedTextureBudget`] of its context. Its pixels are kept, and it's uploaded again
+/// (with an [`EguiManagedTextureUpdated`](crate::EguiManagedTextureUpdated) event) once it gets painted.
+///
+/// For the font atlas ([`egui::TextureId::default`]), the texture isn't removed: the maximum
+/// texture side of the context is lowered, and Egui rebuilds the atlas during the next pass.
+#[derive(Event, Debug, Clone, Copy, PartialEq, Eq)]
+pub struct EguiManagedTextureEvicted {
+    /// Entity of the context (window) the texture belongs to.
+    pub context: Entity,
+    /// Id of the texture ([`egui::TextureId::Managed`]).
+    pub texture_id: u64,
+    /// Bytes taken by the texture (4 per pixel).
+    pub bytes: usize,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        test_utils::input_test_app, update_egui_textures_system, EguiManagedTextureUpdated,
+        EguiRenderOutput,
+    };
+    use bevy::{
+        app::App,
+        ecs::{event::Events, system::RunSystemOnce},
+    };
+
+    #[test]
+    fn test_managed_texture_budget() {
+        let (mut app, window) = input_test_app();
+        let context_bytes = |app: &App| {
+            app.world
+                .resource::<EguiManagedTextures>()
+                .iter()
+                .filter(|((context, _), _)| *context == window)
+                .map(|(_, texture)| texture.color_image.pixels.len() * 4)
+                .sum::<usize>()
+        };
+        let font_atlas_bytes = context_bytes(&app);
+        assert!(font_atlas_bytes > 0);
+        let budget = font_atlas_bytes + 3 * 16 * 16 * 4;
+        app.world
+            .entity_mut(window)
+            .insert(EguiManagedTextureBudget::new(budget));
+
+        let mut evicted_reader = app
+            .world
+            .resource::<Events<EguiManagedTextureEvicted>>()
+            .get_reader_current();
+        let mut updated_reader = app
+            .world
+            .resource::<Events<EguiManagedTextureUpdated>>()
+            .get_reader_current();
+        let set_texture = |app: &mut App, texture_id, pos: Option<[usize; 2]>, size| {
+            let image_delta = egui::epaint::ImageDelta {
+                image: egui::ColorImage::new(size, egui::Color32::RED).into(),
+                options: Default::default(),
+                pos,
+            };
+            app.world
+                .get_mut::<EguiRenderOutput>(window)
+                .unwrap()
+                .textures_delta
+                .set
+                .push((egui::TextureId::Managed(texture_id), image_delta));
+            app.world.run_system_once(update_egui_textures_system);
+        };
+        let mut evicted = |app: &App| {
+            let events = app.world.resource::<Events<EguiManagedTextureEvicted>>();
+            evicted_reader
+                .read(events)
+                .map(|event| event.texture_id)
+                .collect::<Vec<_>>()
+        };
+
+        // The least recently set textures are evicted.
+        for texture_id in 1..=5 {
+            set_texture(&mut app, texture_id, None, [16, 16]);
+            assert!(context_bytes(&app) <= budget);
+        }
+        assert_eq!(evicted(&app), [1, 2]);
+        set_texture(&mut app, 3, Some([0, 0]), [2, 2]);
+        set_texture(&mut app, 6, None, [16, 16]);
+        assert_eq!(evicted(&app), [4]);
+
+        // A partial update of an evicted texture uploads its kept pixels again.
+        updated_reader.clear(app.world.resource::<Events<EguiManagedTextureUpdated>>());
+        set_texture(&mut app, 1, Some([4, 4]), [2, 2]);
+        let events = app.world.resource::<Events<EguiManagedTextureUpdated>>();
+        let event = updated_reader.read(events).next().unwrap();
+        assert_eq!((event.pos, event.size), (None, [16, 16]));
+        assert_eq!(evicted(&app), [5]);
+        assert!(context_bytes(&app) <= budget);
+        let managed_textures = app.world.resource::<EguiManagedTextures>();
+        let color_image = &managed_textures[&(window, 1)].color_image;
+        assert_eq!(color_image[(5, 5)], egui::Color32::RED);
+        assert_eq!(color_image[(0, 0)], egui::Color32::RED);
+
+        // A painted evicted texture gets uploaded again, and isn't evicted while it's painted.
+        let paint = |app: &mut App, texture_ids: &[u64]| {
+            app.world
+                .get_mut::<EguiRenderOutput>(window)
+                .unwrap()
+                .paint_jobs = texture_ids
+                .iter()
+                .map(|&texture_id| egui::ClippedPrimitive {
+                    clip_rect: egui::Rect::EVERYTHING,
+                    primitive: egui::epaint::Primitive::Mesh(egui::Mesh::with_texture(
+                        egui::TextureId::Managed(texture_id),
+                    )),
+                })
+                .collect();
+            app.world.run_system_once(update_egui_textures_system);
+        };
+        assert!(!app
+            .world
+            .resource::<EguiManagedTextures>()
+            .contains_key(&(window, 2)));
+        paint(&mut app, &[2]);
+        let events = app.world.resource::<Events<EguiManagedTextureUpdated>>();
+        let event = updated_reader.read(events).next().unwrap();
+        assert_eq!(
+            (event.texture_id, event.pos, event.size),
+            (2, None, [16, 16])
+        );
+        assert_eq!(evicted(&app), [3]);
+        let managed_textures = app.world.resource::<EguiManagedTextures>();
+        assert_eq!(
+            managed_textures[&(window, 2)].color_image[(0, 0)],
+            egui::Color32::RED
+        );
+        set_texture(&mut app, 8, None, [16, 16]);
+        set_texture(&mut app, 9, None, [16, 16]);
+        assert_eq!(evicted(&app), [6, 1]);
+        assert!(app
+            .world
+            .resource::<EguiManagedTextures>()
+            .contains_key(&(window, 2)));
+
+        // The budget is exceeded while the painted textures don't fit in it.
+        paint(&mut app, &[1, 2, 3, 6]);
+        assert_eq!(evicted(&app), [8, 9]);
+        assert!(context_bytes(&app) > budget);
+        paint(&mut app, &[]);
+        assert!(context_bytes(&app) <= budget);
+        assert_eq!(evicted(&app), [1]);
+
+        // The font atlas gets rebuilt smaller when it exceeds the budget on its own.
+        app.world
+            .get_mut::<EguiManagedTextureBudget>(window)
+            .unwrap()
+            .bytes = 1024;
+        set_texture(&mut app, 7, None, [16, 16]);
+        assert_eq!(evicted(&app), [2, 3, 6, 7, 0]);
+        assert_eq!(
+            app.world
+                .get::<EguiManagedTextureBudget>(window)
+                .unwrap()
+                .max_texture_side(),
+            Some(EguiManagedTextureBudget::MIN_TEXTURE_SIDE)
+        );
+        app.update();
+        app.update();
+        let managed_textures = app.world.resource::<EguiManagedTextures>();
+        let font_atlas = &managed_textures[&(window, 0)].color_image;
+        assert_eq!(
+            font_atlas.width(),
+            EguiManagedTextureBudget::MIN_TEXTURE_SIDE
+        );
+    }
+}
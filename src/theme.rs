@@ -0,0 +1,391 @@
+//! Egui themes defined in RON files, which can be hot-reloaded.
+//!
+//! A theme file (`*.egui-theme.ron`) mirrors the commonly used parts of [`egui::Style`].
+//! All the fields are optional, the missing ones keep the values of the default dark or light
+//! visuals:
+//!
+//! ```ron
+//! (
+//!     dark_mode: true,
+//!     panel_fill: Some((30, 30, 46, 255)),
+//!     window_rounding: Some(8.0),
+//!     text_sizes: { "Body": 14.0, "Heading": 22.0 },
+//! )
+//! ```
+//!
+//! To apply a theme, insert the [`EguiThemeHandle`] component into a window entity.
+
+use crate::EguiContext;
+use bevy::{
+    asset::{
+        io::Reader, Asset, AssetEvent, AssetLoader, Assets, AsyncReadExt, Handle, LoadContext,
+    },
+    ecs::{
+        change_detection::DetectChanges,
+        event::EventReader,
+        system::{Query, Res},
+        world::Ref,
+    },
+    log,
+    prelude::Component,
+    reflect::TypePath,
+    utils::{BoxedFuture, HashMap, HashSet},
+};
+use serde::Deserialize;
+
+/// Egui style that can be loaded from a `*.egui-theme.ron` file.
+///
+/// Colors are sRGB RGBA tuples (unmultiplied alpha).
+#[derive(Asset, TypePath, Deserialize, Clone, Debug, PartialEq)]
+#[serde(default)]
+pub struct EguiTheme {
+    /// Whether to start from the default dark (`true` by default) or light visuals.
+    pub dark_mode: bool,
+    /// Overrides the color of all the text.
+    pub override_text_color: Option<(u8, u8, u8, u8)>,
+    /// Color of hyperlinks.
+    pub hyperlink_color: Option<(u8, u8, u8, u8)>,
+    /// Background color of selected text and widgets.
+    pub selection_color: Option<(u8, u8, u8, u8)>,
+    /// Background color of windows.
+    pub window_fill: Option<(u8, u8, u8, u8)>,
+    /// Background color of panels.
+    pub panel_fill: Option<(u8, u8, u8, u8)>,
+    /// Background color of text edits, scroll bars and other "extreme" backgrounds.
+    pub extreme_bg_color: Option<(u8, u8, u8, u8)>,
+    /// Background color of interactive widgets that aren't hovered or clicked.
+    pub widget_inactive_bg_fill: Option<(u8, u8, u8, u8)>,
+    /// Background color of hovered interactive widgets.
+    pub widget_hovered_bg_fill: Option<(u8, u8, u8, u8)>,
+    /// Background color of clicked interactive widgets.
+    pub widget_active_bg_fill: Option<(u8, u8, u8, u8)>,
+    /// Corner radius of windows.
+    pub window_rounding: Option<f32>,
+    /// Corner radius of widgets.
+    pub widget_rounding: Option<f32>,
+    /// Horizontal and vertical spacing between widgets.
+    pub item_spacing: Option<(f32, f32)>,
+    /// Horizontal and vertical padding within buttons.
+    pub button_padding: Option<(f32, f32)>,
+    /// Font sizes of text styles, keyed by the names of [`egui::TextStyle`] variants
+    /// (`Small`, `Body`, `Monospace`, `Button`, `Heading`, or a name of a custom style).
+    pub text_sizes: HashMap<String, f32>,
+}
+
+impl Default for EguiTheme {
+    fn default() -> Self {
+        Self {
+            dark_mode: true,
+            override_text_color: None,
+            hyperlink_color: None,
+            selection_color: None,
+            window_fill: None,
+            panel_fill: None,
+            extreme_bg_color: None,
+            widget_inactive_bg_fill: None,
+            widget_hovered_bg_fill: None,
+            widget_active_bg_fill: None,
+            window_rounding: None,
+            widget_rounding: None,
+            item_spacing: None,
+            button_padding: None,
+            text_sizes: HashMap::default(),
+        }
+    }
+}
+
+impl EguiTheme {
+    /// All the fields that can be set in a theme file.
+    const FIELDS: &'static [&'static str] = &[
+        "dark_mode",
+        "override_text_color",
+        "hyperlink_color",
+        "selection_color",
+        "window_fill",
+        "panel_fill",
+        "extreme_bg_color",
+        "widget_inactive_bg_fill",
+        "widget_hovered_bg_fill",
+        "widget_active_bg_fill",
+        "window_rounding",
+        "widget_rounding",
+        "item_spacing",
+        "button_padding",
+        "text_sizes",
+    ];
+
+    /// Parses a theme from a RON string.
+    ///
+    /// Returns the theme along with the names of the fields that aren't known and were ignored,
+    /// and the names of the fields that are missing and fell back to their defaults.
+    #[allow(clippy::type_complexity)]
+    pub fn from_ron(
+        ron_str: &str,
+    ) -> Result<(Self, Vec<String>, Vec<&'static str>), ron::error::SpannedError> {
+        let fields: Vec<String> = match ron::from_str::<ron::Value>(ron_str)? {
+            ron::Value::Map(map) => map
+                .keys()
+                .filter_map(|key| match key {
+                    ron::Value::String(field) => Some(field.clone()),
+                    _ => None,
+                })
+                .collect(),
+            _ => Vec::new(),
+        };
+        let theme = ron::from_str(ron_str)?;
+        let unknown_fields = fields
+            .iter()
+            .filter(|field| !Self::FIELDS.contains(&field.as_str()))
+            .cloned()
+            .collect();
+        let missing_fields = Self::FIELDS
+            .iter()
+            .copied()
+            .filter(|field| !fields.iter().any(|name| name == field))
+            .collect();
+        Ok((theme, unknown_fields, missing_fields))
+    }
+
+    /// Builds an Egui style with the theme applied to the default visuals.
+    #[must_use]
+    pub fn to_style(&self) -> egui::Style {
+        let color =
+            |(r, g, b, a): (u8, u8, u8, u8)| egui::Color32::from_rgba_unmultiplied(r, g, b, a);
+
+        let mut style = egui::Style {
+            visuals: if self.dark_mode {
+                egui::Visuals::dark()
+            } else {
+                egui::Visuals::light()
+            },
+            ..Default::default()
+        };
+        let visuals = &mut style.visuals;
+        visuals.override_text_color = self.override_text_color.map(color);
+        if let Some(hyperlink_color) = self.hyperlink_color {
+            visuals.hyperlink_color = color(hyperlink_color);
+        }
+        if let Some(selection_color) = self.selection_color {
+            visuals.selection.bg_fill = color(selection_color);
+        }
+        if let Some(window_fill) = self.window_fill {
+            visuals.window_fill = color(window_fill);
+        }
+        if let Some(panel_fill) = self.panel_fill {
+            visuals.panel_fill = color(panel_fill);
+        }
+        if let Some(extreme_bg_color) = self.extreme_bg_color {
+            visuals.extreme_bg_color = color(extreme_bg_color);
+        }
+        for (fill, widget_visuals) in [
+            (self.widget_inactive_bg_fill, &mut visuals.widgets.inactive),
+            (self.widget_hovered_bg_fill, &mut visuals.widgets.hovered),
+            (self.widget_active_bg_fill, &mut visuals.widgets.active),
+        ] {
+            if let Some(fill) = fill {
+                widget_visuals.bg_fill = color(fill);
+                widget_visuals.weak_bg_fill = color(fill);
+            }
+        }
+        if let Some(window_rounding) = self.window_rounding {
+            visuals.window_rounding = window_rounding.into();
+        }
+        if let Some(widget_rounding) = self.widget_rounding {
+            let widgets = &mut visuals.widgets;
+            for widget_visuals in [
+                &mut widgets.noninteractive,
+                &mut widgets.inactive,
+                &mut widgets.hovered,
+                &mut widgets.active,
+                &mut widgets.open,
+            ] {
+                widget_visuals.rounding = widget_rounding.into();
+            }
+        }
+        if let Some((x, y)) = self.item_spacing {
+            style.spacing.item_spacing = egui::vec2(x, y);
+        }
+        if let Some((x, y)) = self.button_padding {
+            style.spacing.button_padding = egui::vec2(x, y);
+        }
+        for (text_style, size) in &self.text_sizes {
+            let text_style = match text_style.as_str() {
+                "Small" => egui::TextStyle::Small,
+                "Body" => egui::TextStyle::Body,
+                "Monospace" => egui::TextStyle::Monospace,
+                "Button" => egui::TextStyle::Button,
+                "Heading" => egui::TextStyle::Heading,
+                name => egui::TextStyle::Name(name.into()),
+            };
+            if let Some(font_id) = style.text_styles.get_mut(&text_style) {
+                font_id.size = *size;
+            } else {
+                log::warn!("Unknown text style in an Egui theme: {text_style:?}");
+            }
+        }
+        style
+    }
+}
+
+/// Applies an [`EguiTheme`] to the Egui context of the entity.
+///
+/// The theme gets applied once the asset is loaded, and re-applied every time it's modified
+/// (hot-reloaded) or the handle changes.
+#[derive(Component, Clone, Debug, Default)]
+pub struct EguiThemeHandle(pub Handle<EguiTheme>);
+
+/// Loads [`EguiTheme`] assets from `*.egui-theme.ron` files.
+#[derive(Default)]
+pub struct EguiThemeLoader;
+
+/// An error that occurs when loading an [`EguiTheme`].
+#[derive(Debug)]
+pub enum EguiThemeLoaderError {
+    /// Failed to read the file.
+    Io(std::io::Error),
+    /// Failed to parse the file.
+    Ron(ron::error::SpannedError),
+}
+
+impl std::fmt::Display for EguiThemeLoaderError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Io(err) => write!(f, "failed to read an Egui theme: {err}"),
+            Self::Ron(err) => write!(f, "failed to parse an Egui theme: {err}"),
+        }
+    }
+}
+
+impl std::error::Error for EguiThemeLoaderError {}
+
+impl AssetLoader for EguiThemeLoader {
+    type Asset = EguiTheme;
+    type Settings = ();
+    type Error = EguiThemeLoaderError;
+
+    fn load<'a>(
+        &'a self,
+        reader: &'a mut Reader,
+        _settings: &'a Self::Settings,
+        load_context: &'a mut LoadContext,
+    ) -> BoxedFuture<'a, Result<Self::Asset, Self::Error>> {
+        Box::pin(async move {
+            let mut ron_str = String::new();
+            reader
+                .read_to_string(&mut ron_str)
+                .await
+                .map_err(EguiThemeLoaderError::Io)?;
+            let (theme, unknown_fields, missing_fields) =
+                EguiTheme::from_ron(&ron_str).map_err(EguiThemeLoaderError::Ron)?;
+            if !unknown_fields.is_empty() || !missing_fields.is_empty() {
+                log::warn!(
+                    "Unknown fields in the Egui theme ({}) are ignored: [{}], missing fields fall back to the defaults: [{}]",
+                    load_context.path().display(),
+                    unknown_fields.join(", "),
+                    missing_fields.join(", ")
+                );
+            }
+            Ok(theme)
+        })
+    }
+
+    fn extensions(&self) -> &[&str] {
+        &["egui-theme.ron"]
+    }
+}
+
+/// Applies loaded and modified themes to the contexts with [`EguiThemeHandle`].
+pub fn apply_egui_themes_system(
+    mut contexts: Query<(Ref<EguiThemeHandle>, &mut EguiContext)>,
+    themes: Res<Assets<EguiTheme>>,
+    mut theme_events: EventReader<AssetEvent<EguiTheme>>,
+) {
+    let updated_themes: HashSet<_> = theme_events
+        .read()
+        .filter_map(|event| match event {
+            AssetEvent::LoadedWithDependencies { id } | AssetEvent::Modified { id } => Some(*id),
+            _ => None,
+        })
+        .collect();
+
+    for (theme_handle, mut ctx) in contexts.iter_mut() {
+        if !theme_handle.is_changed() && !updated_themes.contains(&theme_handle.0.id()) {
+            continue;
+        }
+        if let Some(theme) = themes.get(&theme_handle.0) {
+            ctx.get_mut().set_style(theme.to_style());
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils::headless_app;
+    use bevy::{
+        app::App,
+        ecs::{entity::Entity, query::With},
+        window::PrimaryWindow,
+    };
+
+    #[test]
+    fn test_theme() {
+        let (theme, unknown_fields, missing_fields) = EguiTheme::from_ron(
+            r#"(
+                dark_mode: false,
+                panel_fill: Some((10, 20, 30, 255)),
+                text_sizes: { "Heading": 30.0 },
+                unknown_field: 1,
+            )"#,
+        )
+        .unwrap();
+        assert_eq!(unknown_fields, vec!["unknown_field".to_string()]);
+        assert_eq!(missing_fields.len(), EguiTheme::FIELDS.len() - 3);
+        assert!(missing_fields.contains(&"window_fill"));
+        assert!(!missing_fields.contains(&"panel_fill"));
+        assert!(!theme.dark_mode);
+        assert_eq!(theme.window_fill, None);
+
+        let mut app = headless_app();
+        app.update();
+        let window = app
+            .world
+            .query_filtered::<Entity, With<PrimaryWindow>>()
+            .single(&app.world);
+        let mut themes = app.world.resource_mut::<Assets<EguiTheme>>();
+        let handle = themes.add(theme);
+        app.world
+            .entity_mut(window)
+            .insert(EguiThemeHandle(handle.clone()));
+        app.update();
+
+        let style = |app: &mut App| {
+            app.world
+                .get_mut::<EguiContext>(window)
+                .unwrap()
+                .get_mut()
+                .style()
+        };
+        let applied_style = style(&mut app);
+        assert!(!applied_style.visuals.dark_mode);
+        assert_eq!(
+            applied_style.visuals.panel_fill,
+            egui::Color32::from_rgb(10, 20, 30)
+        );
+        assert_eq!(
+            applied_style.text_styles[&egui::TextStyle::Heading].size,
+            30.0
+        );
+
+        // Modifying the asset re-applies the theme, as it happens on hot-reload. Asset events are
+        // sent at the end of a frame, so the theme gets re-applied during the next one.
+        let mut themes = app.world.resource_mut::<Assets<EguiTheme>>();
+        themes.get_mut(&handle).unwrap().panel_fill = Some((40, 50, 60, 255));
+        app.update();
+        app.update();
+        assert_eq!(
+            style(&mut app).visuals.panel_fill,
+            egui::Color32::from_rgb(40, 50, 60)
+        );
+    }
+}
@@ -0,0 +1,349 @@
+//! Toast notifications ("Saved!", errors, etc) that any system can queue.
+
+use crate::{input::focus::PrimaryContextQuery, EguiContext, EguiSchedules, EguiSet};
+use bevy::{
+    app::{App, Plugin},
+    ecs::{
+        entity::Entity,
+        schedule::IntoSystemConfigs,
+        system::{Query, Res, ResMut, Resource},
+    },
+};
+
+/// Draws the toasts queued in [`EguiToasts`]. Requires [`EguiPlugin`](crate::EguiPlugin).
+///
+/// Toasts are stacked in a corner of their context (see [`EguiToastSettings`]), in a
+/// non-interactable [`egui::Area`], so they never capture the pointer. They fade in and out, and
+/// their timing follows the time of the context (see [`EguiTimeSource`](crate::EguiTimeSource)).
+/// The timer of a toast starts once it's shown, toasts over [`EguiToastSettings::max_visible`]
+/// wait in the queue.
+///
+/// ```no_run
+/// use bevy::prelude::*;
+/// use bevy_egui::{
+///     toast::{EguiToastPlugin, EguiToasts, Toast, ToastLevel},
+///     EguiPlugin,
+/// };
+///
+/// fn save_system(keys: Res<ButtonInput<KeyCode>>, mut toasts: ResMut<EguiToasts>) {
+///     if keys.just_pressed(KeyCode::F5) {
+///         toasts.push(Toast::new("Saved!"));
+///         toasts.push(Toast {
+///             level: ToastLevel::Warning,
+///             ..Toast::new("The disk is almost full")
+///         });
+///     }
+/// }
+///
+/// App::new()
+///     .add_plugins(DefaultPlugins)
+///     .add_plugins(EguiPlugin::default())
+///     .add_plugins(EguiToastPlugin)
+///     .add_systems(Update, save_system)
+///     .run();
+/// ```
+pub struct EguiToastPlugin;
+
+impl Plugin for EguiToastPlugin {
+    fn build(&self, app: &mut App) {
+        let schedule = EguiSchedules::from_app(app).process_output;
+        app.init_resource::<EguiToastSettings>()
+            .init_resource::<EguiToasts>()
+            .add_systems(schedule, toast_system.before(EguiSet::ProcessOutput));
+    }
+}
+
+/// How a [`Toast`] is colored.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub enum ToastLevel {
+    /// Uses the text color of the style.
+    #[default]
+    Info,
+    /// Green text.
+    Success,
+    /// Uses [`egui::Visuals::warn_fg_color`].
+    Warning,
+    /// Uses [`egui::Visuals::error_fg_color`].
+    Error,
+}
+
+/// A notification of the [`EguiToastPlugin`].
+#[derive(Clone, Debug, PartialEq)]
+pub struct Toast {
+    /// The message.
+    pub text: String,
+    /// How the toast is colored.
+    pub level: ToastLevel,
+    /// How long (in seconds) the toast is shown, fading included.
+    pub duration: f32,
+    /// The context (window entity) the toast is shown in, [`None`] for the primary context.
+    pub target: Option<Entity>,
+}
+
+impl Toast {
+    /// Creates an [`ToastLevel::Info`] toast shown for 3 seconds in the primary context.
+    pub fn new(text: impl Into<String>) -> Self {
+        Self {
+            text: text.into(),
+            level: ToastLevel::Info,
+            duration: 3.0,
+            target: None,
+        }
+    }
+}
+
+/// Identifies a queued [`Toast`], is returned by [`EguiToasts::push`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct ToastId(u64);
+
+/// Settings of the [`EguiToastPlugin`].
+#[derive(Resource, Clone, Copy, Debug, PartialEq)]
+pub struct EguiToastSettings {
+    /// The corner (or edge) the toasts are stacked in (the bottom right corner by default).
+    pub anchor: egui::Align2,
+    /// Distance between the toasts and the edges of the context, in Egui points.
+    pub margin: egui::Vec2,
+    /// How many toasts are shown at most per context, the other ones are collapsed into a
+    /// "+N more" line.
+    pub max_visible: usize,
+    /// How long (in seconds) fading in and fading out take.
+    pub fade_duration: f32,
+}
+
+impl Default for EguiToastSettings {
+    fn default() -> Self {
+        Self {
+            anchor: egui::Align2::RIGHT_BOTTOM,
+            margin: egui::vec2(8.0, 8.0),
+            max_visible: 5,
+            fade_duration: 0.2,
+        }
+    }
+}
+
+/// The queue of the [`EguiToastPlugin`].
+#[derive(Resource, Clone, Debug, Default)]
+pub struct EguiToasts {
+    toasts: Vec<QueuedToast>,
+    next_id: u64,
+}
+
+#[derive(Clone, Debug)]
+struct QueuedToast {
+    id: ToastId,
+    toast: Toast,
+    /// Time of the context when the toast got shown the first time.
+    shown_at: Option<f64>,
+}
+
+impl EguiToasts {
+    /// Queues a toast, it's shown after the toasts queued before it for the same context.
+    pub fn push(&mut self, toast: Toast) -> ToastId {
+        let id = ToastId(self.next_id);
+        self.next_id += 1;
+        self.toasts.push(QueuedToast {
+            id,
+            toast,
+            shown_at: None,
+        });
+        id
+    }
+
+    /// Removes a toast, returns `false` if it has expired (or has been dismissed) already.
+    pub fn dismiss(&mut self, id: ToastId) -> bool {
+        let len = self.toasts.len();
+        self.toasts.retain(|queued| queued.id != id);
+        self.toasts.len() != len
+    }
+
+    /// Removes all the toasts.
+    pub fn clear(&mut self) {
+        self.toasts.clear();
+    }
+
+    /// Iterates over the queued toasts, in the order they were pushed.
+    pub fn iter(&self) -> impl Iterator<Item = (ToastId, &Toast)> {
+        self.toasts.iter().map(|queued| (queued.id, &queued.toast))
+    }
+
+    /// Number of queued toasts, shown ones included.
+    pub fn len(&self) -> usize {
+        self.toasts.len()
+    }
+
+    /// Returns `true` if no toasts are queued.
+    pub fn is_empty(&self) -> bool {
+        self.toasts.is_empty()
+    }
+}
+
+/// Removes the expired toasts and draws the other ones, in the contexts that run a pass.
+pub fn toast_system(
+    mut toasts: ResMut<EguiToasts>,
+    settings: Res<EguiToastSettings>,
+    primary_contexts: PrimaryContextQuery,
+    mut contexts: Query<(Entity, &mut EguiContext)>,
+) {
+    if toasts.is_empty() {
+        return;
+    }
+    let primary_context = crate::input::focus::primary_context(&primary_contexts);
+    // Toasts of contexts that don't exist anymore would never expire.
+    toasts.toasts.retain(|queued| {
+        queued
+            .toast
+            .target
+            .or(primary_context)
+            .is_some_and(|target| contexts.contains(target))
+    });
+
+    for (entity, mut ctx) in contexts.iter_mut() {
+        if !ctx.is_pass_due() {
+            continue;
+        }
+        let ctx = ctx.get_mut();
+        let now = ctx.input(|input| input.time);
+        let targets_context =
+            |queued: &QueuedToast| queued.toast.target.or(primary_context) == Some(entity);
+        toasts.toasts.retain(|queued| match queued.shown_at {
+            Some(shown_at) if targets_context(queued) => {
+                now - shown_at < queued.toast.duration as f64
+            }
+            _ => true,
+        });
+
+        let mut shown = Vec::new();
+        let mut hidden_count = 0;
+        for queued in toasts
+            .toasts
+            .iter_mut()
+            .filter(|queued| targets_context(queued))
+        {
+            if shown.len() == settings.max_visible {
+                hidden_count += 1;
+                continue;
+            }
+            let shown_at = *queued.shown_at.get_or_insert(now);
+            let elapsed = (now - shown_at) as f32;
+            let fade = settings.fade_duration.max(f32::EPSILON);
+            let opacity = (elapsed / fade)
+                .min((queued.toast.duration - elapsed) / fade)
+                .clamp(0.0, 1.0);
+            shown.push((&queued.toast, opacity));
+        }
+        if shown.is_empty() {
+            continue;
+        }
+
+        let align = settings.anchor;
+        let offset = egui::vec2(
+            -align.x().to_sign() * settings.margin.x,
+            -align.y().to_sign() * settings.margin.y,
+        );
+        egui::Area::new(egui::Id::new("bevy_egui_toasts"))
+            .order(egui::Order::Foreground)
+            .interactable(false)
+            .anchor(align, offset)
+            .show(ctx, |ui| {
+                ui.with_layout(egui::Layout::top_down(align.x()), |ui| {
+                    for (toast, opacity) in shown {
+                        ui.scope(|ui| {
+                            ui.set_opacity(opacity);
+                            let visuals = ui.visuals();
+                            let color = match toast.level {
+                                ToastLevel::Info => visuals.text_color(),
+                                ToastLevel::Success => egui::Color32::from_rgb(90, 200, 90),
+                                ToastLevel::Warning => visuals.warn_fg_color,
+                                ToastLevel::Error => visuals.error_fg_color,
+                            };
+                            egui::Frame::popup(ui.style()).show(ui, |ui| {
+                                ui.label(egui::RichText::new(&toast.text).color(color));
+                            });
+                        });
+                    }
+                    if hidden_count > 0 {
+                        egui::Frame::popup(ui.style()).show(ui, |ui| {
+                            ui.label(format!("+{hidden_count} more"));
+                        });
+                    }
+                });
+            });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        test_utils::input_test_app, EguiCustomTime, EguiOcclusionMap, EguiTimeSource,
+        EguiWantsInput,
+    };
+    use bevy::{math::Vec2, window::CursorMoved};
+
+    #[test]
+    fn test_toasts() {
+        let (mut app, window) = input_test_app();
+        app.add_plugins(EguiToastPlugin)
+            .insert_resource(EguiToastSettings {
+                anchor: egui::Align2::LEFT_TOP,
+                max_visible: 2,
+                ..Default::default()
+            });
+        app.world.entity_mut(window).insert((
+            EguiTimeSource::Custom,
+            EguiCustomTime(0.0),
+            EguiOcclusionMap::default(),
+        ));
+        let mut toasts = app.world.resource_mut::<EguiToasts>();
+        toasts.push(Toast {
+            duration: 1.0,
+            ..Toast::new("First")
+        });
+        toasts.push(Toast {
+            duration: 2.0,
+            ..Toast::new("Second")
+        });
+        let third = toasts.push(Toast::new("Third"));
+        let texts = |app: &App| {
+            app.world
+                .resource::<EguiToasts>()
+                .iter()
+                .map(|(_, toast)| toast.text.clone())
+                .collect::<Vec<_>>()
+        };
+        let set_time = |app: &mut App, time| {
+            app.world.get_mut::<EguiCustomTime>(window).unwrap().0 = time;
+            app.update();
+        };
+
+        // The third toast waits until the first one expires, its timer starts then.
+        set_time(&mut app, 0.0);
+        set_time(&mut app, 0.5);
+        assert_eq!(texts(&app), ["First", "Second", "Third"]);
+        set_time(&mut app, 1.5);
+        assert_eq!(texts(&app), ["Second", "Third"]);
+        set_time(&mut app, 2.5);
+        assert_eq!(texts(&app), ["Third"]);
+        set_time(&mut app, 4.0);
+        assert_eq!(texts(&app), ["Third"]);
+
+        // Hovering a toast doesn't make Egui want the pointer.
+        app.world.send_event(CursorMoved {
+            window,
+            position: Vec2::new(20.0, 20.0),
+            delta: None,
+        });
+        set_time(&mut app, 4.1);
+        set_time(&mut app, 4.2);
+        let map = app.world.get::<EguiOcclusionMap>(window).unwrap();
+        assert!(map.is_point_covered(egui::pos2(20.0, 20.0)));
+        let egui_wants_input = *app.world.resource::<EguiWantsInput>();
+        assert!(!egui_wants_input.is_pointer_over_area);
+        assert!(!egui_wants_input.wants_pointer_input);
+
+        let mut toasts = app.world.resource_mut::<EguiToasts>();
+        assert!(toasts.dismiss(third));
+        assert!(!toasts.dismiss(third));
+        assert!(toasts.is_empty());
+    }
+}
@@ -0,0 +1,355 @@
+//! An on-screen keyboard drawn with Egui, for platforms without a system one.
+
+use crate::{
+    input::{EguiInputEvent, TextInputFocus, TextInputOwner},
+    EguiContext, EguiSchedules, EguiSet,
+};
+use bevy::{
+    app::{App, Plugin},
+    ecs::{
+        entity::Entity,
+        event::EventWriter,
+        schedule::IntoSystemConfigs,
+        system::{Query, Res, ResMut, Resource},
+    },
+    time::{Real, Time},
+};
+
+/// Shows an Egui-drawn keyboard while a text input of an Egui context has focus (consoles and
+/// kiosks often have no system soft keyboard). Requires [`EguiPlugin`](crate::EguiPlugin).
+///
+/// The keyboard is an [`egui::Window`] anchored to the bottom of the context that owns the
+/// [`TextInputFocus`]. Its keys send [`EguiInputEvent`]s to that context, and it hides once no
+/// text input has had focus for [`EguiVirtualKeyboardSettings::grace_period`]. The keys are
+/// regular Egui buttons, so they can be navigated like any other widget.
+///
+/// ```no_run
+/// use bevy::prelude::*;
+/// use bevy_egui::{
+///     virtual_keyboard::{EguiVirtualKeyboardPlugin, EguiVirtualKeyboardSettings, VirtualKeyboardLayout},
+///     EguiPlugin,
+/// };
+///
+/// App::new()
+///     .add_plugins(DefaultPlugins)
+///     .add_plugins(EguiPlugin::default())
+///     .add_plugins(EguiVirtualKeyboardPlugin)
+///     .insert_resource(EguiVirtualKeyboardSettings {
+///         layout: VirtualKeyboardLayout::azerty(),
+///         ..default()
+///     })
+///     .run();
+/// ```
+pub struct EguiVirtualKeyboardPlugin;
+
+impl Plugin for EguiVirtualKeyboardPlugin {
+    fn build(&self, app: &mut App) {
+        let schedule = EguiSchedules::from_app(app).process_output;
+        app.init_resource::<EguiVirtualKeyboardSettings>()
+            .init_resource::<EguiVirtualKeyboardState>()
+            .add_systems(
+                schedule,
+                virtual_keyboard_system.before(EguiSet::ProcessOutput),
+            );
+    }
+}
+
+/// A key of a [`VirtualKeyboardLayout`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum VirtualKey {
+    /// Types `normal`, or `shifted` while [`VirtualKey::Shift`] is active.
+    Char {
+        /// The character typed without shift.
+        normal: char,
+        /// The character typed with shift.
+        shifted: char,
+    },
+    /// Makes the next key type its shifted character.
+    Shift,
+    /// Erases the character before the cursor.
+    Backspace,
+    /// Presses [`egui::Key::Enter`].
+    Enter,
+    /// Types a space.
+    Space,
+}
+
+impl VirtualKey {
+    /// The text shown on the key.
+    pub fn label(&self, shift: bool) -> String {
+        match *self {
+            VirtualKey::Char { normal, shifted } => {
+                if shift { shifted } else { normal }.to_string()
+            }
+            VirtualKey::Shift => "Shift".to_owned(),
+            VirtualKey::Backspace => "Back".to_owned(),
+            VirtualKey::Enter => "Enter".to_owned(),
+            VirtualKey::Space => "Space".to_owned(),
+        }
+    }
+}
+
+/// Rows of keys of the [`EguiVirtualKeyboardPlugin`].
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct VirtualKeyboardLayout {
+    /// The rows from top to bottom.
+    pub rows: Vec<Vec<VirtualKey>>,
+}
+
+impl Default for VirtualKeyboardLayout {
+    fn default() -> Self {
+        Self::qwerty()
+    }
+}
+
+impl VirtualKeyboardLayout {
+    /// The US QWERTY layout.
+    pub fn qwerty() -> Self {
+        Self::from_rows(
+            &["1234567890", "qwertyuiop", "asdfghjkl", "zxcvbnm,."],
+            &["!@#$%^&*()", "QWERTYUIOP", "ASDFGHJKL", "ZXCVBNM?:"],
+        )
+    }
+
+    /// The French AZERTY layout.
+    pub fn azerty() -> Self {
+        Self::from_rows(
+            &["&é\"'(-è_çà", "azertyuiop", "qsdfghjklm", "wxcvbn,;:!"],
+            &["1234567890", "AZERTYUIOP", "QSDFGHJKLM", "WXCVBN?./§"],
+        )
+    }
+
+    /// Creates a layout from rows of characters and their shifted counterparts (characters
+    /// without a shifted counterpart are skipped). [`VirtualKey::Shift`] and
+    /// [`VirtualKey::Backspace`] surround the last row, followed by a row with
+    /// [`VirtualKey::Space`] and [`VirtualKey::Enter`].
+    pub fn from_rows(rows: &[&str], shifted_rows: &[&str]) -> Self {
+        let mut rows: Vec<Vec<VirtualKey>> = rows
+            .iter()
+            .zip(shifted_rows)
+            .map(|(row, shifted_row)| {
+                row.chars()
+                    .zip(shifted_row.chars())
+                    .map(|(normal, shifted)| VirtualKey::Char { normal, shifted })
+                    .collect()
+            })
+            .collect();
+        if let Some(last_row) = rows.last_mut() {
+            last_row.insert(0, VirtualKey::Shift);
+            last_row.push(VirtualKey::Backspace);
+        }
+        rows.push(vec![VirtualKey::Space, VirtualKey::Enter]);
+        Self { rows }
+    }
+}
+
+/// Settings of the [`EguiVirtualKeyboardPlugin`].
+#[derive(Resource, Clone, Debug, PartialEq)]
+pub struct EguiVirtualKeyboardSettings {
+    /// The keys.
+    pub layout: VirtualKeyboardLayout,
+    /// How long (in seconds) the keyboard stays shown after text inputs lose focus (`0.5` by
+    /// default). Tapping a key makes the text input lose focus until the key is released.
+    pub grace_period: f32,
+    /// The minimum size of a key in Egui points (the space bar is five keys wide).
+    pub key_size: egui::Vec2,
+}
+
+impl Default for EguiVirtualKeyboardSettings {
+    fn default() -> Self {
+        Self {
+            layout: VirtualKeyboardLayout::default(),
+            grace_period: 0.5,
+            key_size: egui::vec2(32.0, 32.0),
+        }
+    }
+}
+
+/// State of the [`EguiVirtualKeyboardPlugin`].
+#[derive(Resource, Clone, Copy, Debug, Default, PartialEq)]
+pub struct EguiVirtualKeyboardState {
+    /// The context the keyboard is shown in, [`None`] if it's hidden.
+    pub context: Option<Entity>,
+    /// Whether [`VirtualKey::Shift`] is active.
+    pub shift: bool,
+    /// The text input the keys type into, which gets focused again when a key is tapped.
+    text_input: Option<egui::Id>,
+    /// Time (elapsed since the startup, in seconds) when a text input had focus the last time.
+    last_focus_time: f64,
+}
+
+/// Shows or hides the virtual keyboard, and sends the events of the tapped keys.
+pub fn virtual_keyboard_system(
+    mut state: ResMut<EguiVirtualKeyboardState>,
+    settings: Res<EguiVirtualKeyboardSettings>,
+    text_input_focus: Res<TextInputFocus>,
+    time: Res<Time<Real>>,
+    mut contexts: Query<&mut EguiContext>,
+    mut input_events: EventWriter<EguiInputEvent>,
+) {
+    let now = time.elapsed_seconds_f64();
+    let focused_context = match text_input_focus.0 {
+        Some(TextInputOwner::Egui(context)) => Some(context),
+        _ => None,
+    };
+    if let Some(context) = focused_context {
+        if state.context != Some(context) {
+            *state = EguiVirtualKeyboardState {
+                context: Some(context),
+                ..Default::default()
+            };
+        }
+        state.last_focus_time = now;
+    } else if state.context.is_some() && now - state.last_focus_time > settings.grace_period as f64
+    {
+        *state = EguiVirtualKeyboardState::default();
+    }
+
+    let Some(context) = state.context else {
+        return;
+    };
+    let Ok(mut ctx) = contexts.get_mut(context) else {
+        *state = EguiVirtualKeyboardState::default();
+        return;
+    };
+    if !ctx.is_pass_due() {
+        return;
+    }
+    let ctx = ctx.get_mut();
+    if focused_context.is_some() {
+        if let Some(text_input) = ctx.memory(|memory| memory.focused()) {
+            state.text_input = Some(text_input);
+        }
+    }
+
+    let mut tapped_key = None;
+    egui::Window::new("Virtual keyboard")
+        .id(egui::Id::new("bevy_egui_virtual_keyboard"))
+        .title_bar(false)
+        .resizable(false)
+        .anchor(egui::Align2::CENTER_BOTTOM, egui::Vec2::ZERO)
+        .show(ctx, |ui| {
+            for row in &settings.layout.rows {
+                ui.horizontal(|ui| {
+                    for key in row {
+                        let mut size = settings.key_size;
+                        if *key == VirtualKey::Space {
+                            size.x *= 5.0;
+                        }
+                        let button = egui::Button::new(key.label(state.shift))
+                            .min_size(size)
+                            .selected(*key == VirtualKey::Shift && state.shift);
+                        if ui.add(button).clicked() {
+                            tapped_key = Some(*key);
+                        }
+                    }
+                });
+            }
+        });
+
+    let Some(key) = tapped_key else {
+        return;
+    };
+    // Pressing the key has taken the focus away from the text input.
+    if let Some(text_input) = state.text_input {
+        ctx.memory_mut(|memory| memory.request_focus(text_input));
+    }
+    let press = |key| {
+        [true, false].map(|pressed| egui::Event::Key {
+            key,
+            physical_key: None,
+            pressed,
+            repeat: false,
+            modifiers: egui::Modifiers::NONE,
+        })
+    };
+    let events = match key {
+        VirtualKey::Char { normal, shifted } => {
+            let char = if state.shift { shifted } else { normal };
+            state.shift = false;
+            vec![egui::Event::Text(char.to_string())]
+        }
+        VirtualKey::Shift => {
+            state.shift = !state.shift;
+            Vec::new()
+        }
+        VirtualKey::Backspace => press(egui::Key::Backspace).to_vec(),
+        VirtualKey::Enter => press(egui::Key::Enter).to_vec(),
+        VirtualKey::Space => vec![egui::Event::Text(" ".to_owned())],
+    };
+    input_events.send_batch(
+        events
+            .into_iter()
+            .map(|event| EguiInputEvent::new(context, event)),
+    );
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{test_utils::input_test_app, EguiContexts};
+    use bevy::{
+        app::Update,
+        input::{
+            mouse::{MouseButton, MouseButtonInput},
+            ButtonState,
+        },
+        math::Vec2,
+        window::CursorMoved,
+    };
+
+    #[test]
+    fn test_virtual_keyboard() {
+        #[derive(Resource, Default)]
+        struct Text(String);
+
+        fn click(app: &mut App, window: Entity, position: Vec2) {
+            app.world.send_event(CursorMoved {
+                window,
+                position,
+                delta: None,
+            });
+            for state in [ButtonState::Pressed, ButtonState::Released] {
+                app.world.send_event(MouseButtonInput {
+                    button: MouseButton::Left,
+                    state,
+                    window,
+                });
+                app.update();
+            }
+        }
+
+        let (mut app, window) = input_test_app();
+        app.add_plugins(EguiVirtualKeyboardPlugin)
+            .init_resource::<Text>()
+            .add_systems(
+                Update,
+                |mut contexts: EguiContexts, mut text: ResMut<Text>| {
+                    egui::CentralPanel::default().show(contexts.ctx_mut(), |ui| {
+                        ui.text_edit_singleline(&mut text.0);
+                    });
+                },
+            );
+        app.update();
+        let keyboard_state = |app: &App| *app.world.resource::<EguiVirtualKeyboardState>();
+        assert_eq!(keyboard_state(&app).context, None);
+
+        click(&mut app, window, Vec2::new(50.0, 18.0));
+        app.update();
+        assert_eq!(keyboard_state(&app).context, Some(window));
+
+        // The top left key types "1".
+        let ctx = app.world.get::<EguiContext>(window).unwrap().ctx.clone();
+        let keyboard_rect = ctx
+            .memory(|memory| memory.area_rect(egui::Id::new("bevy_egui_virtual_keyboard")))
+            .unwrap();
+        click(
+            &mut app,
+            window,
+            Vec2::new(keyboard_rect.min.x + 20.0, keyboard_rect.min.y + 20.0),
+        );
+        app.update();
+        assert_eq!(app.world.resource::<Text>().0, "1");
+        assert_eq!(keyboard_state(&app).context, Some(window));
+    }
+}
@@ -0,0 +1,153 @@
+//! Keeps [`EguiObservedCanvasSize`] up to date with `ResizeObserver`s.
+//!
+//! To check it manually, make the canvas fill a flexbox container (`fit_canvas_to_parent`), then
+//! open the developer tools docked to the side and zoom the page in and out: Egui windows should
+//! stay under the cursor, and panels should stick to the edges of the canvas, without resizing
+//! the browser window.
+
+use crate::{EguiContext, EguiObservedCanvasSize, EguiSettings, WindowSize};
+use bevy::{
+    ecs::system::NonSendMut,
+    log,
+    prelude::{Commands, Entity, Query, Res, With},
+    utils::HashMap,
+    window::Window,
+};
+use crossbeam_channel::{Receiver, Sender};
+use wasm_bindgen::{prelude::*, JsCast};
+
+/// The `ResizeObserver`s of the canvases.
+pub struct CanvasResizeObservers {
+    observers: HashMap<Entity, CanvasResizeObserver>,
+    sender: Sender<(Entity, WindowSize)>,
+    receiver: Receiver<(Entity, WindowSize)>,
+}
+
+impl Default for CanvasResizeObservers {
+    fn default() -> Self {
+        let (sender, receiver) = crossbeam_channel::unbounded();
+        Self {
+            observers: HashMap::default(),
+            sender,
+            receiver,
+        }
+    }
+}
+
+impl CanvasResizeObservers {
+    /// Disconnects all the observers, this can be useful for gracefully destroying a Bevy
+    /// instance in a page. Observers are installed again for the windows during the next frame,
+    /// unless [`EguiSettings::observe_canvas_resizes`] is disabled.
+    pub fn disconnect(&mut self) {
+        self.observers.clear();
+    }
+}
+
+struct CanvasResizeObserver {
+    observer: web_sys::ResizeObserver,
+    _closure: Closure<dyn FnMut(js_sys::Array, web_sys::ResizeObserver)>,
+}
+
+impl Drop for CanvasResizeObserver {
+    fn drop(&mut self) {
+        self.observer.disconnect();
+    }
+}
+
+/// Observes the canvases of new windows, and writes the observed sizes into
+/// [`EguiObservedCanvasSize`].
+pub fn read_canvas_resizes_system(
+    mut commands: Commands,
+    mut observers: NonSendMut<CanvasResizeObservers>,
+    egui_settings: Res<EguiSettings>,
+    mut windows: Query<(Entity, &Window, Option<&mut EguiObservedCanvasSize>), With<EguiContext>>,
+) {
+    let observers = &mut *observers;
+    if !egui_settings.observe_canvas_resizes {
+        if !observers.observers.is_empty() {
+            observers.disconnect();
+            for (entity, _, canvas_size) in windows.iter() {
+                if canvas_size.is_some() {
+                    commands.entity(entity).remove::<EguiObservedCanvasSize>();
+                }
+            }
+        }
+        return;
+    }
+
+    observers
+        .observers
+        .retain(|entity, _| windows.contains(*entity));
+    for (entity, window, _) in windows.iter() {
+        if observers.observers.contains_key(&entity) {
+            continue;
+        }
+        // Windows whose canvas can't be observed are retried during the next frames.
+        if let Some(observer) = observe_canvas(entity, window, observers.sender.clone()) {
+            observers.observers.insert(entity, observer);
+        }
+    }
+
+    for (entity, window_size) in observers.receiver.try_iter() {
+        match windows.get_mut(entity) {
+            Ok((_, _, Some(mut canvas_size))) => canvas_size.0 = window_size,
+            Ok((_, _, None)) => {
+                commands
+                    .entity(entity)
+                    .insert(EguiObservedCanvasSize(window_size));
+            }
+            Err(_) => {}
+        }
+    }
+}
+
+/// Returns [`None`] if the canvas doesn't exist (yet) or the browser doesn't support
+/// `ResizeObserver`.
+fn observe_canvas(
+    entity: Entity,
+    window: &Window,
+    sender: Sender<(Entity, WindowSize)>,
+) -> Option<CanvasResizeObserver> {
+    let web_window = web_sys::window()?;
+    if !js_sys::Reflect::has(&web_window, &JsValue::from_str("ResizeObserver")).unwrap_or(false) {
+        return None;
+    }
+    // Windows without a selector get the canvas created by `bevy_winit`, the first one.
+    let selector = window.canvas.as_deref().unwrap_or("canvas");
+    let canvas = web_window.document()?.query_selector(selector).ok()??;
+
+    let closure = Closure::<dyn FnMut(_, _)>::new(
+        move |entries: js_sys::Array, _observer: web_sys::ResizeObserver| {
+            let Some(entry) = entries
+                .iter()
+                .last()
+                .and_then(|entry| entry.dyn_into::<web_sys::ResizeObserverEntry>().ok())
+            else {
+                return;
+            };
+            // The device pixel ratio changes with the browser zoom.
+            let scale_factor = web_sys::window().map_or(1.0, |window| window.device_pixel_ratio());
+            let rect = entry.content_rect();
+            let window_size = WindowSize::new(
+                (rect.width() * scale_factor).round() as f32,
+                (rect.height() * scale_factor).round() as f32,
+                scale_factor as f32,
+            );
+            if sender.send((entity, window_size)).is_err() {
+                log::error!("Failed to send a canvas size: channel is disconnected");
+            }
+        },
+    );
+    let observer = match web_sys::ResizeObserver::new(closure.as_ref().unchecked_ref()) {
+        Ok(observer) => observer,
+        Err(err) => {
+            log::error!("Failed to create a ResizeObserver: {err:?}");
+            return None;
+        }
+    };
+    observer.observe(&canvas);
+    Some(CanvasResizeObserver {
+        observer,
+        _closure: closure,
+    })
+}
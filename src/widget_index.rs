@@ -0,0 +1,193 @@
+//! An index of the widgets of a context, for UI automation (bots clicking buttons in playtests,
+//! for example).
+//!
+//! Insert an [`EguiWidgetIndex`] into a window entity to opt in. The index is built from the
+//! AccessKit tree that Egui outputs, so only widgets that report their info to Egui (buttons,
+//! labels, checkboxes, text edits, etc) are listed. Rects are in Egui points, use
+//! [`helpers::egui_to_physical_rect`](crate::helpers::egui_to_physical_rect) to convert them
+//! into physical window pixels (and divide by [`Window::scale_factor`](bevy::window::Window::scale_factor)
+//! for the logical positions of [`CursorMoved`](bevy::window::CursorMoved) events).
+//!
+//! ```no_run
+//! use bevy::{prelude::*, window::PrimaryWindow};
+//! use bevy_egui::widget_index::EguiWidgetIndex;
+//!
+//! fn setup(mut commands: Commands, windows: Query<Entity, With<PrimaryWindow>>) {
+//!     commands.entity(windows.single()).insert(EguiWidgetIndex::default());
+//! }
+//!
+//! fn bot_system(widget_indices: Query<&EguiWidgetIndex, With<PrimaryWindow>>) {
+//!     if let Some(rect) = widget_indices.single().find_by_label("Start game") {
+//!         info!("The button is at {rect:?}");
+//!     }
+//! }
+//! ```
+
+use crate::{EguiContext, EguiPlatformOutput};
+use bevy::ecs::{component::Component, query::Added, system::Query};
+use egui::accesskit::{self, Role};
+
+/// Widgets of a context that were shown during its last pass, is updated during
+/// [`EguiSet::ProcessOutput`](crate::EguiSet::ProcessOutput).
+#[derive(Component, Clone, Debug, Default)]
+pub struct EguiWidgetIndex {
+    /// The widgets in the order Egui reported them.
+    pub entries: Vec<EguiWidgetEntry>,
+}
+
+impl EguiWidgetIndex {
+    /// Returns the rect of the first widget with the label.
+    pub fn find_by_label(&self, label: &str) -> Option<egui::Rect> {
+        self.entries
+            .iter()
+            .find(|entry| entry.label.as_deref() == Some(label))
+            .map(|entry| entry.rect)
+    }
+}
+
+/// A widget of an [`EguiWidgetIndex`].
+#[derive(Clone, Debug, PartialEq)]
+pub struct EguiWidgetEntry {
+    /// [`egui::Id::value`] of the widget.
+    pub id: u64,
+    /// Where the widget is, in Egui points.
+    pub rect: egui::Rect,
+    /// The text of the widget (the text of a button, for example).
+    pub label: Option<String>,
+    /// The kind of the widget. Image buttons and collapsing headers are reported as
+    /// [`egui::WidgetType::Button`].
+    pub kind: egui::WidgetType,
+}
+
+/// Makes the contexts with a new [`EguiWidgetIndex`] output their AccessKit tree.
+pub fn enable_widget_index_system(contexts: Query<&EguiContext, Added<EguiWidgetIndex>>) {
+    for context in contexts.iter() {
+        context.ctx.enable_accesskit();
+    }
+}
+
+/// Rebuilds the [`EguiWidgetIndex`] of the contexts that ran a pass.
+pub fn update_widget_index_system(
+    mut contexts: Query<(&EguiContext, &EguiPlatformOutput, &mut EguiWidgetIndex)>,
+) {
+    for (context, egui_output, mut widget_index) in contexts.iter_mut() {
+        if !context.is_pass_due() {
+            continue;
+        }
+        let Some(tree_update) = &egui_output.platform_output.accesskit_update else {
+            continue;
+        };
+        widget_index.entries = tree_update
+            .nodes
+            .iter()
+            .filter_map(|(node_id, node)| {
+                let kind = widget_type(node.role())?;
+                let accesskit::Rect { x0, y0, x1, y1 } = node.bounds()?;
+                Some(EguiWidgetEntry {
+                    id: node_id.0,
+                    rect: egui::Rect::from_min_max(
+                        egui::pos2(x0 as f32, y0 as f32),
+                        egui::pos2(x1 as f32, y1 as f32),
+                    ),
+                    label: node.name().map(str::to_owned),
+                    kind,
+                })
+            })
+            .collect();
+    }
+}
+
+/// Maps the roles Egui assigns to widgets back to their types, [`None`] for the root node.
+fn widget_type(role: Role) -> Option<egui::WidgetType> {
+    use egui::WidgetType;
+
+    Some(match role {
+        Role::Window => return None,
+        Role::StaticText => WidgetType::Label,
+        Role::Link => WidgetType::Link,
+        Role::TextInput | Role::MultilineTextInput => WidgetType::TextEdit,
+        Role::Button => WidgetType::Button,
+        Role::CheckBox => WidgetType::Checkbox,
+        Role::RadioButton => WidgetType::RadioButton,
+        Role::ToggleButton => WidgetType::SelectableLabel,
+        Role::ComboBox => WidgetType::ComboBox,
+        Role::Slider => WidgetType::Slider,
+        Role::SpinButton => WidgetType::DragValue,
+        Role::ColorWell => WidgetType::ColorButton,
+        Role::ProgressIndicator => WidgetType::ProgressIndicator,
+        _ => WidgetType::Other,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{helpers, test_utils::input_test_app, EguiComputedScale, EguiContexts};
+    use bevy::{
+        app::Update,
+        ecs::system::{ResMut, Resource},
+        input::{
+            mouse::{MouseButton, MouseButtonInput},
+            ButtonState,
+        },
+        window::{CursorMoved, Window},
+    };
+
+    #[test]
+    fn test_widget_index_click() {
+        #[derive(Resource, Default)]
+        struct Clicked(bool);
+
+        let (mut app, window) = input_test_app();
+        app.init_resource::<Clicked>().add_systems(
+            Update,
+            |mut contexts: EguiContexts, mut clicked: ResMut<Clicked>| {
+                egui::CentralPanel::default().show(contexts.ctx_mut(), |ui| {
+                    ui.label("Main menu");
+                    if ui.button("Start game").clicked() {
+                        clicked.0 = true;
+                    }
+                });
+            },
+        );
+        app.world
+            .get_mut::<Window>(window)
+            .unwrap()
+            .resolution
+            .set_scale_factor(2.0);
+        app.world
+            .entity_mut(window)
+            .insert(EguiWidgetIndex::default());
+        app.update();
+        app.update();
+
+        let widget_index = app.world.get::<EguiWidgetIndex>(window).unwrap();
+        let label = widget_index
+            .entries
+            .iter()
+            .find(|entry| entry.label.as_deref() == Some("Main menu"))
+            .unwrap();
+        assert_eq!(label.kind, egui::WidgetType::Label);
+        let rect = widget_index.find_by_label("Start game").unwrap();
+        assert!(rect.min.y > label.rect.min.y, "{rect:?}");
+
+        // Cursor events are in logical window pixels.
+        let computed_scale = *app.world.get::<EguiComputedScale>(window).unwrap();
+        let position = helpers::egui_to_physical_pos(rect.center(), &computed_scale)
+            / app.world.get::<Window>(window).unwrap().scale_factor();
+        app.world.send_event(CursorMoved {
+            window,
+            position,
+            delta: None,
+        });
+        for state in [ButtonState::Pressed, ButtonState::Released] {
+            app.world.send_event(MouseButtonInput {
+                button: MouseButton::Left,
+                state,
+                window,
+            });
+            app.update();
+        }
+        assert!(app.world.resource::<Clicked>().0);
+    }
+}
@@ -0,0 +1,94 @@
+//! Checks that packing the paint jobs reuses the buffers of the node between frames.
+//!
+//! It lives in its own test binary because it installs a counting global allocator.
+#![cfg(feature = "render")]
+
+use bevy::{
+    prelude::*,
+    render::{settings::WgpuSettings, RenderPlugin},
+    window::PrimaryWindow,
+    winit::WinitPlugin,
+};
+use bevy_egui::{
+    egui, egui_node::EguiNode, EguiContexts, EguiPlugin, EguiRenderOutput, WindowSize,
+};
+use std::{
+    alloc::{GlobalAlloc, Layout, System},
+    cell::Cell,
+};
+
+/// Counts allocations (and reallocations) made by the current thread.
+struct CountingAllocator;
+
+thread_local! {
+    static ALLOCATIONS: Cell<usize> = const { Cell::new(0) };
+}
+
+unsafe impl GlobalAlloc for CountingAllocator {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        let _ = ALLOCATIONS.try_with(|allocations| allocations.set(allocations.get() + 1));
+        System.alloc(layout)
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        System.dealloc(ptr, layout)
+    }
+
+    unsafe fn realloc(&self, ptr: *mut u8, layout: Layout, new_size: usize) -> *mut u8 {
+        let _ = ALLOCATIONS.try_with(|allocations| allocations.set(allocations.get() + 1));
+        System.realloc(ptr, layout, new_size)
+    }
+}
+
+#[global_allocator]
+static ALLOCATOR: CountingAllocator = CountingAllocator;
+
+#[test]
+fn test_paint_jobs_packing_reuses_buffers() {
+    let mut app = App::new();
+    app.add_plugins(
+        DefaultPlugins
+            .set(RenderPlugin {
+                render_creation: bevy::render::settings::RenderCreation::Automatic(WgpuSettings {
+                    backends: None,
+                    ..Default::default()
+                }),
+                ..Default::default()
+            })
+            .build()
+            .disable::<WinitPlugin>(),
+    )
+    .add_plugins(EguiPlugin::default())
+    .add_systems(Update, |mut contexts: EguiContexts| {
+        egui::Window::new("Hello").show(contexts.ctx_mut(), |ui| {
+            ui.label("world");
+            let _ = ui.button("Button");
+        });
+    });
+    app.update();
+    app.update();
+    app.update();
+    let window = app
+        .world
+        .query_filtered::<Entity, With<PrimaryWindow>>()
+        .single(&app.world);
+    let paint_jobs = app
+        .world
+        .get::<EguiRenderOutput>(window)
+        .unwrap()
+        .paint_jobs
+        .clone();
+    assert!(!paint_jobs.is_empty());
+    let window_size = *app.world.get::<WindowSize>(window).unwrap();
+
+    let mut node = EguiNode::new(window);
+    node.pack_paint_jobs(&paint_jobs, window_size, 1.0, u32::MAX);
+    let allocations = ALLOCATIONS.with(|allocations| allocations.get());
+    for _ in 0..10 {
+        node.pack_paint_jobs(&paint_jobs, window_size, 1.0, u32::MAX);
+    }
+    assert_eq!(
+        ALLOCATIONS.with(|allocations| allocations.get()),
+        allocations
+    );
+}